@@ -0,0 +1,116 @@
+use crate::{Builder, Error, StreamDecoder};
+use bytes::Bytes;
+use futures_core::Stream;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Asynchronous stream of the frames read from an [`AsyncRead`] such as a tokio `TcpStream`.
+///
+/// Bytes are read in chunks of up to `capacity` bytes,
+/// [`DEFAULT_BUF_CAPACITY`](crate::DEFAULT_BUF_CAPACITY) by default, and decoded by a
+/// [`StreamDecoder`], so a frame may span any number of reads. The reader is only polled
+/// while the stream is polled, which lets a slow consumer push back on the sender.
+///
+/// The stream ends at the end of the reader at a frame boundary, and yields an error of kind
+/// `UnexpectedEof` if the reader ends in the middle of a frame. A frame whose checksum does
+/// not match yields an error of kind `InvalidData` and the stream continues with the next
+/// frame; any other decoding error ends the stream.
+///
+/// ```
+/// use futures_util::StreamExt;
+/// use length_delimited_codec::{AsyncFramedReader, Encoder};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut bytes = vec![];
+/// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+///
+/// let mut frames = AsyncFramedReader::new(bytes.as_slice());
+/// assert_eq!(frames.next().await.unwrap().unwrap(), &b"hello"[..]);
+/// assert!(frames.next().await.is_none());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncFramedReader<R> {
+    decoder: StreamDecoder,
+    reader: R,
+    chunk: Vec<u8>,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncFramedReader<R> {
+    /// Creates an asynchronous framed reader with the default frame layout.
+    pub fn new(reader: R) -> Self {
+        Builder::new().new_async_framed_reader(reader)
+    }
+
+    pub(crate) fn from_decoder(decoder: StreamDecoder, capacity: usize, reader: R) -> Self {
+        Self {
+            decoder,
+            reader,
+            chunk: vec![0; capacity.max(1)],
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Number of oversized frames skipped so far with
+    /// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard).
+    pub fn num_discarded(&self) -> usize {
+        self.decoder.num_discarded()
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the underlying reader; bytes read but not yet yielded as frames are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncFramedReader<R> {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match this.decoder.next_frame() {
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(Bytes::from(frame)))),
+                Ok(None) => {}
+                Err(e @ Error::ChecksumMismatch { .. }) => return Poll::Ready(Some(Err(e.into()))),
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+            }
+
+            if this.eof {
+                this.done = true;
+                return Poll::Ready(this.decoder.finish().err().map(|e| Err(e.into())));
+            }
+
+            let mut buf = ReadBuf::new(&mut this.chunk);
+            ready!(Pin::new(&mut this.reader).poll_read(cx, &mut buf))?;
+            match buf.filled() {
+                [] => this.eof = true,
+                filled => this.decoder.feed(filled),
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{Builder, Encoder};
+use bytes::Bytes;
+use futures_sink::Sink;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use tokio::io::AsyncWrite;
+
+/// Asynchronous sink of frames written to an [`AsyncWrite`] such as a tokio `TcpStream`.
+///
+/// Frames are encoded into a buffer which is written out once it holds at least `capacity`
+/// bytes, or when the sink is flushed or closed. The sink is not ready to accept another
+/// frame until the full buffer is written, so a slow receiver pushes back on the sender
+/// rather than letting the buffer grow without bounds.
+///
+/// ```
+/// use futures_util::SinkExt;
+/// use length_delimited_codec::{AsyncFramedWriter, Decoder};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut sink = AsyncFramedWriter::new(vec![]);
+/// sink.send("hello".into()).await.unwrap();
+/// sink.send("world".into()).await.unwrap();
+///
+/// let frames = Decoder::new().decode_all(sink.get_ref()).unwrap();
+/// assert_eq!(frames, vec![b"hello".to_vec(), b"world".to_vec()]);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncFramedWriter<W> {
+    encoder: Encoder,
+    writer: W,
+    buffer: Vec<u8>,
+    /// Number of bytes of the buffer already written.
+    written: usize,
+    capacity: usize,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncFramedWriter<W> {
+    /// Creates an asynchronous framed writer with the default frame layout.
+    pub fn new(writer: W) -> Self {
+        Builder::new().new_async_framed_writer(writer)
+    }
+
+    pub(crate) fn from_encoder(encoder: Encoder, capacity: usize, writer: W) -> Self {
+        Self {
+            encoder,
+            writer,
+            buffer: Vec::with_capacity(capacity),
+            written: 0,
+            capacity,
+        }
+    }
+
+    /// Number of encoded bytes which are not yet written.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.written
+    }
+
+    /// Writes the buffered bytes to the writer, retrying short writes.
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.buffer.len() {
+            let unwritten = &self.buffer[self.written..];
+            match ready!(Pin::new(&mut self.writer).poll_write(cx, unwritten))? {
+                0 => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                n => self.written += n,
+            }
+        }
+        self.buffer.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the underlying writer; buffered frames which are not yet written are lost.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<Bytes> for AsyncFramedWriter<W> {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.buffered_len() < this.capacity {
+            true => Poll::Ready(Ok(())),
+            false => this.poll_write_buffer(cx),
+        }
+    }
+
+    /// Encodes the frame of the `payload` into the buffer.
+    ///
+    /// Nothing is buffered if the payload cannot be encoded, in which case an error of kind
+    /// `InvalidInput` is returned.
+    fn start_send(self: Pin<&mut Self>, payload: Bytes) -> io::Result<()> {
+        let this = self.get_mut();
+        this.encoder
+            .encode(&payload, &mut this.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.writer).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Pin::new(&mut this.writer).poll_shutdown(cx)
+    }
+}
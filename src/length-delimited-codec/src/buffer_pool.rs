@@ -0,0 +1,162 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Pool of reusable buffers holding owned frames, which avoids allocating a buffer for each
+/// frame read.
+///
+/// A [`PooledBuffer`] returns to its pool when it is dropped, keeping its capacity for the
+/// next frame. At most `max_idle` buffers are kept in the pool; the others are deallocated.
+/// Clones of a pool share its buffers, and buffers may be dropped on other threads than the
+/// one reading the frames.
+///
+/// ```
+/// use length_delimited_codec::{BufferPool, Encoder, FramedReader};
+///
+/// let mut bytes = vec![];
+/// for payload in [&b"hello"[..], b"pooled", b"world"] {
+///     Encoder::new().encode(payload, &mut bytes).unwrap();
+/// }
+///
+/// let pool = BufferPool::new(16);
+/// let mut reader = FramedReader::new(bytes.as_slice());
+/// while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+///     assert!(frame.len() >= 5);
+/// }
+/// // the buffer of each frame is reused by the next one
+/// assert_eq!(pool.num_allocated(), 1);
+/// assert_eq!(pool.num_idle(), 1);
+/// ```
+#[derive(Clone)]
+pub struct BufferPool {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    idle: Mutex<Vec<Vec<u8>>>,
+    max_idle: usize,
+    num_allocated: AtomicUsize,
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("num_idle", &self.num_idle())
+            .field("max_idle", &self.shared.max_idle)
+            .field("num_allocated", &self.num_allocated())
+            .finish()
+    }
+}
+
+impl BufferPool {
+    /// Creates an empty pool keeping at most `max_idle` buffers which are not in use.
+    pub fn new(max_idle: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                idle: Mutex::new(Vec::with_capacity(max_idle)),
+                max_idle,
+                num_allocated: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Takes an empty buffer from the pool, or creates one if the pool is empty.
+    pub fn get(&self) -> PooledBuffer {
+        let buffer = match self.lock_idle().pop() {
+            Some(buffer) => buffer,
+            None => {
+                self.shared.num_allocated.fetch_add(1, Ordering::Relaxed);
+                vec![]
+            }
+        };
+        PooledBuffer {
+            buffer: Some(buffer),
+            pool: self.clone(),
+        }
+    }
+
+    /// Number of buffers in the pool which are not in use.
+    pub fn num_idle(&self) -> usize {
+        self.lock_idle().len()
+    }
+
+    /// Number of buffers created by the pool since its creation.
+    pub fn num_allocated(&self) -> usize {
+        self.shared.num_allocated.load(Ordering::Relaxed)
+    }
+
+    fn lock_idle(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        // the buffers stay valid even if a thread panicked while holding the lock
+        self.shared
+            .idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn put(&self, mut buffer: Vec<u8>) {
+        let mut idle = self.lock_idle();
+        if idle.len() < self.shared.max_idle {
+            buffer.clear();
+            idle.push(buffer);
+        }
+    }
+}
+
+/// Buffer taken from a [`BufferPool`], which it returns to when dropped.
+///
+/// Dereferences to the underlying `Vec<u8>`.
+pub struct PooledBuffer {
+    /// None once the buffer is detached from the pool.
+    buffer: Option<Vec<u8>>,
+    pool: BufferPool,
+}
+
+impl PooledBuffer {
+    /// Detaches the buffer from its pool, so that it is not returned to the pool.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.buffer.take().unwrap_or_default()
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        self.buffer
+            .as_ref()
+            .expect("buffer is detached only when consumed")
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        self.buffer
+            .as_mut()
+            .expect("buffer is detached only when consumed")
+    }
+}
+
+impl AsRef<[u8]> for PooledBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for PooledBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledBuffer").field(&**self).finish()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.put(buffer);
+        }
+    }
+}
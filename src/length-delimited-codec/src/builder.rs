@@ -0,0 +1,450 @@
+#[cfg(feature = "async")]
+use crate::{AsyncFramedReader, AsyncFramedWriter};
+use crate::{
+    Checksum, ChecksumPosition, Decoder, Encoder, Endianness, FixedStreamDecoder,
+    MAX_SYNC_MARKER_LEN, OversizePolicy,
+    config::{Config, SyncMarker},
+};
+#[cfg(feature = "alloc")]
+use crate::{
+    Chunker, Compression, Demultiplexer, Multiplexer, Reassembler, Serializer, StreamDecoder,
+    TypedDecoder, TypedEncoder,
+};
+#[cfg(feature = "std")]
+use crate::{
+    DEFAULT_BUF_CAPACITY, FileReader, FileWriter, FramedBufReader, FramedReader, FramedWriter,
+};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Seek, Write};
+
+/// Configures the layout of length delimited frames and creates encoders and decoders for it.
+///
+/// A frame is laid out as follows:
+///
+/// ```text
+/// +---------------------+--------------+------------------------------+
+/// | header              | length field | body                         |
+/// | length_field_offset | 1/2/3/4/8    | length field value           |
+/// | bytes               | or varint    | + length_adjustment bytes    |
+/// +---------------------+--------------+------------------------------+
+/// ```
+///
+/// The decoder yields the frame without its first `num_skip` bytes, which by default strips
+/// the header and the length field. The encoder takes the header followed by the body and
+/// inserts the length field between them.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use length_delimited_codec::Builder;
+///
+/// // 2-byte little endian length field after a 1-byte message type,
+/// // where the length counts the entire frame.
+/// let builder = Builder::new()
+///     .length_field_offset(1)
+///     .length_field_length(2)
+///     .little_endian()
+///     .length_adjustment(-3)
+///     .num_skip(0);
+///
+/// let mut bytes = vec![];
+/// builder.new_encoder().encode(&[7, b'h', b'i'], &mut bytes).unwrap();
+/// assert_eq!(bytes, vec![7, 5, 0, b'h', b'i']);
+///
+/// let (frame, len) = builder.new_decoder().decode_frame(&bytes).unwrap();
+/// assert_eq!(frame, vec![7, 5, 0, b'h', b'i']);
+/// assert_eq!(len, 5);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    config: Config,
+}
+
+impl Builder {
+    /// Creates a builder with the default layout: a 4-byte big endian length field
+    /// holding the length of the payload.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of header bytes preceding the length field; 0 by default.
+    pub fn length_field_offset(mut self, offset: usize) -> Self {
+        self.config.length_field_offset = offset;
+        self
+    }
+
+    /// Sets the number of bytes of the length field; 4 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not one of 1, 2, 3, 4 or 8.
+    pub fn length_field_length(mut self, len: usize) -> Self {
+        assert!(
+            matches!(len, 1 | 2 | 3 | 4 | 8),
+            "length field length must be one of 1, 2, 3, 4 or 8, but is {len}"
+        );
+        self.config.length_field_len = len;
+        self
+    }
+
+    /// Sets the byte order of the length field; big endian by default.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+
+    /// Reads and writes the length field in big endian byte order.
+    pub fn big_endian(self) -> Self {
+        self.endianness(Endianness::Big)
+    }
+
+    /// Reads and writes the length field in little endian byte order.
+    pub fn little_endian(self) -> Self {
+        self.endianness(Endianness::Little)
+    }
+
+    /// Sets the value added to the length field to obtain the length of the body; 0 by default.
+    ///
+    /// For instance, when the length field counts the entire frame, the adjustment is the
+    /// negated sum of the header and length field lengths.
+    pub fn length_adjustment(mut self, adjustment: isize) -> Self {
+        self.config.length_adjustment = adjustment;
+        self
+    }
+
+    /// Sets the number of bytes skipped from the start of each decoded frame.
+    ///
+    /// By default, the header and the length field are skipped so that only the body is yielded.
+    pub fn num_skip(mut self, num_skip: usize) -> Self {
+        self.config.num_skip = Some(num_skip);
+        self
+    }
+
+    /// Uses an unsigned LEB128 varint length field, as protobuf-style streams do.
+    ///
+    /// The varint takes between 1 and 10 bytes depending on its value; the length field
+    /// length and endianness settings are ignored.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use length_delimited_codec::Builder;
+    ///
+    /// let builder = Builder::new().varint();
+    /// let mut bytes = vec![];
+    /// builder.new_encoder().encode(&[1; 300], &mut bytes).unwrap();
+    /// assert_eq!(&bytes[..2], &[0xAC, 0x02]);
+    /// assert_eq!(bytes.len(), 302);
+    /// # }
+    /// ```
+    pub fn varint(mut self) -> Self {
+        self.config.varint = true;
+        self
+    }
+
+    /// Sets the maximum length of the body of a frame; [`DEFAULT_MAX_FRAME_LENGTH`](crate::DEFAULT_MAX_FRAME_LENGTH) by default.
+    pub fn max_frame_length(mut self, max: usize) -> Self {
+        self.config.max_frame_length = max;
+        self
+    }
+
+    /// Sets the action taken by the decoders on frames exceeding the maximum frame length;
+    /// [`OversizePolicy::Error`] by default.
+    ///
+    /// The encoder always fails on payloads exceeding the maximum frame length.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use length_delimited_codec::{Builder, OversizePolicy};
+    ///
+    /// let builder = Builder::new().max_frame_length(4);
+    /// let bytes = [0, 0, 0, 6, 1, 2, 3, 4, 5, 6, 0, 0, 0, 2, 7, 8];
+    /// assert!(builder.new_decoder().decode_all(&bytes).is_err());
+    ///
+    /// let decoder = builder.oversize_policy(OversizePolicy::Discard).new_decoder();
+    /// assert_eq!(decoder.decode_all(&bytes).unwrap(), vec![vec![7, 8]]);
+    /// # }
+    /// ```
+    pub fn oversize_policy(mut self, policy: OversizePolicy) -> Self {
+        self.config.oversize_policy = policy;
+        self
+    }
+
+    /// Adds a checksum of the given algorithm to each frame, which is verified by the decoders.
+    ///
+    /// The checksum is part of the body; i.e., it is counted by the length field and by the
+    /// maximum frame length.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use length_delimited_codec::{Builder, Checksum, Error};
+    ///
+    /// let builder = Builder::new().checksum(Checksum::Crc32);
+    /// let mut bytes = vec![];
+    /// builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    /// assert_eq!(bytes.len(), 4 + 5 + 4);
+    /// assert_eq!(builder.new_decoder().decode_all(&bytes).unwrap(), vec![b"hello".to_vec()]);
+    ///
+    /// bytes[6] ^= 1;
+    /// assert!(matches!(
+    ///     builder.new_decoder().decode_all(&bytes),
+    ///     Err(Error::ChecksumMismatch { frame_index: 0, offset: 0, .. })
+    /// ));
+    /// # }
+    /// ```
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.config.checksum = Some(checksum);
+        self
+    }
+
+    /// Sets whether the checksum precedes or follows the body; [`ChecksumPosition::Append`] by default.
+    pub fn checksum_position(mut self, position: ChecksumPosition) -> Self {
+        self.config.checksum_position = position;
+        self
+    }
+
+    /// Compresses the body of each frame with the given `compression`; frames are not
+    /// compressed by default.
+    ///
+    /// Each body is prefixed by a flag telling whether it is compressed, and is stored as is
+    /// when compression does not shrink it; see the [`compression`](crate::compression) module.
+    /// The maximum frame length bounds both the stored and the decompressed body.
+    ///
+    /// The decoders returning owned or buffered frames decompress them, whereas
+    /// [`Decoder::decode_frame_ref`], [`Decoder::frames`], [`Decoder::index`] and
+    /// [`Decoder::resync_frames`], which borrow the frames from the buffer, yield the stored
+    /// bodies, which [`Decoder::decompress`] decompresses.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Builder, Compression};
+    ///
+    /// let builder = Builder::new().compression(Compression::Lz4);
+    /// let payload = b"abcabcabcabcabcabcabcabcabcabcabcabc".to_vec();
+    ///
+    /// let mut bytes = vec![];
+    /// builder.new_encoder().encode(&payload, &mut bytes).unwrap();
+    /// assert!(bytes.len() < payload.len());
+    ///
+    /// let frames = builder.new_decoder().decode_all(&bytes).unwrap();
+    /// assert_eq!(frames, vec![payload]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.config.compression = Some(compression);
+        self
+    }
+
+    /// Precedes each frame with the `marker`, so that decoding can resynchronize at the next
+    /// marker after a corruption; frames are not marked by default.
+    ///
+    /// The marker is never yielded by the decoders, which reject frames not starting with it
+    /// with [`Error::MissingSyncMarker`](crate::Error::MissingSyncMarker). The skipping decoders
+    /// are [`Decoder::resync_frames`] and [`StreamDecoder::next_resync`]. A longer marker is
+    /// less likely to appear by chance within the frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `marker` is empty or longer than [`MAX_SYNC_MARKER_LEN`] bytes.
+    pub fn sync_marker(mut self, marker: &[u8]) -> Self {
+        assert!(!marker.is_empty(), "sync marker must not be empty");
+        assert!(
+            marker.len() <= MAX_SYNC_MARKER_LEN,
+            "sync marker must be at most {MAX_SYNC_MARKER_LEN} bytes, but is {}",
+            marker.len()
+        );
+        self.config.sync_marker = SyncMarker::new(marker);
+        self
+    }
+
+    /// Creates an encoder for the configured frame layout.
+    pub fn new_encoder(&self) -> Encoder {
+        Encoder::from_config(self.config.clone())
+    }
+
+    /// Creates a decoder for the configured frame layout.
+    pub fn new_decoder(&self) -> Decoder {
+        Decoder::from_config(self.config.clone())
+    }
+
+    /// Creates a stream decoder for the configured frame layout.
+    #[cfg(feature = "alloc")]
+    pub fn new_stream_decoder(&self) -> StreamDecoder {
+        StreamDecoder::from_config(self.config.clone())
+    }
+
+    /// Creates a stream decoder for the configured frame layout which buffers the received
+    /// bytes in the given `buffer` rather than allocating.
+    pub fn new_fixed_stream_decoder<'a>(&self, buffer: &'a mut [u8]) -> FixedStreamDecoder<'a> {
+        FixedStreamDecoder::from_config(self.config.clone(), buffer)
+    }
+
+    /// Creates an encoder of messages of type `T` serialized by the `serializer`.
+    #[cfg(feature = "alloc")]
+    pub fn new_typed_encoder<T, S: Serializer<T>>(&self, serializer: S) -> TypedEncoder<T, S> {
+        TypedEncoder::from_encoder(self.new_encoder(), serializer)
+    }
+
+    /// Creates a decoder of messages of type `T` deserialized by the `serializer`.
+    #[cfg(feature = "alloc")]
+    pub fn new_typed_decoder<T, S: Serializer<T>>(&self, serializer: S) -> TypedDecoder<T, S> {
+        TypedDecoder::from_decoder(self.new_decoder(), serializer)
+    }
+
+    /// Creates a multiplexer splitting the messages of its channels into fragments of at most
+    /// `max_fragment_len` bytes, each encoded as a frame with the configured layout.
+    ///
+    /// Panics if `max_fragment_len` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn new_multiplexer(&self, max_fragment_len: usize) -> Multiplexer {
+        Multiplexer::from_encoder(self.new_encoder(), max_fragment_len)
+    }
+
+    /// Creates a demultiplexer of the fragment frames with the configured layout, rejecting
+    /// the messages longer than `max_message_len` bytes and the fragments of new channels
+    /// while `max_channels` channels have pending messages.
+    #[cfg(feature = "alloc")]
+    pub fn new_demultiplexer(&self, max_message_len: usize, max_channels: usize) -> Demultiplexer {
+        Demultiplexer::from_decoder(self.new_stream_decoder(), max_message_len, max_channels)
+    }
+
+    /// Creates a chunker splitting messages into chunks of at most `max_chunk_len` bytes, each
+    /// encoded as a frame with the configured layout.
+    ///
+    /// Panics if `max_chunk_len` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn new_chunker(&self, max_chunk_len: usize) -> Chunker {
+        Chunker::from_encoder(self.new_encoder(), max_chunk_len)
+    }
+
+    /// Creates a reassembler of the chunk frames with the configured layout, rejecting the
+    /// messages longer than `max_message_len` bytes or split into more than `max_chunks`
+    /// chunks.
+    #[cfg(feature = "alloc")]
+    pub fn new_reassembler(&self, max_message_len: usize, max_chunks: usize) -> Reassembler {
+        Reassembler::from_decoder(self.new_stream_decoder(), max_message_len, max_chunks)
+    }
+
+    /// Creates a reader of the frames with the configured layout from the `reader`.
+    #[cfg(feature = "std")]
+    pub fn new_framed_reader<R: Read>(&self, reader: R) -> FramedReader<R> {
+        FramedReader::from_config(self.config.clone(), reader)
+    }
+
+    /// Creates a buffered reader of the frames with the configured layout from the `reader`,
+    /// with a buffer of [`DEFAULT_BUF_CAPACITY`] bytes.
+    #[cfg(feature = "std")]
+    pub fn new_framed_buf_reader<R: Read>(&self, reader: R) -> FramedBufReader<R> {
+        self.new_framed_buf_reader_with_capacity(DEFAULT_BUF_CAPACITY, reader)
+    }
+
+    /// Creates a buffered reader of the frames with the configured layout from the `reader`,
+    /// with a buffer of `capacity` bytes.
+    #[cfg(feature = "std")]
+    pub fn new_framed_buf_reader_with_capacity<R: Read>(
+        &self,
+        capacity: usize,
+        reader: R,
+    ) -> FramedBufReader<R> {
+        FramedBufReader::from_config(self.config.clone(), capacity, reader)
+    }
+
+    /// Creates a writer of the frames with the configured layout to the `writer`.
+    #[cfg(feature = "std")]
+    pub fn new_framed_writer<W: Write>(&self, writer: W) -> FramedWriter<W> {
+        FramedWriter::from_encoder(self.new_encoder(), writer)
+    }
+
+    /// Creates a writer of a [file container](crate::file) of records with the configured
+    /// layout to the `writer`, and writes the file header.
+    #[cfg(feature = "std")]
+    pub fn new_file_writer<W: Write>(&self, writer: W) -> io::Result<FileWriter<W>> {
+        FileWriter::from_encoder(self.new_encoder(), writer)
+    }
+
+    /// Creates a reader of a [file container](crate::file) of records with the configured
+    /// layout from the `reader`, and reads its index footer.
+    ///
+    /// Fails with an error of kind `InvalidData` if the file header is invalid, or if the
+    /// index footer is missing or corrupt.
+    #[cfg(feature = "std")]
+    pub fn new_file_reader<R: Read + Seek>(&self, reader: R) -> io::Result<FileReader<R>> {
+        FileReader::from_config(self.config.clone(), reader)
+    }
+
+    /// Creates a reader of a [file container](crate::file) of records with the configured
+    /// layout from the `reader`, rebuilding the index by scanning the records.
+    ///
+    /// This recovers the records of a file whose index footer is missing or corrupt, for
+    /// instance, because writing it was interrupted. Scanning stops at the end of the file,
+    /// at the first record which is truncated or invalid, or at the start of an index footer.
+    ///
+    /// ```
+    /// use length_delimited_codec::Builder;
+    /// use std::io::Cursor;
+    ///
+    /// let builder = Builder::new();
+    /// let mut writer = builder.new_file_writer(vec![]).unwrap();
+    /// writer.write_record(b"first").unwrap();
+    /// writer.write_record(b"second").unwrap();
+    /// let mut bytes = writer.finish_without_index().unwrap();
+    /// bytes.truncate(bytes.len() - 1);
+    ///
+    /// assert!(builder.new_file_reader(Cursor::new(&bytes)).is_err());
+    ///
+    /// let mut reader = builder.recover_file_reader(Cursor::new(&bytes)).unwrap();
+    /// assert_eq!(reader.len(), 1);
+    /// assert_eq!(reader.read_record(0).unwrap(), b"first");
+    /// assert_eq!(reader.records_end(), 17);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recover_file_reader<R: Read + Seek>(&self, reader: R) -> io::Result<FileReader<R>> {
+        FileReader::recover_from_config(self.config.clone(), reader)
+    }
+
+    /// Creates an asynchronous stream of the frames with the configured layout read from the
+    /// `reader` in chunks of up to [`DEFAULT_BUF_CAPACITY`] bytes.
+    #[cfg(feature = "async")]
+    pub fn new_async_framed_reader<R>(&self, reader: R) -> AsyncFramedReader<R>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        self.new_async_framed_reader_with_capacity(DEFAULT_BUF_CAPACITY, reader)
+    }
+
+    /// Creates an asynchronous stream of the frames with the configured layout read from the
+    /// `reader` in chunks of up to `capacity` bytes.
+    #[cfg(feature = "async")]
+    pub fn new_async_framed_reader_with_capacity<R>(
+        &self,
+        capacity: usize,
+        reader: R,
+    ) -> AsyncFramedReader<R>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        AsyncFramedReader::from_decoder(self.new_stream_decoder(), capacity, reader)
+    }
+
+    /// Creates an asynchronous sink of the frames with the configured layout to the `writer`,
+    /// buffering up to [`DEFAULT_BUF_CAPACITY`] bytes before applying backpressure.
+    #[cfg(feature = "async")]
+    pub fn new_async_framed_writer<W>(&self, writer: W) -> AsyncFramedWriter<W>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        self.new_async_framed_writer_with_capacity(DEFAULT_BUF_CAPACITY, writer)
+    }
+
+    /// Creates an asynchronous sink of the frames with the configured layout to the `writer`,
+    /// buffering up to `capacity` bytes before applying backpressure.
+    #[cfg(feature = "async")]
+    pub fn new_async_framed_writer_with_capacity<W>(
+        &self,
+        capacity: usize,
+        writer: W,
+    ) -> AsyncFramedWriter<W>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        AsyncFramedWriter::from_encoder(self.new_encoder(), capacity, writer)
+    }
+}
@@ -0,0 +1,239 @@
+//! Checksums guarding the integrity of the frames.
+//!
+//! The checksum of a frame covers all of its bytes except for the checksum itself; i.e.,
+//! the header, the length field and the body. It is stored in the byte order of the length
+//! field, either right before or right after the body.
+
+/// Algorithm of the checksum added to each frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Checksum {
+    /// 4-byte CRC-32 (IEEE 802.3), as used by zip and ethernet.
+    Crc32,
+    /// 4-byte CRC-32C (Castagnoli), as used by iSCSI and ext4.
+    Crc32c,
+    /// 8-byte XXH64 hash with seed 0.
+    XxHash64,
+}
+
+impl Checksum {
+    /// Number of bytes of the checksum.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Crc32 | Self::Crc32c => 4,
+            Self::XxHash64 => 8,
+        }
+    }
+
+    /// Computes the checksum of the concatenation of the `parts`.
+    pub fn compute(&self, parts: &[&[u8]]) -> u64 {
+        match self {
+            Self::Crc32 => crc(&CRC32_TABLE, parts) as u64,
+            Self::Crc32c => crc(&CRC32C_TABLE, parts) as u64,
+            Self::XxHash64 => {
+                let mut hasher = XxHash64::new(0);
+                for part in parts {
+                    hasher.update(part);
+                }
+                hasher.finish()
+            }
+        }
+    }
+}
+
+/// Position of the checksum within the frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChecksumPosition {
+    /// Between the length field and the body.
+    Prepend,
+    /// After the body.
+    #[default]
+    Append,
+}
+
+/// CRC-32 (IEEE 802.3) of the `data`.
+///
+/// ```
+/// assert_eq!(length_delimited_codec::checksum::crc32(b"123456789"), 0xCBF43926);
+/// ```
+pub fn crc32(data: &[u8]) -> u32 {
+    crc(&CRC32_TABLE, &[data])
+}
+
+/// CRC-32C (Castagnoli) of the `data`.
+///
+/// ```
+/// assert_eq!(length_delimited_codec::checksum::crc32c(b"123456789"), 0xE3069283);
+/// ```
+pub fn crc32c(data: &[u8]) -> u32 {
+    crc(&CRC32C_TABLE, &[data])
+}
+
+/// XXH64 hash of the `data` with the given `seed`.
+///
+/// ```
+/// assert_eq!(length_delimited_codec::checksum::xxh64(b"", 0), 0xEF46DB3751D8E999);
+/// ```
+pub fn xxh64(data: &[u8], seed: u64) -> u64 {
+    let mut hasher = XxHash64::new(seed);
+    hasher.update(data);
+    hasher.finish()
+}
+
+// crc
+
+const CRC32_TABLE: [u32; 256] = crc_table(0xEDB8_8320);
+const CRC32C_TABLE: [u32; 256] = crc_table(0x82F6_3B78);
+
+/// Lookup table of the reflected CRC-32 with the given reversed `polynomial`.
+const fn crc_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ polynomial,
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+fn crc(table: &[u32; 256], parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for byte in parts.iter().flat_map(|x| x.iter()) {
+        crc = table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+// xxh64
+
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Streaming XXH64, consuming the input in 32-byte stripes.
+struct XxHash64 {
+    seed: u64,
+    accumulators: [u64; 4],
+    /// Bytes of the incomplete stripe.
+    buffer: [u8; 32],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl XxHash64 {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            accumulators: [
+                seed.wrapping_add(PRIME_1).wrapping_add(PRIME_2),
+                seed.wrapping_add(PRIME_2),
+                seed,
+                seed.wrapping_sub(PRIME_1),
+            ],
+            buffer: [0; 32],
+            buffer_len: 0,
+            total_len: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        if self.buffer_len > 0 {
+            let n = (32 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+            if self.buffer_len < 32 {
+                return;
+            }
+            let stripe = self.buffer;
+            self.consume(&stripe);
+            self.buffer_len = 0;
+        }
+
+        let mut stripes = data.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.consume(stripe);
+        }
+        let rest = stripes.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    fn consume(&mut self, stripe: &[u8]) {
+        for (acc, lane) in self.accumulators.iter_mut().zip(stripe.chunks_exact(8)) {
+            *acc = round(*acc, read_u64(lane));
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = match self.total_len >= 32 {
+            true => {
+                let [a, b, c, d] = self.accumulators;
+                let mut hash = a
+                    .rotate_left(1)
+                    .wrapping_add(b.rotate_left(7))
+                    .wrapping_add(c.rotate_left(12))
+                    .wrapping_add(d.rotate_left(18));
+                for acc in self.accumulators {
+                    hash = (hash ^ round(0, acc))
+                        .wrapping_mul(PRIME_1)
+                        .wrapping_add(PRIME_4);
+                }
+                hash
+            }
+            false => self.seed.wrapping_add(PRIME_5),
+        };
+        hash = hash.wrapping_add(self.total_len);
+
+        let mut rest = &self.buffer[..self.buffer_len];
+        while rest.len() >= 8 {
+            hash ^= round(0, read_u64(rest));
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(PRIME_1)
+                .wrapping_add(PRIME_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let lane = u32::from_le_bytes(rest[..4].try_into().expect("4 bytes")) as u64;
+            hash ^= lane.wrapping_mul(PRIME_1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(PRIME_2)
+                .wrapping_add(PRIME_3);
+            rest = &rest[4..];
+        }
+        for byte in rest {
+            hash ^= (*byte as u64).wrapping_mul(PRIME_5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME_1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+fn round(acc: u64, lane: u64) -> u64 {
+    acc.wrapping_add(lane.wrapping_mul(PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME_1)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().expect("8 bytes"))
+}
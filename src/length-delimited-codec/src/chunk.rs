@@ -0,0 +1,264 @@
+//! Chunking of messages larger than a frame into bounded frames, and their reassembly.
+//!
+//! Each message is split into chunks of at most `max_chunk_len` bytes, each chunk being the
+//! payload of a frame preceded by a chunk header. The first chunk declares the length of the
+//! whole message, so that messages exceeding the limits of the reassembler are rejected
+//! before their chunks are buffered:
+//!
+//! ```text
+//! first chunk:  +-------+-------------------+------------+
+//!               | flags | message length    | chunk data |
+//!               | 1     | varint, 1-10      | 0..=max    |
+//!               +-------+-------------------+------------+
+//! other chunks: +-------+------------+
+//!               | flags | chunk data |
+//!               | 1     | 0..=max    |
+//!               +-------+------------+
+//! ```
+//!
+//! The first chunk is flagged by [`FLAG_FIRST`] and the last one by [`FLAG_LAST`]; a
+//! message fitting in one chunk is flagged by both.
+
+use crate::{Encoder, Error, StreamDecoder, varint};
+use alloc::{vec, vec::Vec};
+
+/// Flag of the first chunk of a message.
+pub const FLAG_FIRST: u8 = 0b01;
+
+/// Flag of the last chunk of a message.
+pub const FLAG_LAST: u8 = 0b10;
+
+/// Splits messages into chunk frames of bounded length.
+///
+/// ```
+/// use length_delimited_codec::Builder;
+///
+/// let builder = Builder::new().max_frame_length(64);
+/// let message = vec![7; 1000];
+///
+/// let mut chunker = builder.new_chunker(60);
+/// let mut bytes = vec![];
+/// chunker.encode(&message, &mut bytes).unwrap();
+/// assert_eq!(chunker.num_chunks(message.len()), 17);
+///
+/// let mut reassembler = builder.new_reassembler(1 << 20, 100);
+/// reassembler.feed(&bytes);
+/// assert_eq!(reassembler.next_message(), Ok(Some(message)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Chunker {
+    encoder: Encoder,
+    max_chunk_len: usize,
+    /// Payload of the current chunk.
+    buffer: Vec<u8>,
+}
+
+impl Chunker {
+    pub(crate) fn from_encoder(encoder: Encoder, max_chunk_len: usize) -> Self {
+        assert!(max_chunk_len > 0, "chunks must carry at least a byte");
+        Self {
+            encoder,
+            max_chunk_len,
+            buffer: vec![],
+        }
+    }
+
+    /// Maximum number of bytes of a message carried by a chunk.
+    pub fn max_chunk_len(&self) -> usize {
+        self.max_chunk_len
+    }
+
+    /// Number of chunks of a message with the given length; an empty message is sent as a
+    /// single empty chunk.
+    pub fn num_chunks(&self, message_len: usize) -> usize {
+        message_len.div_ceil(self.max_chunk_len).max(1)
+    }
+
+    /// Appends the frames of the chunks of the `message` to `dst`.
+    ///
+    /// Nothing is appended to `dst` if a chunk cannot be encoded, for instance, because it
+    /// exceeds the maximum frame length.
+    pub fn encode(&mut self, message: &[u8], dst: &mut Vec<u8>) -> Result<(), Error> {
+        let num_chunks = self.num_chunks(message.len());
+        let dst_len = dst.len();
+        for i in 0..num_chunks {
+            let start = i * self.max_chunk_len;
+            let end = (start + self.max_chunk_len).min(message.len());
+
+            self.buffer.clear();
+            match i {
+                0 => {
+                    self.buffer.push(FLAG_FIRST | last_flag(num_chunks == 1));
+                    varint::encode(message.len() as u64, &mut self.buffer);
+                }
+                _ => self.buffer.push(last_flag(i + 1 == num_chunks)),
+            }
+            self.buffer.extend_from_slice(&message[start..end]);
+
+            if let Err(e) = self.encoder.encode(&self.buffer, dst) {
+                dst.truncate(dst_len);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn last_flag(is_last: bool) -> u8 {
+    match is_last {
+        true => FLAG_LAST,
+        false => 0,
+    }
+}
+
+/// Reassembles the messages of chunk frames arriving in arbitrary pieces.
+///
+/// Memory is bounded by the limits on the length of a message and the number of chunks
+/// in flight, i.e., buffered for the message being reassembled. A message whose declared
+/// length exceeds the maximum message length is rejected by its first chunk, and the bytes
+/// of a message are never buffered beyond its declared length.
+#[derive(Clone, Debug)]
+pub struct Reassembler {
+    decoder: StreamDecoder,
+    max_message_len: usize,
+    max_chunks: usize,
+    partial: Option<Partial>,
+    /// Whether the chunks of the current message are dropped after an error.
+    dropping: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Partial {
+    message: Vec<u8>,
+    declared_len: usize,
+    num_chunks: usize,
+}
+
+impl Reassembler {
+    pub(crate) fn from_decoder(
+        decoder: StreamDecoder,
+        max_message_len: usize,
+        max_chunks: usize,
+    ) -> Self {
+        Self {
+            decoder,
+            max_message_len,
+            max_chunks,
+            partial: None,
+            dropping: false,
+        }
+    }
+
+    /// Appends the `chunk` of received bytes.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.decoder.feed(chunk);
+    }
+
+    /// Number of bytes held for the message being reassembled and the incomplete frame.
+    pub fn buffered_len(&self) -> usize {
+        self.decoder.buffered_len() + self.partial.as_ref().map_or(0, |x| x.message.len())
+    }
+
+    /// Returns the next reassembled message, or None if more bytes are required.
+    ///
+    /// Returns an error if a frame or its chunk header is invalid, the chunks of a message do
+    /// not add up to its declared length, or the message exceeds the maximum message length
+    /// or number of chunks. The invalid chunk is consumed together with the partial message,
+    /// and the remaining chunks of a dropped message are skipped; so that reassembly can
+    /// continue with the next message.
+    pub fn next_message(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        while let Some(frame) = self.decoder.next_frame()? {
+            if let Some(message) = self.append(&frame)? {
+                return Ok(Some(message));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns an error if the stream cannot end here, since a frame or a message is
+    /// incomplete.
+    pub fn finish(&self) -> Result<(), Error> {
+        self.decoder.finish()?;
+        match &self.partial {
+            Some(partial) => Err(Error::TruncatedPayload {
+                expected: partial.declared_len,
+                available: partial.message.len(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts a message with the declared length `len`, unless it exceeds the limit.
+    fn start(&self, len: usize) -> Result<Partial, Error> {
+        match len > self.max_message_len {
+            true => Err(Error::MessageTooLarge {
+                len,
+                max: self.max_message_len,
+            }),
+            false => Ok(Partial {
+                message: vec![],
+                declared_len: len,
+                num_chunks: 0,
+            }),
+        }
+    }
+
+    /// Appends the `chunk` to the partial message; returns the message if it is complete.
+    fn append(&mut self, chunk: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let (&flags, mut data) = chunk.split_first().ok_or(Error::InvalidChunk)?;
+        let is_last = flags & FLAG_LAST != 0;
+        let result = match (flags & FLAG_FIRST != 0, self.partial.take()) {
+            _ if flags & !(FLAG_FIRST | FLAG_LAST) != 0 => Err(Error::InvalidChunk),
+            (true, None) => {
+                self.dropping = false;
+                match varint::decode(data) {
+                    Ok((len, n)) => {
+                        data = &data[n..];
+                        self.start(usize::try_from(len).unwrap_or(usize::MAX))
+                    }
+                    Err(_) => Err(Error::InvalidChunk),
+                }
+            }
+            (false, Some(partial)) => Ok(partial),
+            (false, None) if self.dropping => {
+                self.dropping = !is_last;
+                return Ok(None);
+            }
+            // a first chunk interrupting a partial message, or a chunk without a first one
+            _ => Err(Error::InvalidChunk),
+        };
+
+        let mut partial = match result {
+            Ok(partial) => partial,
+            Err(e) => {
+                self.dropping = !is_last;
+                return Err(e);
+            }
+        };
+
+        partial.num_chunks += 1;
+        let len = partial.message.len() + data.len();
+        let error = if partial.num_chunks > self.max_chunks {
+            Some(Error::TooManyChunks {
+                max: self.max_chunks,
+            })
+        } else if len > partial.declared_len || (is_last && len < partial.declared_len) {
+            Some(Error::InvalidChunk)
+        } else {
+            None
+        };
+        if let Some(e) = error {
+            self.dropping = !is_last;
+            return Err(e);
+        }
+
+        partial.message.extend_from_slice(data);
+        match is_last {
+            true => Ok(Some(partial.message)),
+            false => {
+                self.partial = Some(partial);
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use super::{layout::LayoutArgs, open_input, stdout};
+use std::{
+    io::{self, BufRead, Read},
+    num::NonZeroUsize,
+    path::PathBuf,
+};
+
+/// Wraps the records of the input into frames written to stdout.
+#[derive(clap::Args, Debug)]
+pub struct FrameArgs {
+    #[command(flatten)]
+    layout: LayoutArgs,
+
+    /// Splits the input into records of this many bytes, the last one possibly shorter,
+    /// rather than into lines, which are framed without their newline.
+    #[arg(long)]
+    record_size: Option<NonZeroUsize>,
+
+    /// File to read the records from; stdin if omitted.
+    input: Option<PathBuf>,
+}
+
+pub fn run(args: &FrameArgs) -> io::Result<()> {
+    let mut input = open_input(args.input.as_deref())?;
+    let mut writer = args.layout.builder().new_framed_writer(stdout());
+    let mut record = vec![];
+    for index in 0usize.. {
+        record.clear();
+        let len = match args.record_size {
+            Some(size) => input
+                .by_ref()
+                .take(size.get() as u64)
+                .read_to_end(&mut record)?,
+            None => {
+                let len = input.read_until(b'\n', &mut record)?;
+                if record.last() == Some(&b'\n') {
+                    record.pop();
+                }
+                len
+            }
+        };
+        if len == 0 {
+            break;
+        }
+        writer
+            .write_frame(&record)
+            .map_err(|e| io::Error::new(e.kind(), format!("record {index}: {e}")))?;
+    }
+    writer.flush()
+}
@@ -0,0 +1,100 @@
+use super::{layout::LayoutArgs, read_input, stdout};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Lists the frames of the input with their offsets and lengths, reporting the invalid ones.
+///
+/// Checksums are validated and compressed bodies decompressed; the command fails if any
+/// frame is invalid. Frames whose header is valid are stepped over even if their body is
+/// invalid. After an invalid header, inspection continues at the next sync marker, if the
+/// frames are marked.
+#[derive(clap::Args, Debug)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    layout: LayoutArgs,
+
+    /// Lists only the invalid frames.
+    #[arg(long)]
+    errors_only: bool,
+
+    /// File to read the frames from; stdin if omitted.
+    input: Option<PathBuf>,
+}
+
+pub fn run(args: &InspectArgs) -> io::Result<()> {
+    let bytes = read_input(args.input.as_deref())?;
+    let decoder = args.layout.builder().new_decoder();
+    let mut out = stdout();
+    writeln!(
+        out,
+        "{:>8} {:>12} {:>10} {:>10}  status",
+        "frame", "offset", "length", "payload"
+    )?;
+
+    let (mut num_frames, mut num_invalid) = (0, 0);
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let src = &bytes[offset..];
+        // skipped ranges have no frame index and no payload
+        let (index, len, status) = match decoder.frame_len(src) {
+            Ok(len) if len <= src.len() => {
+                num_frames += 1;
+                let status = decoder.decode_frame(&src[..len]).map(|(x, _)| x.len());
+                (Some(num_frames - 1), len, status)
+            }
+            result => {
+                // the frame cannot be delimited, and the next one is only found at a marker
+                let error = match result {
+                    Ok(_) => decoder.decode_frame(src).expect_err("frame is truncated"),
+                    Err(e) => e,
+                };
+                (
+                    None,
+                    skip_to_marker(args.layout.sync_marker(), src),
+                    Err(error),
+                )
+            }
+        };
+
+        let index = index.map_or("-".to_string(), |x| x.to_string());
+        match status {
+            Ok(_) if args.errors_only => {}
+            Ok(payload_len) => writeln!(
+                out,
+                "{index:>8} {offset:>12} {len:>10} {payload_len:>10}  ok"
+            )?,
+            Err(e) => {
+                num_invalid += 1;
+                writeln!(out, "{index:>8} {offset:>12} {len:>10} {:>10}  {e}", "-")?;
+            }
+        }
+        offset += len;
+    }
+
+    writeln!(
+        out,
+        "\nframes = {num_frames}, invalid = {num_invalid}, bytes = {}",
+        bytes.len()
+    )?;
+    out.flush()?;
+    match num_invalid {
+        0 => Ok(()),
+        n => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{n} invalid frames or skipped ranges"),
+        )),
+    }
+}
+
+/// Number of bytes from the start of `src` to the next sync marker, or to the end of `src`.
+fn skip_to_marker(marker: Option<&[u8]>, src: &[u8]) -> usize {
+    let position = marker.and_then(|marker| {
+        src[1..]
+            .windows(marker.len())
+            .position(|x| x == marker)
+            .map(|x| 1 + x)
+    });
+    position.unwrap_or(src.len())
+}
@@ -0,0 +1,165 @@
+use length_delimited_codec::{
+    Builder, Checksum, ChecksumPosition, Compression, DEFAULT_LENGTH_FIELD_LEN,
+    DEFAULT_MAX_FRAME_LENGTH,
+};
+use std::str::FromStr;
+
+/// Options of the frame layout, shared by all subcommands; see the methods of [`Builder`].
+#[derive(clap::Args, Clone, Debug)]
+pub struct LayoutArgs {
+    /// Number of bytes of the length field; one of 1, 2, 3, 4 or 8.
+    #[arg(long, default_value_t = DEFAULT_LENGTH_FIELD_LEN, value_parser = parse_length_field_length)]
+    length_field_length: usize,
+
+    /// Reads and writes the length field in little endian rather than big endian byte order.
+    #[arg(long)]
+    little_endian: bool,
+
+    /// Uses an unsigned LEB128 varint length field.
+    #[arg(long, conflicts_with_all = ["length_field_length", "little_endian"])]
+    varint: bool,
+
+    /// Number of header bytes preceding the length field.
+    #[arg(long, default_value_t = 0)]
+    length_field_offset: usize,
+
+    /// Value added to the length field to obtain the length of the body.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    length_adjustment: isize,
+
+    /// Number of bytes skipped from the start of each decoded frame; by default, only the
+    /// body is yielded.
+    #[arg(long)]
+    num_skip: Option<usize>,
+
+    /// Maximum length of the body of a frame.
+    #[arg(long, default_value_t = DEFAULT_MAX_FRAME_LENGTH)]
+    max_frame_length: usize,
+
+    /// Checksum of each frame.
+    #[arg(long, value_enum)]
+    checksum: Option<ChecksumArg>,
+
+    /// Position of the checksum in each frame.
+    #[arg(long, value_enum, default_value_t = ChecksumPositionArg::Append, requires = "checksum")]
+    checksum_position: ChecksumPositionArg,
+
+    /// Sync marker preceding each frame, as hex digits; e.g. `a55a`.
+    #[arg(long)]
+    sync_marker: Option<Hex>,
+
+    /// Compression of the body of each frame.
+    #[arg(long, value_enum)]
+    compression: Option<CompressionArg>,
+}
+
+impl LayoutArgs {
+    /// Builder of the configured frame layout.
+    pub fn builder(&self) -> Builder {
+        let mut builder = Builder::new()
+            .length_field_offset(self.length_field_offset)
+            .length_field_length(self.length_field_length)
+            .length_adjustment(self.length_adjustment)
+            .max_frame_length(self.max_frame_length)
+            .checksum_position(self.checksum_position.into());
+        if self.little_endian {
+            builder = builder.little_endian();
+        }
+        if self.varint {
+            builder = builder.varint();
+        }
+        if let Some(num_skip) = self.num_skip {
+            builder = builder.num_skip(num_skip);
+        }
+        if let Some(checksum) = self.checksum {
+            builder = builder.checksum(checksum.into());
+        }
+        if let Some(marker) = &self.sync_marker {
+            builder = builder.sync_marker(&marker.0);
+        }
+        if let Some(compression) = self.compression {
+            builder = builder.compression(compression.into());
+        }
+        builder
+    }
+
+    /// Sync marker preceding each frame, if any.
+    pub fn sync_marker(&self) -> Option<&[u8]> {
+        self.sync_marker.as_ref().map(|x| x.0.as_slice())
+    }
+}
+
+fn parse_length_field_length(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(len @ (1 | 2 | 3 | 4 | 8)) => Ok(len),
+        _ => Err(format!("must be one of 1, 2, 3, 4 or 8, but is {s}")),
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ChecksumArg {
+    Crc32,
+    Crc32c,
+    #[value(name = "xxhash64")]
+    XxHash64,
+}
+
+impl From<ChecksumArg> for Checksum {
+    fn from(value: ChecksumArg) -> Self {
+        match value {
+            ChecksumArg::Crc32 => Self::Crc32,
+            ChecksumArg::Crc32c => Self::Crc32c,
+            ChecksumArg::XxHash64 => Self::XxHash64,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ChecksumPositionArg {
+    Prepend,
+    Append,
+}
+
+impl From<ChecksumPositionArg> for ChecksumPosition {
+    fn from(value: ChecksumPositionArg) -> Self {
+        match value {
+            ChecksumPositionArg::Prepend => Self::Prepend,
+            ChecksumPositionArg::Append => Self::Append,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CompressionArg {
+    Lz4,
+}
+
+impl From<CompressionArg> for Compression {
+    fn from(value: CompressionArg) -> Self {
+        match value {
+            CompressionArg::Lz4 => Self::Lz4,
+        }
+    }
+}
+
+/// Non-empty bytes given as hex digits.
+#[derive(Clone, Debug)]
+struct Hex(Vec<u8>);
+
+impl FromStr for Hex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.len().is_multiple_of(2) || !s.is_ascii() {
+            return Err(format!(
+                "expected a non-empty even number of hex digits: {s}"
+            ));
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .collect::<Result<_, _>>()
+            .map(Self)
+            .map_err(|_| format!("invalid hex digits: {s}"))
+    }
+}
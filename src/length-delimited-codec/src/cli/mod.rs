@@ -0,0 +1,42 @@
+//! Subcommands framing, unframing and inspecting length delimited streams.
+
+pub mod frame;
+pub mod inspect;
+pub mod layout;
+pub mod stats;
+pub mod unframe;
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, StdoutLock},
+    path::Path,
+};
+
+/// Reader of the file at `path`, or of stdin if there is none.
+fn open_input(path: Option<&Path>) -> io::Result<Box<dyn BufRead>> {
+    Ok(match path {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(io::stdin().lock()),
+    })
+}
+
+/// All bytes of the file at `path`, or of stdin if there is none.
+fn read_input(path: Option<&Path>) -> io::Result<Vec<u8>> {
+    match path {
+        Some(path) => std::fs::read(path),
+        None => {
+            let mut bytes = vec![];
+            io::stdin().lock().read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+    }
+}
+
+fn stdout() -> BufWriter<StdoutLock<'static>> {
+    BufWriter::new(io::stdout().lock())
+}
+
+/// Error of the input data, prefixed by the `context` locating it.
+fn invalid_data(context: impl std::fmt::Display, error: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{context}: {error}"))
+}
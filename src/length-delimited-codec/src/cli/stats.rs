@@ -0,0 +1,75 @@
+use super::{invalid_data, layout::LayoutArgs, read_input, stdout};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Width of the bar of the most frequent frame lengths in the histogram.
+const MAX_BAR_LEN: usize = 50;
+
+/// Prints the number of frames of the input and the histogram of their lengths.
+///
+/// Lengths are counted in buckets of powers of two. The command fails at the first invalid
+/// frame, after printing the statistics of the frames preceding it.
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    layout: LayoutArgs,
+
+    /// File to read the frames from; stdin if omitted.
+    input: Option<PathBuf>,
+}
+
+pub fn run(args: &StatsArgs) -> io::Result<()> {
+    let bytes = read_input(args.input.as_deref())?;
+    let decoder = args.layout.builder().new_decoder();
+
+    // lengths of the whole frames, including their headers and checksums
+    let mut lengths = vec![];
+    let mut frames = decoder.frames(&bytes);
+    let mut start = 0;
+    let mut error = None;
+    while let Some(result) = frames.next() {
+        match result {
+            Ok(_) => {
+                lengths.push(frames.position() - start);
+                start = frames.position();
+            }
+            Err(e) => error = Some(invalid_data(format_args!("frame at offset {start}"), e)),
+        }
+    }
+
+    let mut out = stdout();
+    let total: usize = lengths.iter().sum();
+    writeln!(out, "frames = {}, bytes = {total}", lengths.len())?;
+    if let (Some(min), Some(max)) = (lengths.iter().min(), lengths.iter().max()) {
+        let mean = total as f64 / lengths.len() as f64;
+        writeln!(
+            out,
+            "frame length: min = {min}, max = {max}, mean = {mean:.1}"
+        )?;
+
+        let mut counts = vec![0usize; bucket(*max) + 1];
+        for len in &lengths {
+            counts[bucket(*len)] += 1;
+        }
+        let max_count = counts.iter().max().copied().unwrap_or(1);
+        writeln!(out, "\n{:>24} {:>10}", "frame length", "frames")?;
+        for (i, count) in counts.iter().enumerate().skip(bucket(*min)) {
+            let bar = "#".repeat((count * MAX_BAR_LEN).div_ceil(max_count));
+            let range = format!("[{}, {})", 1usize << i, 1u128 << (i + 1));
+            writeln!(out, "{range:>24} {count:>10}  {bar}")?;
+        }
+    }
+    out.flush()?;
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Index of the bucket of the frame length `len`, which is at least 1.
+fn bucket(len: usize) -> usize {
+    (usize::BITS - 1 - len.leading_zeros()) as usize
+}
@@ -0,0 +1,37 @@
+use super::{invalid_data, layout::LayoutArgs, open_input, stdout};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Writes the payloads of the frames of the input to stdout.
+#[derive(clap::Args, Debug)]
+pub struct UnframeArgs {
+    #[command(flatten)]
+    layout: LayoutArgs,
+
+    /// Writes a newline after each payload, which reverses the framing of lines.
+    #[arg(long)]
+    lines: bool,
+
+    /// File to read the frames from; stdin if omitted.
+    input: Option<PathBuf>,
+}
+
+pub fn run(args: &UnframeArgs) -> io::Result<()> {
+    let input = open_input(args.input.as_deref())?;
+    let mut reader = args.layout.builder().new_framed_buf_reader(input);
+    let mut out = stdout();
+    for index in 0usize.. {
+        let frame = match reader.read_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break,
+            Err(e) => return Err(invalid_data(format_args!("frame {index}"), e)),
+        };
+        out.write_all(frame)?;
+        if args.lines {
+            out.write_all(b"\n")?;
+        }
+    }
+    out.flush()
+}
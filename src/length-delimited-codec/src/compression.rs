@@ -0,0 +1,247 @@
+//! Compression of the bodies of the frames.
+//!
+//! With compression enabled, the body of each frame starts with a flag byte telling whether
+//! the rest of the body is stored as is, [`FLAG_RAW`], or compressed, [`FLAG_LZ4`]. A
+//! compressed body is followed by the varint length of the decompressed body and the LZ4
+//! block. Bodies which do not shrink by compression are stored as is, so that compression
+//! never costs more than the flag byte.
+//!
+//! The header bytes preceding the length field, the length field and the checksum are never
+//! compressed; the checksum covers the stored, possibly compressed, bytes.
+
+use crate::{Error, varint};
+use alloc::vec::Vec;
+
+/// Algorithm compressing the bodies of the frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// LZ4 block format, favoring speed over ratio.
+    Lz4,
+}
+
+/// Flag of a body stored as is.
+pub const FLAG_RAW: u8 = 0;
+
+/// Flag of a body compressed in the LZ4 block format.
+pub const FLAG_LZ4: u8 = 1;
+
+impl Compression {
+    /// Appends the flagged, and compressed if it shrinks, `body` to `dst`.
+    pub(crate) fn pack(&self, body: &[u8], dst: &mut Vec<u8>) {
+        let start = dst.len();
+        match self {
+            Self::Lz4 => {
+                dst.push(FLAG_LZ4);
+                varint::encode(body.len() as u64, dst);
+                lz4_compress(body, dst);
+            }
+        }
+
+        if dst.len() - start > body.len() {
+            dst.truncate(start);
+            dst.push(FLAG_RAW);
+            dst.extend_from_slice(body);
+        }
+    }
+
+    /// Appends the body of the flagged `src` to `dst`, rejecting bodies decompressing to
+    /// more than `max_len` bytes.
+    pub(crate) fn unpack(
+        &self,
+        src: &[u8],
+        max_len: usize,
+        dst: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        match src.split_first() {
+            Some((&FLAG_RAW, body)) => {
+                dst.extend_from_slice(body);
+                Ok(())
+            }
+            Some((&FLAG_LZ4, rest)) => {
+                let (len, n) = varint::decode(rest).map_err(|_| Error::InvalidCompression)?;
+                let len = usize::try_from(len).unwrap_or(usize::MAX);
+                if len > max_len {
+                    return Err(Error::FrameTooLarge { len, max: max_len });
+                }
+                lz4_decompress(&rest[n..], len, dst)
+            }
+            _ => Err(Error::InvalidCompression),
+        }
+    }
+}
+
+// lz4
+
+const MIN_MATCH: usize = 4;
+/// The last match starts at least this many bytes before the end of the block.
+const MF_LIMIT: usize = 12;
+/// The block ends with at least this many literals.
+const LAST_LITERALS: usize = 5;
+/// Inputs up to this length use a smaller hash table, which is cheaper to reset.
+const SMALL_INPUT_LEN: usize = 1024;
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+/// Appends the LZ4 block of `src` to `dst`.
+///
+/// ```
+/// use length_delimited_codec::compression::{lz4_compress, lz4_decompress};
+///
+/// let text = b"to be or not to be, that is the question; to be or not to be".repeat(4);
+/// let mut compressed = vec![];
+/// lz4_compress(&text, &mut compressed);
+/// assert!(compressed.len() < text.len() / 2);
+///
+/// let mut decompressed = vec![];
+/// lz4_decompress(&compressed, text.len(), &mut decompressed).unwrap();
+/// assert_eq!(decompressed, text);
+/// ```
+pub fn lz4_compress(src: &[u8], dst: &mut Vec<u8>) {
+    match src.len() <= SMALL_INPUT_LEN {
+        true => compress(src, &mut [0; 1 << 8], dst),
+        false => compress(src, &mut [0; 1 << 12], dst),
+    }
+}
+
+/// Compresses `src` with the `table` of the last positions of the hashes of 4-byte sequences,
+/// whose length is a power of two.
+fn compress(src: &[u8], table: &mut [u32], dst: &mut Vec<u8>) {
+    let hash_log = table.len().trailing_zeros();
+    let mut anchor = 0;
+
+    if src.len() > MF_LIMIT {
+        let match_limit = src.len() - MF_LIMIT;
+        let match_end_limit = src.len() - LAST_LITERALS;
+        let mut i = 0;
+        while i < match_limit {
+            let sequence = read_u32(src, i);
+            let hash = hash(sequence, hash_log);
+            let candidate = table[hash] as usize;
+            table[hash] = i as u32;
+
+            let is_match = candidate < i
+                && i - candidate <= MAX_OFFSET
+                && read_u32(src, candidate) == sequence;
+            if !is_match {
+                // skips faster through incompressible bytes
+                i += 1 + ((i - anchor) >> 6);
+                continue;
+            }
+
+            let mut len = MIN_MATCH;
+            while i + len < match_end_limit && src[candidate + len] == src[i + len] {
+                len += 1;
+            }
+            write_sequence(&src[anchor..i], Some(((i - candidate) as u16, len)), dst);
+            i += len;
+            anchor = i;
+        }
+    }
+
+    write_sequence(&src[anchor..], None, dst);
+}
+
+/// Appends the `len` bytes decompressed from the LZ4 block `src` to `dst`.
+///
+/// Returns [`Error::InvalidCompression`] if the block is malformed or does not decompress
+/// to exactly `len` bytes.
+pub fn lz4_decompress(src: &[u8], len: usize, dst: &mut Vec<u8>) -> Result<(), Error> {
+    let start = dst.len();
+    let end = start.checked_add(len).ok_or(Error::InvalidCompression)?;
+    // a block expands at most 255 times, which bounds the allocation of a malformed length
+    dst.reserve(len.min(src.len().saturating_mul(255)));
+
+    let mut i = 0;
+    loop {
+        let token = *src.get(i).ok_or(Error::InvalidCompression)?;
+        i += 1;
+
+        let num_literals = read_len(token >> 4, src, &mut i)?;
+        let literals = i
+            .checked_add(num_literals)
+            .and_then(|x| src.get(i..x))
+            .ok_or(Error::InvalidCompression)?;
+        if dst.len() + literals.len() > end {
+            return Err(Error::InvalidCompression);
+        }
+        dst.extend_from_slice(literals);
+        i += num_literals;
+
+        if i == src.len() {
+            break;
+        }
+
+        let offset = src.get(i..i + 2).ok_or(Error::InvalidCompression)?;
+        let offset = u16::from_le_bytes([offset[0], offset[1]]) as usize;
+        i += 2;
+        let match_len = read_len(token & 0x0F, src, &mut i)? + MIN_MATCH;
+        if offset == 0 || offset > dst.len() - start || match_len > end - dst.len() {
+            return Err(Error::InvalidCompression);
+        }
+
+        let match_start = dst.len() - offset;
+        match offset >= match_len {
+            true => dst.extend_from_within(match_start..match_start + match_len),
+            // the match overlaps the bytes it produces, repeating the last `offset` bytes
+            false => {
+                for x in match_start..match_start + match_len {
+                    dst.push(dst[x]);
+                }
+            }
+        }
+    }
+
+    match dst.len() == end {
+        true => Ok(()),
+        false => Err(Error::InvalidCompression),
+    }
+}
+
+fn hash(sequence: u32, hash_log: u32) -> usize {
+    (sequence.wrapping_mul(2_654_435_761) >> (32 - hash_log)) as usize
+}
+
+fn read_u32(src: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(src[i..i + 4].try_into().expect("4 bytes"))
+}
+
+/// Appends the sequence of the `literals` followed by the match of `(offset, len)`, if any.
+fn write_sequence(literals: &[u8], matched: Option<(u16, usize)>, dst: &mut Vec<u8>) {
+    let match_len = matched.map(|(_, len)| len - MIN_MATCH).unwrap_or(0);
+    dst.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    write_len(literals.len(), dst);
+    dst.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        dst.extend_from_slice(&offset.to_le_bytes());
+        write_len(match_len, dst);
+    }
+}
+
+/// Appends the bytes extending a length of at least 15 stored in a token nibble.
+fn write_len(len: usize, dst: &mut Vec<u8>) {
+    if len >= 15 {
+        let mut rest = len - 15;
+        while rest >= 255 {
+            dst.push(255);
+            rest -= 255;
+        }
+        dst.push(rest as u8);
+    }
+}
+
+/// Reads the length whose token nibble is `nibble`, followed by extension bytes if it is 15.
+fn read_len(nibble: u8, src: &[u8], i: &mut usize) -> Result<usize, Error> {
+    let mut len = nibble as usize;
+    if nibble == 15 {
+        loop {
+            let byte = *src.get(*i).ok_or(Error::InvalidCompression)?;
+            *i += 1;
+            len = len
+                .checked_add(byte as usize)
+                .ok_or(Error::InvalidCompression)?;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+    Ok(len)
+}
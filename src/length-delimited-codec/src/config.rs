@@ -0,0 +1,511 @@
+#[cfg(feature = "alloc")]
+use crate::Compression;
+use crate::{
+    Checksum, ChecksumPosition, DEFAULT_LENGTH_FIELD_LEN, DEFAULT_MAX_FRAME_LENGTH, Endianness,
+    Error, MAX_SYNC_MARKER_LEN, OversizePolicy, varint,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+/// Parsed header of a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FrameHeader {
+    /// Number of bytes from the start of the frame to the end of the length field.
+    pub header_len: usize,
+    pub body_len: usize,
+}
+
+impl FrameHeader {
+    pub fn frame_len(&self) -> usize {
+        self.header_len + self.body_len
+    }
+}
+
+/// Position of a frame in the decoded stream, reported by checksum mismatches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Position {
+    /// Number of frames preceding the frame, including the discarded ones.
+    pub frame_index: usize,
+    /// Number of bytes preceding the frame.
+    pub offset: usize,
+}
+
+impl Position {
+    /// Moves the position past the frame with the given `header`.
+    pub fn advance(&mut self, header: FrameHeader) {
+        self.frame_index += 1;
+        self.offset += header.frame_len();
+    }
+}
+
+/// State of a decoder of frames arriving in fragments.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum StreamState {
+    /// The header of the current frame is not yet parsed.
+    Header,
+    /// The header of the current frame is parsed and its body is awaited.
+    Body(FrameHeader),
+    /// The remaining bytes of an oversized frame are being skipped.
+    Discard {
+        header: FrameHeader,
+        remaining: usize,
+    },
+}
+
+/// Sync marker stored inline, so that the layout of the frames requires no allocation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SyncMarker {
+    bytes: [u8; MAX_SYNC_MARKER_LEN],
+    len: usize,
+}
+
+impl SyncMarker {
+    /// Stores the `marker`, which has at most `MAX_SYNC_MARKER_LEN` bytes.
+    pub fn new(marker: &[u8]) -> Self {
+        let mut bytes = [0; MAX_SYNC_MARKER_LEN];
+        bytes[..marker.len()].copy_from_slice(marker);
+        Self {
+            bytes,
+            len: marker.len(),
+        }
+    }
+}
+
+impl Deref for SyncMarker {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Layout of the frames shared by the encoder and decoder.
+///
+/// A frame is composed of the sync marker, if any, `length_field_offset` header bytes, the
+/// length field and the body. The value of the length field plus `length_adjustment` is the length of the body,
+/// which includes the checksum, if any.
+#[derive(Clone, Debug)]
+pub(crate) struct Config {
+    pub length_field_offset: usize,
+    pub length_field_len: usize,
+    pub endianness: Endianness,
+    /// Whether the length field is an unsigned LEB128 varint rather than a fixed-width integer.
+    pub varint: bool,
+    pub length_adjustment: isize,
+    pub num_skip: Option<usize>,
+    pub max_frame_length: usize,
+    pub oversize_policy: OversizePolicy,
+    pub checksum: Option<Checksum>,
+    pub checksum_position: ChecksumPosition,
+    /// Bytes preceding each frame which allow resynchronizing after a corruption; empty if
+    /// the frames are not marked.
+    pub sync_marker: SyncMarker,
+    #[cfg(feature = "alloc")]
+    pub compression: Option<Compression>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            length_field_offset: 0,
+            length_field_len: DEFAULT_LENGTH_FIELD_LEN,
+            endianness: Endianness::Big,
+            varint: false,
+            length_adjustment: 0,
+            num_skip: None,
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+            oversize_policy: OversizePolicy::Error,
+            checksum: None,
+            checksum_position: ChecksumPosition::Append,
+            sync_marker: SyncMarker::default(),
+            #[cfg(feature = "alloc")]
+            compression: None,
+        }
+    }
+}
+
+impl Config {
+    /// Number of bytes of the length field holding the `value`.
+    pub fn length_field_len(&self, value: u64) -> usize {
+        match self.varint {
+            true => varint::encoded_len(value),
+            false => self.length_field_len,
+        }
+    }
+
+    /// Number of bytes of the checksum of each frame.
+    pub fn checksum_len(&self) -> usize {
+        self.checksum.map(|x| x.size()).unwrap_or(0)
+    }
+
+    /// Number of bytes of the compression flag of each body.
+    #[cfg(feature = "alloc")]
+    pub fn compression_flag_len(&self) -> usize {
+        self.compression.is_some() as usize
+    }
+
+    /// Number of bytes of the compression flag of each body.
+    #[cfg(not(feature = "alloc"))]
+    pub fn compression_flag_len(&self) -> usize {
+        0
+    }
+
+    /// Largest value that the length field can hold.
+    pub fn max_length_value(&self) -> u64 {
+        match (self.varint, self.length_field_len) {
+            (true, _) | (false, 8) => u64::MAX,
+            (false, n) => (1 << (8 * n)) - 1,
+        }
+    }
+
+    /// Converts the value of the length field into the length of the body.
+    pub fn body_len(&self, value: u64) -> Result<usize, Error> {
+        let len = value as i128 + self.length_adjustment as i128;
+        usize::try_from(len).map_err(|_| Error::InvalidLength { value: len })
+    }
+
+    /// Converts the length of the body into the value of the length field.
+    pub fn length_value(&self, body_len: usize) -> Result<u64, Error> {
+        let value = body_len as i128 - self.length_adjustment as i128;
+        match u64::try_from(value) {
+            Ok(x) if x <= self.max_length_value() => Ok(x),
+            _ => Err(Error::InvalidLength { value }),
+        }
+    }
+
+    /// Reads the length field of the frame at the beginning of `src`.
+    ///
+    /// Returns the value of the length field together with the number of bytes from the
+    /// start of the frame to the end of the length field.
+    pub fn read_length_value(&self, src: &[u8]) -> Result<(u64, usize), Error> {
+        let marker = &self.sync_marker[..self.sync_marker.len().min(src.len())];
+        if !src.starts_with(marker) {
+            return Err(Error::MissingSyncMarker);
+        }
+
+        let offset = self.sync_marker.len() + self.length_field_offset;
+        let truncated = |needed: usize| Error::TruncatedHeader {
+            needed,
+            available: src.len(),
+        };
+
+        if self.varint {
+            let field = src.get(offset..).ok_or(truncated(offset + 1))?;
+            return match varint::decode(field) {
+                Ok((value, len)) => Ok((value, offset + len)),
+                Err(Error::TruncatedHeader { needed, .. }) => Err(truncated(offset + needed)),
+                Err(e) => Err(e),
+            };
+        }
+
+        let header_len = offset + self.length_field_len;
+        let bytes = src.get(offset..header_len).ok_or(truncated(header_len))?;
+        Ok((self.read_uint(bytes), header_len))
+    }
+
+    /// Reads the fixed-width unsigned integer of at most 8 `bytes` in the configured byte order.
+    fn read_uint(&self, bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        match self.endianness {
+            Endianness::Big => {
+                buf[8 - bytes.len()..].copy_from_slice(bytes);
+                u64::from_be_bytes(buf)
+            }
+            Endianness::Little => {
+                buf[..bytes.len()].copy_from_slice(bytes);
+                u64::from_le_bytes(buf)
+            }
+        }
+    }
+
+    /// Writes the `value` as a fixed-width unsigned integer filling `dst` in the configured byte order.
+    fn write_uint(&self, value: u64, dst: &mut [u8]) {
+        let n = dst.len();
+        match self.endianness {
+            Endianness::Big => dst.copy_from_slice(&value.to_be_bytes()[8 - n..]),
+            Endianness::Little => dst.copy_from_slice(&value.to_le_bytes()[..n]),
+        }
+    }
+
+    /// Parses the header of the frame at the beginning of `src` without validating the body
+    /// length against the maximum frame length.
+    pub fn parse_header(&self, src: &[u8]) -> Result<FrameHeader, Error> {
+        let (value, header_len) = self.read_length_value(src)?;
+        let body_len = self.body_len(value)?;
+        match body_len < self.checksum_len() {
+            true => Err(Error::InvalidLength {
+                value: body_len as i128,
+            }),
+            false => Ok(FrameHeader {
+                header_len,
+                body_len,
+            }),
+        }
+    }
+
+    /// Returns the `header` back if its body length does not exceed the maximum frame length.
+    pub fn check_length(&self, header: FrameHeader) -> Result<FrameHeader, Error> {
+        match header.body_len > self.max_frame_length {
+            true => Err(Error::FrameTooLarge {
+                len: header.body_len,
+                max: self.max_frame_length,
+            }),
+            false => Ok(header),
+        }
+    }
+
+    /// Whether oversized frames are skipped rather than reported as errors.
+    pub fn discard_oversized(&self) -> bool {
+        self.oversize_policy == OversizePolicy::Discard
+    }
+
+    /// Range of the bytes of the frame with the given `header` yielded by the decoder.
+    ///
+    /// By default, the sync marker, the header, the length field and the checksum are skipped.
+    /// An explicit `num_skip` counts from the end of the sync marker; neither the sync marker
+    /// nor an appended checksum is ever yielded.
+    pub fn yielded_range(&self, header: FrameHeader) -> core::ops::Range<usize> {
+        let frame_len = header.frame_len();
+        let (skip, end) = match self.checksum_position {
+            ChecksumPosition::Prepend => (header.header_len + self.checksum_len(), frame_len),
+            ChecksumPosition::Append => (header.header_len, frame_len - self.checksum_len()),
+        };
+        let skip = match self.num_skip {
+            Some(num_skip) => self.sync_marker.len() + num_skip,
+            None => skip,
+        };
+        skip.min(end)..end
+    }
+
+    /// Range of the body of the frame with the given `header` without its checksum.
+    #[cfg(feature = "alloc")]
+    fn body_range(&self, header: FrameHeader) -> core::ops::Range<usize> {
+        match self.checksum_position {
+            ChecksumPosition::Prepend => {
+                header.header_len + self.checksum_len()..header.frame_len()
+            }
+            ChecksumPosition::Append => header.header_len..header.frame_len() - self.checksum_len(),
+        }
+    }
+
+    /// Appends the bytes of the `frame` with the given `header` yielded by the decoders to `dst`.
+    ///
+    /// With compression, the body is decompressed and `num_skip` counts the bytes of the frame
+    /// as if its body were not compressed.
+    #[cfg(feature = "alloc")]
+    pub fn unpack(
+        &self,
+        frame: &[u8],
+        header: FrameHeader,
+        dst: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let yielded = self.yielded_range(header);
+        let Some(compression) = self.compression else {
+            dst.extend_from_slice(&frame[yielded]);
+            return Ok(());
+        };
+
+        let body = self.body_range(header);
+        let start = dst.len();
+        dst.extend_from_slice(&frame[yielded.start.min(body.start)..body.start]);
+        compression.unpack(&frame[body.clone()], self.max_frame_length, dst)?;
+        let skip_in_body = yielded
+            .start
+            .saturating_sub(body.start)
+            .min(dst.len() - start);
+        dst.drain(start..start + skip_in_body);
+        Ok(())
+    }
+
+    /// Returns the bytes of the `frame` with the given `header` yielded by the decoders; a slice
+    /// of the frame, or with compression, the frame decompressed into `unpacked`.
+    #[cfg(feature = "alloc")]
+    pub fn yielded<'a>(
+        &self,
+        frame: &'a [u8],
+        header: FrameHeader,
+        unpacked: &'a mut Vec<u8>,
+    ) -> Result<&'a [u8], Error> {
+        match self.compression {
+            None => Ok(&frame[self.yielded_range(header)]),
+            Some(_) => {
+                unpacked.clear();
+                self.unpack(frame, header, unpacked)?;
+                Ok(unpacked)
+            }
+        }
+    }
+
+    /// Number of bytes to skip from the start of `src`, where no valid frame starts, to reach
+    /// the next sync marker.
+    ///
+    /// At least one byte is skipped. If `src` contains no complete marker, the bytes which
+    /// might be the beginning of a marker arriving later are kept; without a sync marker, all
+    /// bytes are skipped.
+    pub fn skip_to_sync_marker(&self, src: &[u8]) -> usize {
+        let marker: &[u8] = &self.sync_marker;
+        let rest = &src[1.min(src.len())..];
+        let skipped = match marker.is_empty() {
+            true => rest.len(),
+            false => match rest.windows(marker.len()).position(|x| x == marker) {
+                Some(position) => position,
+                None => (0..rest.len())
+                    .find(|i| marker.starts_with(&rest[*i..]))
+                    .unwrap_or(rest.len()),
+            },
+        };
+        1 + skipped
+    }
+
+    /// Finds the first sync marker after the start of `src`, and at or after `from`, where a
+    /// complete frame with a matching checksum starts; `position` is the position of `src`.
+    ///
+    /// Returns the offset of the frame in `src`, or else the offset up to which no such frame
+    /// starts: the first marker whose frame is incomplete, or the end of `src` but for the
+    /// bytes which might begin a marker. Without a sync marker and a checksum, a frame cannot
+    /// be told apart from marker bytes within the body of another, and none is found.
+    #[cfg(feature = "alloc")]
+    pub fn find_checked_frame(
+        &self,
+        src: &[u8],
+        from: usize,
+        position: Position,
+    ) -> Result<usize, usize> {
+        let marker: &[u8] = &self.sync_marker;
+        if marker.is_empty() || self.checksum.is_none() {
+            return Err(from);
+        }
+
+        let mut i = from.max(1);
+        loop {
+            let rest = src.get(i..).unwrap_or_default();
+            let Some(found) = rest.windows(marker.len()).position(|x| x == marker) else {
+                return Err(i.max((src.len() + 1).saturating_sub(marker.len())));
+            };
+            i += found;
+            let frame = &src[i..];
+            let position = Position {
+                offset: position.offset + i,
+                ..position
+            };
+            match self.parse_header(frame).and_then(|x| self.check_length(x)) {
+                Ok(header) if frame.len() < header.frame_len() => return Err(i),
+                Ok(header) if self.verify(frame, header, position).is_ok() => return Ok(i),
+                Err(Error::TruncatedHeader { .. }) => return Err(i),
+                _ => i += 1,
+            }
+        }
+    }
+
+    /// Verifies the checksum of the `frame` with the given `header`, if checksums are enabled.
+    pub fn verify(
+        &self,
+        frame: &[u8],
+        header: FrameHeader,
+        position: Position,
+    ) -> Result<(), Error> {
+        self.verify_split(&frame[..header.frame_len()], &[], header, position)
+    }
+
+    /// Verifies the checksum of the frame with the given `header` whose bytes are split into
+    /// `head` followed by `tail`, which ends at the end of the frame.
+    pub fn verify_split(
+        &self,
+        head: &[u8],
+        tail: &[u8],
+        header: FrameHeader,
+        position: Position,
+    ) -> Result<(), Error> {
+        let Some(checksum) = self.checksum else {
+            return Ok(());
+        };
+
+        let split = head.len();
+        let field = self.checksum_field(checksum, header.header_len, header.frame_len());
+        let byte = |i: usize| match i < split {
+            true => head[i],
+            false => tail[i - split],
+        };
+        let mut field_bytes = [0u8; 8];
+        for (i, x) in field.clone().enumerate() {
+            field_bytes[i] = byte(x);
+        }
+        let expected = self.read_uint(&field_bytes[..field.len()]);
+        let actual = checksum.compute(&[
+            &head[..field.start.min(split)],
+            &tail[..field.start.saturating_sub(split)],
+            &head[field.end.min(split)..],
+            &tail[field.end.saturating_sub(split)..],
+        ]);
+        match expected == actual {
+            true => Ok(()),
+            false => Err(Error::ChecksumMismatch {
+                frame_index: position.frame_index,
+                offset: position.offset,
+                expected,
+                actual,
+            }),
+        }
+    }
+
+    /// Computes the checksum of the encoded `frame`, whose length field ends at `header_len`,
+    /// and writes it to the checksum field of the frame, if checksums are enabled.
+    pub fn seal(&self, frame: &mut [u8], header_len: usize) {
+        let Some(checksum) = self.checksum else {
+            return;
+        };
+
+        let field = self.checksum_field(checksum, header_len, frame.len());
+        let value = checksum.compute(&[&frame[..field.start], &frame[field.end..]]);
+        self.write_uint(value, &mut frame[field]);
+    }
+
+    /// Computes the checksum of the frame whose length field ends at `header_len` in `head`
+    /// and whose body is `body`, and writes it to the bytes of `head` following the length
+    /// field, if checksums are enabled.
+    ///
+    /// The checksum covers the same bytes wherever it is placed in the frame, so that the
+    /// frame can be written as `head` around `body` without being assembled.
+    #[cfg(feature = "std")]
+    pub fn seal_split(&self, head: &mut [u8], header_len: usize, body: &[u8]) {
+        let Some(checksum) = self.checksum else {
+            return;
+        };
+
+        let value = checksum.compute(&[&head[..header_len], body]);
+        self.write_uint(value, &mut head[header_len..header_len + checksum.size()]);
+    }
+
+    fn checksum_field(
+        &self,
+        checksum: Checksum,
+        header_len: usize,
+        frame_len: usize,
+    ) -> core::ops::Range<usize> {
+        match self.checksum_position {
+            ChecksumPosition::Prepend => header_len..header_len + checksum.size(),
+            ChecksumPosition::Append => frame_len - checksum.size()..frame_len,
+        }
+    }
+
+    /// Appends the length field holding the `value` to `dst`.
+    #[cfg(feature = "alloc")]
+    pub fn write_length_value(&self, value: u64, dst: &mut Vec<u8>) {
+        let n = self.length_field_len;
+        match (self.varint, self.endianness) {
+            (true, _) => varint::encode(value, dst),
+            (false, Endianness::Big) => dst.extend_from_slice(&value.to_be_bytes()[8 - n..]),
+            (false, Endianness::Little) => dst.extend_from_slice(&value.to_le_bytes()[..n]),
+        }
+    }
+
+    /// Writes the length field holding the `value` to `dst`, which has exactly the length
+    /// of the field.
+    pub fn write_length_value_to(&self, value: u64, dst: &mut [u8]) {
+        match self.varint {
+            true => _ = varint::encode_to_slice(value, dst),
+            false => self.write_uint(value, dst),
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    Builder, Error, Frames, ResyncFrames,
+    config::{Config, FrameHeader, Position},
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+/// Decodes length delimited frames from byte buffers.
+///
+/// By default, each frame is expected to be a 4-byte big endian length field followed by
+/// the payload; see [`Builder`] for other layouts.
+///
+/// With [compression](Builder::compression), the owning methods, such as
+/// [`decode_frame`](Decoder::decode_frame) and [`decode_all`](Decoder::decode_all), yield the
+/// decompressed frames, whereas the borrowing ones, such as [`frames`](Decoder::frames), yield
+/// the frames as stored, to be decompressed by [`decompress`](Decoder::decompress).
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    config: Config,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder {
+    /// Creates a decoder with the default frame layout.
+    pub fn new() -> Self {
+        Builder::new().new_decoder()
+    }
+
+    pub(crate) fn from_config(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Decodes the frame at the beginning of `src`.
+    ///
+    /// Returns the frame without its first `num_skip` bytes together with the number of bytes
+    /// of `src` the frame occupies. The length is validated against the maximum frame length
+    /// before the frame is allocated.
+    ///
+    /// With [`OversizePolicy::Discard`](crate::OversizePolicy::Discard), the oversized frames
+    /// preceding the frame are skipped and included in the returned number of bytes.
+    #[cfg(feature = "alloc")]
+    pub fn decode_frame(&self, src: &[u8]) -> Result<(Vec<u8>, usize), Error> {
+        let (frame, len, header) = self.locate_frame(src)?;
+        let mut unpacked = vec![];
+        self.config.unpack(frame, header, &mut unpacked)?;
+        Ok((unpacked, len))
+    }
+
+    /// Decodes the frame at the beginning of `src` without copying it; see
+    /// [`decode_frame`](Decoder::decode_frame).
+    ///
+    /// ```
+    /// use length_delimited_codec::Decoder;
+    ///
+    /// let bytes = [0, 0, 0, 2, b'h', b'i'];
+    /// let (frame, len) = Decoder::new().decode_frame_ref(&bytes).unwrap();
+    /// assert_eq!(frame, b"hi");
+    /// assert_eq!(len, 6);
+    /// ```
+    pub fn decode_frame_ref<'a>(&self, src: &'a [u8]) -> Result<(&'a [u8], usize), Error> {
+        let (frame, len, header) = self.locate_frame(src)?;
+        Ok((&frame[self.config.yielded_range(header)], len))
+    }
+
+    /// Number of bytes of the frame at the beginning of `src` according to its header; the
+    /// body is neither validated nor required to be in `src`.
+    ///
+    /// The length is not validated against the maximum frame length, so that tools inspecting
+    /// a stream can step over oversized or corrupted frames whose header is valid.
+    ///
+    /// ```
+    /// use length_delimited_codec::Decoder;
+    ///
+    /// let bytes = [0, 0, 0, 5, b'h'];
+    /// assert_eq!(Decoder::new().frame_len(&bytes), Ok(9));
+    /// ```
+    pub fn frame_len(&self, src: &[u8]) -> Result<usize, Error> {
+        Ok(self.config.parse_header(src)?.frame_len())
+    }
+
+    /// Returns the yielded bytes of the `frame`, as borrowed from a buffer by
+    /// [`decode_frame_ref`](Decoder::decode_frame_ref) or [`frames`](Decoder::frames),
+    /// decompressed if compression is enabled.
+    ///
+    /// The frame must be yielded with the default `num_skip`, so that it starts with its body.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Builder, Compression};
+    ///
+    /// let builder = Builder::new().compression(Compression::Lz4);
+    /// let payload = b"abcd".repeat(100);
+    /// let mut bytes = vec![];
+    /// builder.new_encoder().encode(&payload, &mut bytes).unwrap();
+    ///
+    /// let decoder = builder.new_decoder();
+    /// let (stored, _) = decoder.decode_frame_ref(&bytes).unwrap();
+    /// assert!(stored.len() < 50);
+    /// assert_eq!(decoder.decompress(stored).unwrap(), payload);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decompress(&self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut unpacked = vec![];
+        match self.config.compression {
+            Some(compression) => {
+                compression.unpack(frame, self.config.max_frame_length, &mut unpacked)?
+            }
+            None => unpacked.extend_from_slice(frame),
+        }
+        Ok(unpacked)
+    }
+
+    /// Returns an iterator over the frames of `src`, borrowing each frame from `src`.
+    ///
+    /// `src` must end at a frame boundary; otherwise, the iterator yields the error of the
+    /// incomplete last frame and stops.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use length_delimited_codec::{Decoder, Encoder};
+    ///
+    /// let mut bytes = vec![];
+    /// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+    /// Encoder::new().encode(b"world", &mut bytes).unwrap();
+    ///
+    /// let decoder = Decoder::new();
+    /// let frames: Result<Vec<&[u8]>, _> = decoder.frames(&bytes).collect();
+    /// assert_eq!(frames.unwrap(), vec![b"hello", b"world"]);
+    /// # }
+    /// ```
+    pub fn frames<'a>(&'a self, src: &'a [u8]) -> Frames<'a> {
+        Frames::new(self, src)
+    }
+
+    /// Returns an iterator over the frames of `src` which, instead of stopping at an invalid
+    /// frame, skips to the next [sync marker](crate::Builder::sync_marker) and reports the
+    /// range of the skipped bytes.
+    ///
+    /// A frame is invalid if it does not start with the sync marker, its header is invalid,
+    /// its checksum does not match or it is truncated. Since a corrupted frame might be taken
+    /// for a valid one, or the sync marker might appear within the body of a frame, sync
+    /// markers are best combined with a [checksum](crate::Builder::checksum).
+    ///
+    /// Without a sync marker, all bytes after the first invalid frame are skipped.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use length_delimited_codec::{Builder, Error, Resync};
+    ///
+    /// let builder = Builder::new().sync_marker(b"\xA5\x5A");
+    /// let encoder = builder.new_encoder();
+    /// let mut bytes = vec![];
+    /// for payload in [&b"first"[..], b"second", b"third"] {
+    ///     encoder.encode(payload, &mut bytes).unwrap();
+    /// }
+    /// bytes[13] = 0xFF; // corrupts the length field of the second frame
+    ///
+    /// let decoder = builder.new_decoder();
+    /// let items: Vec<_> = decoder.resync_frames(&bytes).collect();
+    /// assert_eq!(items[0], Resync::Frame(&b"first"[..]));
+    /// assert!(matches!(items[1], Resync::Skipped { ref range, .. } if *range == (11..23)));
+    /// assert_eq!(items[2], Resync::Frame(&b"third"[..]));
+    /// # }
+    /// ```
+    pub fn resync_frames<'a>(&'a self, src: &'a [u8]) -> ResyncFrames<'a> {
+        ResyncFrames::new(self, src)
+    }
+
+    /// Splits `src` into the frames it is composed of.
+    ///
+    /// `src` must end at a frame boundary; otherwise, the error of the incomplete last frame is returned.
+    #[cfg(feature = "alloc")]
+    pub fn decode_all(&self, src: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut frames = vec![];
+        let mut position = Position::default();
+        while position.offset < src.len() {
+            let start = position.offset;
+            match self.locate(&src[start..], &mut position)? {
+                Some((_, len, header)) => {
+                    let frame = &src[start + len - header.frame_len()..start + len];
+                    let mut unpacked = vec![];
+                    self.config.unpack(frame, header, &mut unpacked)?;
+                    frames.push(unpacked);
+                }
+                None => break,
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Finds the boundaries of the frames of `src` without copying them.
+    ///
+    /// Returns the range of each frame in `src` without its first `num_skip` bytes; i.e.,
+    /// `&src[range]` is the frame yielded by [`frames`](Decoder::frames). Once indexed,
+    /// the frames can be processed independently, for instance, in parallel.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Decoder, Encoder};
+    ///
+    /// let mut bytes = vec![];
+    /// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+    /// Encoder::new().encode(b"world", &mut bytes).unwrap();
+    ///
+    /// let index = Decoder::new().index(&bytes).unwrap();
+    /// assert_eq!(index, vec![4..9, 13..18]);
+    /// assert_eq!(&bytes[index[1].clone()], b"world");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn index(&self, src: &[u8]) -> Result<Vec<Range<usize>>, Error> {
+        let mut ranges = vec![];
+        let mut position = Position::default();
+        while position.offset < src.len() {
+            let start = position.offset;
+            match self.locate(&src[start..], &mut position)? {
+                Some((range, ..)) => ranges.push((start + range.start)..(start + range.end)),
+                None => break,
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Decodes the frame at the beginning of `src` without copying it unless it is decompressed
+    /// into `unpacked`; returns the frame together with the number of bytes it occupies.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn decode_frame_with<'a>(
+        &self,
+        src: &'a [u8],
+        unpacked: &'a mut Vec<u8>,
+    ) -> Result<(&'a [u8], usize), Error> {
+        let (frame, len, header) = self.locate_frame(src)?;
+        Ok((self.config.yielded(frame, header, unpacked)?, len))
+    }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Locates the frame at the beginning of `src` as in [`locate`](Decoder::locate), returning
+    /// the bytes of the frame, the number of bytes consumed and the header of the frame.
+    fn locate_frame<'a>(&self, src: &'a [u8]) -> Result<(&'a [u8], usize, FrameHeader), Error> {
+        match self.locate(src, &mut Position::default())? {
+            Some((_, len, header)) => Ok((&src[len - header.frame_len()..len], len, header)),
+            None => Err(Error::TruncatedHeader {
+                needed: self.config.sync_marker.len() + self.config.length_field_offset + 1,
+                available: 0,
+            }),
+        }
+    }
+
+    /// Locates the frame at the beginning of `src`, skipping the oversized frames preceding it
+    /// if they are discarded.
+    ///
+    /// Returns the range of the bytes to be yielded, the number of bytes consumed and the
+    /// header of the frame, or None if `src` ends right after the discarded frames. The
+    /// `position` of `src` in the stream is moved past the consumed frames.
+    pub(crate) fn locate(
+        &self,
+        src: &[u8],
+        position: &mut Position,
+    ) -> Result<Option<(Range<usize>, usize, FrameHeader)>, Error> {
+        let mut consumed = 0;
+        loop {
+            let rest = &src[consumed..];
+            if consumed > 0 && rest.is_empty() {
+                return Ok(None);
+            }
+
+            let header = self.config.parse_header(rest)?;
+            let oversized = match self.config.check_length(header) {
+                Ok(_) => false,
+                Err(_) if self.config.discard_oversized() => true,
+                Err(e) => return Err(e),
+            };
+
+            let available = rest.len() - header.header_len;
+            if available < header.body_len {
+                return Err(Error::TruncatedPayload {
+                    expected: header.body_len,
+                    available,
+                });
+            }
+
+            if !oversized {
+                self.config.verify(rest, header, *position)?;
+            }
+            position.advance(header);
+
+            match oversized {
+                true => consumed += header.frame_len(),
+                false => {
+                    let range = self.config.yielded_range(header);
+                    let range = (consumed + range.start)..(consumed + range.end);
+                    return Ok(Some((range, consumed + header.frame_len(), header)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{Builder, ChecksumPosition, Error, config::Config};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, vec::Vec};
+#[cfg(feature = "std")]
+use orx_parallel::*;
+
+/// Encodes payloads into length delimited frames.
+///
+/// By default, each frame is a 4-byte big endian length field followed by the payload;
+/// see [`Builder`] for other layouts.
+#[derive(Clone, Debug)]
+pub struct Encoder {
+    config: Config,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encoder {
+    /// Creates an encoder with the default frame layout.
+    pub fn new() -> Self {
+        Builder::new().new_encoder()
+    }
+
+    pub(crate) fn from_config(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Number of bytes of the encoded frame of a payload with the given length.
+    ///
+    /// With compression, this is the length of the frame of an incompressible payload, which
+    /// is the maximum length of the frame of any payload with the given length.
+    pub fn encoded_len(&self, payload_len: usize) -> usize {
+        let checksum_len = self.config.checksum_len() + self.config.compression_flag_len();
+        let body_len = payload_len.saturating_sub(self.config.length_field_offset) + checksum_len;
+        let value = self.config.length_value(body_len).unwrap_or(u64::MAX);
+        self.config.sync_marker.len()
+            + self.config.length_field_len(value)
+            + payload_len
+            + checksum_len
+    }
+
+    /// Number of bytes of the encoded frame of the `payload`, or the error encoding it would fail with.
+    pub fn frame_len(&self, payload: &[u8]) -> Result<usize, Error> {
+        #[cfg(feature = "alloc")]
+        let payload = &*self.pack(payload)?;
+        self.packed_frame_len(payload)
+    }
+
+    fn packed_frame_len(&self, payload: &[u8]) -> Result<usize, Error> {
+        let value = self.length_value(payload)?;
+        let marker_len = self.config.sync_marker.len();
+        Ok(marker_len
+            + self.config.length_field_len(value)
+            + payload.len()
+            + self.config.checksum_len())
+    }
+
+    /// Appends the frame of the `payload` to `dst`.
+    ///
+    /// The first `length_field_offset` bytes of the payload are the header and the rest is
+    /// the body; the length field is inserted between the two and the sync marker, if any,
+    /// before the header. The body is flagged and compressed if compression is enabled. The
+    /// checksum, if enabled, is computed over the resulting bytes and inserted before or after
+    /// the body.
+    ///
+    /// Returns an error without modifying `dst` if the payload is shorter than the header,
+    /// the body is longer than the maximum frame length or its length cannot be represented
+    /// by the length field.
+    #[cfg(feature = "alloc")]
+    pub fn encode(&self, payload: &[u8], dst: &mut Vec<u8>) -> Result<(), Error> {
+        self.encode_packed(&self.pack(payload)?, dst)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn encode_packed(&self, payload: &[u8], dst: &mut Vec<u8>) -> Result<(), Error> {
+        let value = self.length_value(payload)?;
+        let (header, body) = payload.split_at(self.config.length_field_offset);
+        let checksum_len = self.config.checksum_len();
+
+        let start = dst.len();
+        let marker = &self.config.sync_marker;
+        dst.reserve(
+            marker.len() + self.config.length_field_len(value) + payload.len() + checksum_len,
+        );
+        dst.extend_from_slice(marker);
+        dst.extend_from_slice(header);
+        self.config.write_length_value(value, dst);
+        let header_len = dst.len() - start;
+        match self.config.checksum_position {
+            ChecksumPosition::Prepend => {
+                dst.resize(dst.len() + checksum_len, 0);
+                dst.extend_from_slice(body);
+            }
+            ChecksumPosition::Append => {
+                dst.extend_from_slice(body);
+                dst.resize(dst.len() + checksum_len, 0);
+            }
+        }
+        self.config.seal(&mut dst[start..], header_len);
+        Ok(())
+    }
+
+    /// Appends the frame of the packed `payload` to `dst` except for its body, which is
+    /// returned to be written from the payload itself.
+    ///
+    /// The bytes of the frame preceding the body are followed in `dst` by the bytes following
+    /// it, i.e., an appended checksum, which start at the returned position.
+    #[cfg(feature = "std")]
+    pub(crate) fn encode_packed_split<'a>(
+        &self,
+        payload: &'a [u8],
+        dst: &mut Vec<u8>,
+    ) -> Result<(&'a [u8], usize), Error> {
+        let value = self.length_value(payload)?;
+        let (header, body) = payload.split_at(self.config.length_field_offset);
+        let checksum_len = self.config.checksum_len();
+
+        let start = dst.len();
+        dst.extend_from_slice(&self.config.sync_marker);
+        dst.extend_from_slice(header);
+        self.config.write_length_value(value, dst);
+        let header_len = dst.len() - start;
+        dst.resize(dst.len() + checksum_len, 0);
+        self.config.seal_split(&mut dst[start..], header_len, body);
+
+        let tail_start = match self.config.checksum_position {
+            ChecksumPosition::Prepend => dst.len(),
+            ChecksumPosition::Append => start + header_len,
+        };
+        Ok((body, tail_start))
+    }
+
+    /// Writes the frame of the `payload` to the beginning of `dst`, returning the number of
+    /// bytes written; see [`encode`](Encoder::encode).
+    ///
+    /// Returns [`Error::BufferTooSmall`] if `dst` is shorter than the frame.
+    ///
+    /// ```
+    /// use length_delimited_codec::Encoder;
+    ///
+    /// let mut dst = [0; 8];
+    /// let len = Encoder::new().encode_to_slice(b"hi", &mut dst).unwrap();
+    /// assert_eq!(&dst[..len], &[0, 0, 0, 2, b'h', b'i']);
+    /// ```
+    pub fn encode_to_slice(&self, payload: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+        #[cfg(feature = "alloc")]
+        let payload = &*self.pack(payload)?;
+        self.encode_packed_to_slice(payload, dst)
+    }
+
+    fn encode_packed_to_slice(&self, payload: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+        let value = self.length_value(payload)?;
+        let (header, body) = payload.split_at(self.config.length_field_offset);
+        let checksum_len = self.config.checksum_len();
+
+        let marker: &[u8] = &self.config.sync_marker;
+        let header_start = marker.len();
+        let field_start = header_start + header.len();
+        let header_len = field_start + self.config.length_field_len(value);
+        let len = header_len + body.len() + checksum_len;
+        if dst.len() < len {
+            return Err(Error::BufferTooSmall {
+                needed: len,
+                available: dst.len(),
+            });
+        }
+
+        let body_start = match self.config.checksum_position {
+            ChecksumPosition::Prepend => header_len + checksum_len,
+            ChecksumPosition::Append => header_len,
+        };
+        dst[..header_start].copy_from_slice(marker);
+        dst[header_start..field_start].copy_from_slice(header);
+        self.config
+            .write_length_value_to(value, &mut dst[field_start..header_len]);
+        dst[body_start..body_start + body.len()].copy_from_slice(body);
+        self.config.seal(&mut dst[..len], header_len);
+        Ok(len)
+    }
+
+    /// Encodes the frames of all `payloads` into a single buffer in parallel.
+    ///
+    /// The frame lengths are computed in parallel, their prefix sums give the position of
+    /// each frame in the buffer, and the frames are then written concurrently to the
+    /// disjoint regions of the buffer. The result is identical to encoding the payloads
+    /// sequentially with [`encode`](Encoder::encode).
+    ///
+    /// Returns the error of the first payload which cannot be encoded, if any.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Decoder, Encoder};
+    ///
+    /// let payloads = vec!["hello", "parallel", "world"];
+    /// let bytes = Encoder::new().encode_all_par(&payloads).unwrap();
+    /// let frames = Decoder::new().decode_all(&bytes).unwrap();
+    /// assert_eq!(frames, vec![b"hello".to_vec(), b"parallel".to_vec(), b"world".to_vec()]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn encode_all_par<P>(&self, payloads: &[P]) -> Result<Vec<u8>, Error>
+    where
+        P: AsRef<[u8]> + Sync,
+    {
+        // payloads are compressed once, before their frame lengths are known
+        let packed: Vec<_> = payloads.par().map(|x| self.pack(x.as_ref())).collect();
+        let packed = packed.into_iter().collect::<Result<Vec<_>, _>>()?;
+        let lengths: Vec<_> = packed.par().map(|x| self.packed_frame_len(x)).collect();
+        let lengths = lengths.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        // prefix sums of the lengths are the positions of the frames
+        let mut bytes = vec![0; lengths.iter().sum()];
+        let mut jobs = Vec::with_capacity(payloads.len());
+        let mut rest = bytes.as_mut_slice();
+        for (payload, len) in packed.iter().zip(&lengths) {
+            let (dst, tail) = rest.split_at_mut(*len);
+            jobs.push((payload.as_ref(), dst));
+            rest = tail;
+        }
+
+        jobs.into_par().for_each(|(payload, dst)| {
+            self.encode_packed_to_slice(payload, dst)
+                .expect("frame length is computed for the payload");
+        });
+
+        Ok(bytes)
+    }
+
+    /// Returns the header of the `payload` followed by its flagged and compressed body if
+    /// compression is enabled, or the payload itself otherwise.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn pack<'a>(&self, payload: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        let offset = self.config.length_field_offset;
+        let Some(compression) = self.config.compression else {
+            return Ok(Cow::Borrowed(payload));
+        };
+        let Some((header, body)) = payload.split_at_checked(offset) else {
+            return Ok(Cow::Borrowed(payload)); // rejected by the length validation
+        };
+        if body.len() > self.config.max_frame_length {
+            return Err(Error::FrameTooLarge {
+                len: body.len(),
+                max: self.config.max_frame_length,
+            });
+        }
+
+        let mut packed = Vec::with_capacity(payload.len() + 1);
+        packed.extend_from_slice(header);
+        compression.pack(body, &mut packed);
+        Ok(Cow::Owned(packed))
+    }
+
+    /// Validates the `payload` and returns the value of its length field.
+    fn length_value(&self, payload: &[u8]) -> Result<u64, Error> {
+        let offset = self.config.length_field_offset;
+        if payload.len() < offset {
+            return Err(Error::TruncatedHeader {
+                needed: offset,
+                available: payload.len(),
+            });
+        }
+
+        let body_len = payload.len() - offset + self.config.checksum_len();
+        if body_len > self.config.max_frame_length {
+            return Err(Error::FrameTooLarge {
+                len: body_len,
+                max: self.config.max_frame_length,
+            });
+        }
+        self.config.length_value(body_len)
+    }
+}
@@ -0,0 +1,9 @@
+/// Byte order of the length field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first; i.e., network byte order.
+    #[default]
+    Big,
+    /// Least significant byte first.
+    Little,
+}
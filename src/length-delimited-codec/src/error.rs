@@ -0,0 +1,239 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::Display;
+
+/// Error of encoding or decoding length delimited frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The buffer ends before the length field of the frame is complete.
+    TruncatedHeader {
+        /// Number of bytes required to read the length field.
+        needed: usize,
+        /// Number of bytes available in the buffer.
+        available: usize,
+    },
+    /// The buffer ends before the payload of the frame is complete.
+    TruncatedPayload {
+        /// Length of the payload declared by the length field.
+        expected: usize,
+        /// Number of payload bytes available in the buffer.
+        available: usize,
+    },
+    /// The length of the frame exceeds the maximum allowed frame length.
+    FrameTooLarge {
+        /// Length of the frame.
+        len: usize,
+        /// Maximum allowed frame length.
+        max: usize,
+    },
+    /// The length field does not correspond to a valid body length after the length
+    /// adjustment, or the body length cannot be represented by the length field.
+    InvalidLength {
+        /// Length of the body after the adjustment, or the adjusted value of the length field.
+        value: i128,
+    },
+    /// The varint length field is not encoded with the minimum number of bytes.
+    VarintOverlong {
+        /// Number of bytes of the varint.
+        len: usize,
+    },
+    /// The varint length field does not fit in a `u64`.
+    VarintOverflow,
+    /// The checksum stored in the frame does not match the checksum of its bytes.
+    ChecksumMismatch {
+        /// Index of the frame in the stream, counting the discarded frames.
+        frame_index: usize,
+        /// Position of the first byte of the frame in the stream.
+        offset: usize,
+        /// Checksum stored in the frame.
+        expected: u64,
+        /// Checksum computed from the bytes of the frame.
+        actual: u64,
+    },
+    /// The destination buffer of an encoder, or the buffer of a
+    /// [`FixedStreamDecoder`](crate::FixedStreamDecoder), is too short for the frame.
+    BufferTooSmall {
+        /// Number of bytes of the frame.
+        needed: usize,
+        /// Number of bytes of the buffer.
+        available: usize,
+    },
+    /// The frame does not start with the sync marker.
+    MissingSyncMarker,
+    /// The compression flag of the frame is unknown, or its compressed body is malformed.
+    InvalidCompression,
+    /// The file does not start with the magic bytes of a length delimited file.
+    InvalidFileHeader,
+    /// The file is written with a version of the format which is not supported.
+    UnsupportedFileVersion {
+        /// Version stored in the file header.
+        version: u32,
+    },
+    /// The file does not end with an index footer, for instance, because writing it was
+    /// interrupted.
+    MissingFileIndex,
+    /// The index footer of the file is inconsistent with the file or its checksum.
+    CorruptFileIndex,
+    /// The index of the requested record is not less than the number of records.
+    RecordOutOfRange {
+        /// Index of the requested record.
+        index: usize,
+        /// Number of records.
+        len: usize,
+    },
+    /// The payload of a multiplexed frame does not start with valid flags and a channel id.
+    InvalidMuxHeader,
+    /// The fragment does not continue the message of its channel as flagged; for instance,
+    /// a continuation arrives without a preceding start.
+    UnexpectedFragment {
+        /// Id of the channel of the fragment.
+        channel: u64,
+    },
+    /// The stream ends while the message of a channel is incomplete.
+    IncompleteMessage {
+        /// Id of the channel of the incomplete message.
+        channel: u64,
+    },
+    /// The length of the reassembled message exceeds the maximum allowed message length.
+    MessageTooLarge {
+        /// Length of the message reassembled so far.
+        len: usize,
+        /// Maximum allowed message length.
+        max: usize,
+    },
+    /// A fragment arrives on a new channel while the maximum number of channels with pending
+    /// messages is reached.
+    TooManyChannels {
+        /// Id of the channel of the fragment.
+        channel: u64,
+        /// Maximum allowed number of channels with pending messages.
+        max: usize,
+    },
+    /// The chunk header is invalid, the chunk does not continue a message as flagged, or the
+    /// chunks of a message do not add up to its declared length.
+    InvalidChunk,
+    /// The message is split into more chunks than the allowed number of chunks in flight.
+    TooManyChunks {
+        /// Maximum allowed number of chunks of a message.
+        max: usize,
+    },
+    /// The message cannot be serialized by the serializer of a typed encoder.
+    #[cfg(feature = "alloc")]
+    SerializationFailed {
+        /// Description of the failure reported by the serializer.
+        reason: String,
+    },
+    /// The frame is not a valid serialized message of the type of a typed decoder.
+    #[cfg(feature = "alloc")]
+    InvalidMessage {
+        /// Description of the failure reported by the serializer.
+        reason: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TruncatedHeader { needed, available } => write!(
+                f,
+                "truncated header: {needed} bytes are required but {available} are available"
+            ),
+            Self::TruncatedPayload {
+                expected,
+                available,
+            } => write!(
+                f,
+                "truncated payload: {expected} bytes are expected but {available} are available"
+            ),
+            Self::FrameTooLarge { len, max } => write!(
+                f,
+                "frame too large: length {len} exceeds the maximum frame length {max}"
+            ),
+            Self::InvalidLength { value } => {
+                write!(f, "invalid length: {value} is out of the valid range")
+            }
+            Self::VarintOverlong { len } => write!(
+                f,
+                "overlong varint: {len} bytes are used where fewer would suffice"
+            ),
+            Self::VarintOverflow => write!(f, "varint overflow: value does not fit in a u64"),
+            Self::ChecksumMismatch {
+                frame_index,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checksum mismatch: frame {frame_index} at offset {offset} has checksum {actual:#x} but {expected:#x} is stored"
+            ),
+            Self::BufferTooSmall { needed, available } => write!(
+                f,
+                "buffer too small: {needed} bytes are required but {available} are available"
+            ),
+            Self::MissingSyncMarker => {
+                write!(f, "missing sync marker: frame does not start with it")
+            }
+            Self::InvalidCompression => {
+                write!(f, "invalid compression: body cannot be decompressed")
+            }
+            Self::InvalidFileHeader => write!(f, "invalid file header: magic bytes do not match"),
+            Self::UnsupportedFileVersion { version } => {
+                write!(f, "unsupported file version: {version}")
+            }
+            Self::MissingFileIndex => write!(f, "missing file index: no index footer is found"),
+            Self::CorruptFileIndex => write!(f, "corrupt file index: footer is inconsistent"),
+            Self::RecordOutOfRange { index, len } => write!(
+                f,
+                "record out of range: index {index} is out of the {len} records"
+            ),
+            Self::InvalidMuxHeader => {
+                write!(f, "invalid mux header: flags or channel id are invalid")
+            }
+            Self::UnexpectedFragment { channel } => write!(
+                f,
+                "unexpected fragment: fragment on channel {channel} does not continue its message"
+            ),
+            Self::IncompleteMessage { channel } => write!(
+                f,
+                "incomplete message: stream ends within a message on channel {channel}"
+            ),
+            Self::MessageTooLarge { len, max } => write!(
+                f,
+                "message too large: length {len} exceeds the maximum message length {max}"
+            ),
+            Self::TooManyChannels { channel, max } => write!(
+                f,
+                "too many channels: channel {channel} exceeds the maximum of {max} channels with pending messages"
+            ),
+            Self::InvalidChunk => write!(f, "invalid chunk: header or length is inconsistent"),
+            Self::TooManyChunks { max } => write!(
+                f,
+                "too many chunks: message exceeds the maximum of {max} chunks"
+            ),
+            #[cfg(feature = "alloc")]
+            Self::SerializationFailed { reason } => {
+                write!(f, "serialization failed: {reason}")
+            }
+            #[cfg(feature = "alloc")]
+            Self::InvalidMessage { reason } => write!(f, "invalid message: {reason}"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        let kind = match error {
+            Error::TruncatedHeader { .. }
+            | Error::TruncatedPayload { .. }
+            | Error::IncompleteMessage { .. } => std::io::ErrorKind::UnexpectedEof,
+            Error::RecordOutOfRange { .. } | Error::SerializationFailed { .. } => {
+                std::io::ErrorKind::InvalidInput
+            }
+            _ => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, error)
+    }
+}
@@ -0,0 +1,141 @@
+use super::input::{SplitMix64, process};
+use crate::{max_payload_len, num_frames, run_utils::run_throughput};
+use length_delimited_codec::{Builder, Compression};
+
+// all
+
+pub fn run_all(seed: u64) {
+    println!("\n\n# COMPRESSION - UNCOMPRESSED VS LZ4 FRAMES");
+
+    let uncompressed = Builder::new();
+    let lz4 = Builder::new().compression(Compression::Lz4);
+
+    let inputs = [
+        ("text", text_payloads(seed)),
+        ("random", random_payloads(seed)),
+    ];
+    for (name, payloads) in inputs {
+        let num_frames = payloads.len();
+        let num_bytes: usize = payloads.iter().map(|x| x.len()).sum();
+        println!(
+            "\n## {name} payloads = {num_frames}, bytes = {num_bytes}, max payload length = {}\n",
+            max_payload_len()
+        );
+
+        let log = |bytes: Vec<u8>| {
+            println!(
+                "  bytes = {}\n  ratio = {:.3}",
+                bytes.len(),
+                num_bytes as f64 / bytes.len() as f64
+            );
+        };
+        let log_checksum = |checksum: u64| println!("  checksum = {checksum}");
+
+        run_throughput(
+            "encode_uncompressed",
+            num_bytes,
+            num_frames,
+            || encode(&uncompressed, &payloads),
+            log,
+        );
+        run_throughput(
+            "encode_lz4",
+            num_bytes,
+            num_frames,
+            || encode(&lz4, &payloads),
+            log,
+        );
+        run_throughput(
+            "encode_par_uncompressed",
+            num_bytes,
+            num_frames,
+            || orx(&uncompressed, &payloads),
+            log,
+        );
+        run_throughput(
+            "encode_par_lz4",
+            num_bytes,
+            num_frames,
+            || orx(&lz4, &payloads),
+            log,
+        );
+
+        let bytes = encode(&uncompressed, &payloads);
+        run_throughput(
+            "decode_uncompressed",
+            num_bytes,
+            num_frames,
+            || decode(&uncompressed, &bytes),
+            log_checksum,
+        );
+        let bytes = encode(&lz4, &payloads);
+        run_throughput(
+            "decode_lz4",
+            num_bytes,
+            num_frames,
+            || decode(&lz4, &bytes),
+            log_checksum,
+        );
+    }
+
+    println!();
+}
+
+/// Payloads of words drawn from a small vocabulary, which compress well.
+fn text_payloads(seed: u64) -> Vec<Vec<u8>> {
+    const WORDS: [&str; 16] = [
+        "the ", "frame ", "length ", "of ", "a ", "payload ", "is ", "encoded ", "in ", "header ",
+        "and ", "body ", "with ", "stream ", "codec ", "bytes ",
+    ];
+    let mut rng = SplitMix64::new(seed);
+    (0..num_frames())
+        .map(|_| {
+            let len = rng.next_u64() as usize % (max_payload_len() + 1);
+            let mut payload = Vec::with_capacity(len + 8);
+            while payload.len() < len {
+                let word = WORDS[rng.next_u64() as usize % WORDS.len()];
+                payload.extend_from_slice(word.as_bytes());
+            }
+            payload.truncate(len);
+            payload
+        })
+        .collect()
+}
+
+/// Payloads of random bytes, which are stored without compression.
+fn random_payloads(seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = SplitMix64::new(seed);
+    (0..num_frames())
+        .map(|_| {
+            let len = rng.next_u64() as usize % (max_payload_len() + 1);
+            (0..len).map(|_| rng.next_u64() as u8).collect()
+        })
+        .collect()
+}
+
+// seq
+
+pub fn encode(builder: &Builder, payloads: &[Vec<u8>]) -> Vec<u8> {
+    let encoder = builder.new_encoder();
+    let mut bytes = vec![];
+    for payload in payloads {
+        encoder.encode(payload, &mut bytes).unwrap();
+    }
+    bytes
+}
+
+/// Decodes and processes each frame, decompressing it into a reused buffer.
+pub fn decode(builder: &Builder, bytes: &[u8]) -> u64 {
+    let mut reader = builder.new_framed_buf_reader(bytes);
+    let mut checksum = 0;
+    while let Some(frame) = reader.read_frame().unwrap() {
+        checksum ^= process(frame);
+    }
+    checksum
+}
+
+// orx
+
+pub fn orx(builder: &Builder, payloads: &[Vec<u8>]) -> Vec<u8> {
+    builder.new_encoder().encode_all_par(payloads).unwrap()
+}
@@ -0,0 +1,65 @@
+use crate::{max_payload_len, num_frames};
+use length_delimited_codec::Encoder;
+
+/// Deterministic pseudo-random generator, so that all experiments decode the same input.
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Payloads of the experiments with lengths uniformly distributed in [0, max_payload_len].
+pub fn payloads(seed: u64) -> Vec<Vec<u8>> {
+    payloads_with(seed, num_frames(), max_payload_len())
+}
+
+/// `num_payloads` payloads with lengths uniformly distributed in [0, max_len].
+pub fn payloads_with(seed: u64, num_payloads: usize, max_len: usize) -> Vec<Vec<u8>> {
+    let mut rng = SplitMix64::new(seed);
+    (0..num_payloads)
+        .map(|_| {
+            let len = rng.next_u64() as usize % (max_len + 1);
+            let first = rng.next_u64() as u8;
+            (0..len).map(|i| first.wrapping_add(i as u8)).collect()
+        })
+        .collect()
+}
+
+/// Buffer of the length delimited frames of the payloads of the experiments.
+pub fn encoded_input(seed: u64) -> Vec<u8> {
+    let encoder = Encoder::new();
+    let payloads = payloads(seed);
+    let mut bytes = Vec::with_capacity(payloads.iter().map(|x| encoder.encoded_len(x.len())).sum());
+    for payload in &payloads {
+        encoder
+            .encode(payload, &mut bytes)
+            .expect("payloads must be encodable");
+    }
+    bytes
+}
+
+/// Processing of a decoded frame, reading all of its bytes.
+pub fn process(frame: &[u8]) -> u64 {
+    frame
+        .iter()
+        .fold(frame.len() as u64, |h, x| h.rotate_left(5) ^ *x as u64)
+}
+
+pub fn log_input(bytes: &[u8]) {
+    println!(
+        "  frames = {}, bytes = {}, max payload length = {}\n",
+        num_frames(),
+        bytes.len(),
+        max_payload_len()
+    );
+}
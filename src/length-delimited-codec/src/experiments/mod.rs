@@ -0,0 +1,6 @@
+pub mod compression;
+pub mod input;
+pub mod parallel_decode;
+pub mod parallel_encode;
+pub mod vectored_io;
+pub mod zero_copy;
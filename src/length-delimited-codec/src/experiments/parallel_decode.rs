@@ -0,0 +1,105 @@
+use super::input::{encoded_input, log_input, process};
+use crate::{amount_of_work, run_utils::run_throughput};
+use length_delimited_codec::Decoder;
+use orx_parallel::*;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::ops::Range;
+
+// setup
+
+/// Processes the frame `amount_of_work` times, to simulate heavier per-frame computations.
+fn process_frame(frame: &[u8]) -> u64 {
+    (0..amount_of_work()).fold(0, |h, i| h ^ process(frame).rotate_left(i as u32))
+}
+
+// all
+
+pub fn run_all(seed: u64) {
+    println!("\n\n# PARALLEL DECODING - SEQUENTIAL INDEX PASS, THEN PARALLEL PROCESSING");
+    let bytes = encoded_input(seed);
+    log_input(&bytes);
+
+    let index = Decoder::new().index(&bytes).unwrap();
+    let (num_bytes, num_frames) = (bytes.len(), index.len());
+
+    let log = |(count, checksum): (usize, u64)| {
+        println!("  frames = {count}\n  checksum = {checksum}");
+    };
+
+    run_throughput(
+        "sequential",
+        num_bytes,
+        num_frames,
+        || sequential(&bytes),
+        log,
+    );
+
+    run_throughput(
+        "index_pass",
+        num_bytes,
+        num_frames,
+        || Decoder::new().index(&bytes).unwrap().len(),
+        |count| println!("  frames = {count}"),
+    );
+
+    run_throughput("rayon", num_bytes, num_frames, || rayon(&bytes), log);
+    run_throughput(
+        "rayon_given_index",
+        num_bytes,
+        num_frames,
+        || rayon_given_index(&bytes, &index),
+        log,
+    );
+
+    run_throughput("orx", num_bytes, num_frames, || orx(&bytes), log);
+    run_throughput(
+        "orx_given_index",
+        num_bytes,
+        num_frames,
+        || orx_given_index(&bytes, &index),
+        log,
+    );
+
+    println!();
+}
+
+// seq
+
+pub fn sequential(bytes: &[u8]) -> (usize, u64) {
+    let decoder = Decoder::new();
+    decoder
+        .frames(bytes)
+        .map(|x| process_frame(x.unwrap()))
+        .fold((0, 0), |(count, h), x| (count + 1, h ^ x))
+}
+
+// rayon
+
+pub fn rayon(bytes: &[u8]) -> (usize, u64) {
+    let index = Decoder::new().index(bytes).unwrap();
+    rayon_given_index(bytes, &index)
+}
+
+pub fn rayon_given_index(bytes: &[u8], index: &[Range<usize>]) -> (usize, u64) {
+    let checksum = index
+        .par_iter()
+        .map(|x| process_frame(&bytes[x.clone()]))
+        .reduce(|| 0, |a, b| a ^ b);
+    (index.len(), checksum)
+}
+
+// orx
+
+pub fn orx(bytes: &[u8]) -> (usize, u64) {
+    let index = Decoder::new().index(bytes).unwrap();
+    orx_given_index(bytes, &index)
+}
+
+pub fn orx_given_index(bytes: &[u8], index: &[Range<usize>]) -> (usize, u64) {
+    let checksum = index
+        .par()
+        .map(|x| process_frame(&bytes[x.clone()]))
+        .reduce(|a, b| a ^ b)
+        .unwrap_or(0);
+    (index.len(), checksum)
+}
@@ -0,0 +1,106 @@
+use super::input::{payloads_with, process};
+use crate::{max_payload_len, num_frames, run_utils::run_throughput};
+use length_delimited_codec::Encoder;
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+// all
+
+pub fn run_all(seed: u64) {
+    println!("\n\n# PARALLEL ENCODING - SIZES, PREFIX SUM, CONCURRENT WRITES");
+
+    // the number of payloads is scaled so that each distribution has the same total size
+    let total_len = num_frames() * max_payload_len() / 2;
+    for max_len in [16, max_payload_len(), 16 * 1024] {
+        let num_payloads = total_len / (max_len / 2).max(1);
+        let payloads = payloads_with(seed, num_payloads, max_len);
+        let num_bytes: usize = payloads.iter().map(|x| x.len()).sum();
+        println!("\n## payloads = {num_payloads}, max payload length = {max_len}\n");
+
+        let log = |bytes: Vec<u8>| {
+            println!(
+                "  bytes = {}\n  checksum = {}",
+                bytes.len(),
+                process(&bytes)
+            );
+        };
+
+        run_throughput(
+            "sequential",
+            num_bytes,
+            num_payloads,
+            || sequential(&payloads),
+            log,
+        );
+        run_throughput(
+            "sequential_presized",
+            num_bytes,
+            num_payloads,
+            || sequential_presized(&payloads),
+            log,
+        );
+        run_throughput("rayon", num_bytes, num_payloads, || rayon(&payloads), log);
+        run_throughput("orx", num_bytes, num_payloads, || orx(&payloads), log);
+    }
+
+    println!();
+}
+
+// seq
+
+/// Appends each frame to a growing `Vec`.
+pub fn sequential(payloads: &[Vec<u8>]) -> Vec<u8> {
+    let encoder = Encoder::new();
+    let mut bytes = vec![];
+    for payload in payloads {
+        encoder.encode(payload, &mut bytes).unwrap();
+    }
+    bytes
+}
+
+/// Computes the frame sizes first and writes each frame to its region of the buffer.
+pub fn sequential_presized(payloads: &[Vec<u8>]) -> Vec<u8> {
+    let encoder = Encoder::new();
+    let lengths: Vec<_> = payloads
+        .iter()
+        .map(|x| encoder.frame_len(x).unwrap())
+        .collect();
+
+    let mut bytes = vec![0; lengths.iter().sum()];
+    let mut position = 0;
+    for (payload, len) in payloads.iter().zip(&lengths) {
+        position += encoder
+            .encode_to_slice(payload, &mut bytes[position..position + len])
+            .unwrap();
+    }
+    bytes
+}
+
+// rayon
+
+pub fn rayon(payloads: &[Vec<u8>]) -> Vec<u8> {
+    let encoder = Encoder::new();
+    let lengths: Vec<_> = payloads
+        .par_iter()
+        .map(|x| encoder.frame_len(x).unwrap())
+        .collect();
+
+    let mut bytes = vec![0; lengths.iter().sum()];
+    let mut jobs = Vec::with_capacity(payloads.len());
+    let mut rest = bytes.as_mut_slice();
+    for (payload, len) in payloads.iter().zip(&lengths) {
+        let (dst, tail) = rest.split_at_mut(*len);
+        jobs.push((payload, dst));
+        rest = tail;
+    }
+
+    jobs.into_par_iter().for_each(|(payload, dst)| {
+        encoder.encode_to_slice(payload, dst).unwrap();
+    });
+    bytes
+}
+
+// orx
+
+pub fn orx(payloads: &[Vec<u8>]) -> Vec<u8> {
+    Encoder::new().encode_all_par(payloads).unwrap()
+}
@@ -0,0 +1,297 @@
+use super::input::{SplitMix64, encoded_input, payloads};
+use crate::{max_payload_len, num_frames, run_utils::run_throughput};
+use length_delimited_codec::{
+    BufferPool, Encoder, FramedBufReader, FramedReader, FramedWriter, StreamDecoder,
+};
+use std::io::{self, Write};
+
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Number of frames held before being dropped, as a consumer of owned frames would.
+const NUM_HELD: usize = 64;
+
+// all
+
+pub fn run_all(seed: u64) {
+    println!("\n\n# VECTORED WRITES - NULL DEVICE");
+
+    let inputs = [("small", payloads(seed)), ("large", large_payloads(seed))];
+    for (name, payloads) in inputs {
+        let num_frames = payloads.len();
+        let num_bytes: usize = payloads.iter().map(|x| x.len()).sum();
+        println!("\n## {name} payloads = {num_frames}, bytes = {num_bytes}\n");
+
+        let log = |num_written: usize| println!("  written = {num_written}");
+
+        run_throughput(
+            "copy_then_write",
+            num_bytes,
+            num_frames,
+            || copy_then_write(&payloads),
+            log,
+        );
+        run_throughput(
+            "header_and_payload_writes",
+            num_bytes,
+            num_frames,
+            || header_and_payload_writes(&payloads),
+            log,
+        );
+        run_throughput(
+            "write_frame_vectored",
+            num_bytes,
+            num_frames,
+            || write_frame_vectored(&payloads),
+            log,
+        );
+        run_throughput(
+            "write_frames_batched",
+            num_bytes,
+            num_frames,
+            || write_frames_batched(&payloads),
+            log,
+        );
+    }
+
+    println!("\n\n# BUFFER POOL - OWNED FRAMES");
+
+    let bytes = encoded_input(seed);
+    let num_bytes = bytes.len();
+    println!(
+        "  frames = {}, bytes = {num_bytes}, max payload length = {}\n",
+        num_frames(),
+        max_payload_len()
+    );
+
+    let log = |(checksum, num_allocated): (u64, usize)| {
+        println!("  checksum = {checksum}\n  buffers allocated = {num_allocated}");
+    };
+
+    run_throughput(
+        "stream_allocating",
+        num_bytes,
+        num_frames(),
+        || stream_allocating(&bytes),
+        log,
+    );
+    run_throughput(
+        "stream_pooled",
+        num_bytes,
+        num_frames(),
+        || stream_pooled(&bytes),
+        log,
+    );
+    run_throughput(
+        "buf_reader_to_vec",
+        num_bytes,
+        num_frames(),
+        || buf_reader_to_vec(&bytes),
+        log,
+    );
+    run_throughput(
+        "buf_reader_pooled",
+        num_bytes,
+        num_frames(),
+        || buf_reader_pooled(&bytes),
+        log,
+    );
+    run_throughput(
+        "reader_pooled",
+        num_bytes,
+        num_frames(),
+        || reader_pooled(&bytes),
+        log,
+    );
+
+    println!();
+}
+
+/// Payloads of 1 to 16 KiB, which are written without being copied.
+fn large_payloads(seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = SplitMix64::new(seed);
+    (0..num_frames() / 16)
+        .map(|_| {
+            let len = 1024 + rng.next_u64() as usize % (15 * 1024);
+            (0..len).map(|i| i as u8).collect()
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+const NULL_DEVICE: &str = "/dev/null";
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+
+/// Writer discarding the bytes: the null device of the platform, so that each write is a
+/// system call, or else `io::sink`, which measures the encoding but not the system calls.
+fn null_device() -> Box<dyn Write> {
+    #[cfg(any(unix, windows))]
+    if let Ok(file) = std::fs::File::options().write(true).open(NULL_DEVICE) {
+        return Box::new(file);
+    }
+    Box::new(io::sink())
+}
+
+/// Writer of the null device counting the bytes written, so that no write is optimized away.
+struct Counting {
+    file: Box<dyn Write>,
+    num_written: usize,
+}
+
+impl Write for Counting {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.file.write(buf)?;
+        self.num_written += n;
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let n = self.file.write_vectored(bufs)?;
+        self.num_written += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn counting() -> Counting {
+    Counting {
+        file: null_device(),
+        num_written: 0,
+    }
+}
+
+// writes
+
+/// Encodes each frame into a reused buffer, copying the payload, and writes the buffer.
+pub fn copy_then_write(payloads: &[Vec<u8>]) -> usize {
+    let encoder = Encoder::new();
+    let mut writer = counting();
+    let mut buffer = vec![];
+    for payload in payloads {
+        buffer.clear();
+        encoder.encode(payload, &mut buffer).unwrap();
+        writer.write_all(&buffer).unwrap();
+    }
+    writer.num_written
+}
+
+/// Writes the length field and the payload of each frame with two separate writes.
+pub fn header_and_payload_writes(payloads: &[Vec<u8>]) -> usize {
+    let mut writer = counting();
+    for payload in payloads {
+        writer
+            .write_all(&(payload.len() as u32).to_be_bytes())
+            .unwrap();
+        writer.write_all(payload).unwrap();
+    }
+    writer.num_written
+}
+
+/// Writes each frame with a vectored write of its header and payload.
+pub fn write_frame_vectored(payloads: &[Vec<u8>]) -> usize {
+    let mut writer = FramedWriter::new(counting());
+    for payload in payloads {
+        writer.write_frame(payload).unwrap();
+    }
+    writer.into_inner().num_written
+}
+
+/// Writes batches of frames with a vectored write each.
+pub fn write_frames_batched(payloads: &[Vec<u8>]) -> usize {
+    let mut writer = FramedWriter::new(counting());
+    writer.write_frames(payloads).unwrap();
+    writer.into_inner().num_written
+}
+
+// owned frames
+
+/// Allocates a `Vec<u8>` for each frame of the stream decoder.
+pub fn stream_allocating(bytes: &[u8]) -> (u64, usize) {
+    let mut decoder = StreamDecoder::new();
+    let mut held = Vec::with_capacity(NUM_HELD);
+    let mut checksum = 0;
+    for chunk in bytes.chunks(CHUNK_LEN) {
+        decoder.feed(chunk);
+        while let Some(frame) = decoder.next_frame().unwrap() {
+            checksum ^= touch(&frame);
+            hold(&mut held, frame);
+        }
+    }
+    (checksum, num_frames())
+}
+
+/// Takes the buffer of each frame of the stream decoder from a pool.
+pub fn stream_pooled(bytes: &[u8]) -> (u64, usize) {
+    let pool = BufferPool::new(NUM_HELD);
+    let mut decoder = StreamDecoder::new();
+    let mut held = Vec::with_capacity(NUM_HELD);
+    let mut checksum = 0;
+    for chunk in bytes.chunks(CHUNK_LEN) {
+        decoder.feed(chunk);
+        while let Some(frame) = decoder.next_pooled_frame(&pool).unwrap() {
+            checksum ^= touch(&frame);
+            hold(&mut held, frame);
+        }
+    }
+    drop(held);
+    (checksum, pool.num_allocated())
+}
+
+/// Copies each frame borrowed from the buffered reader into a new `Vec<u8>`.
+pub fn buf_reader_to_vec(bytes: &[u8]) -> (u64, usize) {
+    let mut reader = FramedBufReader::new(bytes);
+    let mut held = Vec::with_capacity(NUM_HELD);
+    let mut checksum = 0;
+    while let Some(frame) = reader.read_frame().unwrap() {
+        checksum ^= touch(frame);
+        hold(&mut held, frame.to_vec());
+    }
+    (checksum, num_frames())
+}
+
+/// Copies each frame borrowed from the buffered reader into a buffer of a pool; a copy
+/// remains since the buffered reader reads ahead of the current frame.
+pub fn buf_reader_pooled(bytes: &[u8]) -> (u64, usize) {
+    let pool = BufferPool::new(NUM_HELD);
+    let mut reader = FramedBufReader::new(bytes);
+    let mut held = Vec::with_capacity(NUM_HELD);
+    let mut checksum = 0;
+    while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+        checksum ^= touch(&frame);
+        hold(&mut held, frame);
+    }
+    drop(held);
+    (checksum, pool.num_allocated())
+}
+
+/// Reads the body of each frame directly into a buffer of a pool, with one small read for the
+/// header and one for the body of each frame.
+pub fn reader_pooled(bytes: &[u8]) -> (u64, usize) {
+    let pool = BufferPool::new(NUM_HELD);
+    let mut reader = FramedReader::new(bytes);
+    let mut held = Vec::with_capacity(NUM_HELD);
+    let mut checksum = 0;
+    while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+        checksum ^= touch(&frame);
+        hold(&mut held, frame);
+    }
+    drop(held);
+    (checksum, pool.num_allocated())
+}
+
+/// Light processing of a frame, so that the cost of obtaining owned frames is not hidden by
+/// reading all of their bytes.
+fn touch(frame: &[u8]) -> u64 {
+    frame.len() as u64 ^ frame.last().map_or(0, |x| *x as u64) << 32
+}
+
+/// Holds the `frame` with the last frames, dropping them once `NUM_HELD` frames are held.
+fn hold<T>(held: &mut Vec<T>, frame: T) {
+    if held.len() == NUM_HELD {
+        held.clear();
+    }
+    held.push(frame);
+}
@@ -0,0 +1,87 @@
+use super::input::{encoded_input, log_input, process};
+use crate::run_utils::run;
+use length_delimited_codec::{Decoder, FramedBufReader, FramedReader, StreamDecoder};
+use std::io::Cursor;
+
+const CHUNK_LEN: usize = 64 * 1024;
+
+// all
+
+pub fn run_all(seed: u64) {
+    let bytes = encoded_input(seed);
+
+    println!("\n\n# ZERO-COPY DECODING - IN-MEMORY BUFFER");
+    log_input(&bytes);
+
+    let log = |(count, checksum): (usize, u64)| {
+        println!("  frames = {count}\n  checksum = {checksum}");
+    };
+
+    run("allocating", || allocating(&bytes), log);
+    run("zero_copy", || zero_copy(&bytes), log);
+
+    println!("\n\n# ZERO-COPY DECODING - READER");
+    log_input(&bytes);
+
+    run("allocating_stream", || allocating_stream(&bytes), log);
+    run("framed_reader", || framed_reader(&bytes), log);
+    run("zero_copy_buf_reader", || zero_copy_buf_reader(&bytes), log);
+
+    println!();
+}
+
+// in-memory buffer
+
+/// Allocates a `Vec<u8>` for each frame.
+pub fn allocating(bytes: &[u8]) -> (usize, u64) {
+    let frames = Decoder::new().decode_all(bytes).unwrap();
+    let checksum = frames.iter().fold(0, |h, x| h ^ process(x));
+    (frames.len(), checksum)
+}
+
+/// Borrows each frame from the buffer.
+pub fn zero_copy(bytes: &[u8]) -> (usize, u64) {
+    let decoder = Decoder::new();
+    decoder
+        .frames(bytes)
+        .map(|x| x.unwrap())
+        .fold((0, 0), |(count, h), x| (count + 1, h ^ process(x)))
+}
+
+// reader
+
+/// Feeds the bytes in chunks to a stream decoder which allocates a `Vec<u8>` for each frame.
+pub fn allocating_stream(bytes: &[u8]) -> (usize, u64) {
+    let mut decoder = StreamDecoder::new();
+    let (mut count, mut checksum) = (0, 0);
+    for chunk in bytes.chunks(CHUNK_LEN) {
+        decoder.feed(chunk);
+        while let Some(frame) = decoder.next_frame().unwrap() {
+            count += 1;
+            checksum ^= process(&frame);
+        }
+    }
+    (count, checksum)
+}
+
+/// Reads each frame into a reused buffer with exact reads.
+pub fn framed_reader(bytes: &[u8]) -> (usize, u64) {
+    let mut reader = FramedReader::new(Cursor::new(bytes));
+    let (mut count, mut checksum) = (0, 0);
+    while let Some(frame) = reader.read_frame().unwrap() {
+        count += 1;
+        checksum ^= process(frame);
+    }
+    (count, checksum)
+}
+
+/// Reads chunks into a buffer and borrows each frame from it.
+pub fn zero_copy_buf_reader(bytes: &[u8]) -> (usize, u64) {
+    let mut reader = FramedBufReader::new(Cursor::new(bytes));
+    let (mut count, mut checksum) = (0, 0);
+    while let Some(frame) = reader.read_frame().unwrap() {
+        count += 1;
+        checksum ^= process(frame);
+    }
+    (count, checksum)
+}
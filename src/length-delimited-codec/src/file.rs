@@ -0,0 +1,490 @@
+//! File container of length delimited records with an index footer for random access.
+//!
+//! A file starts with a header identifying the format, followed by the records encoded as
+//! frames with the layout of the [`Builder`], and optionally ends with a footer indexing the
+//! offsets of the records:
+//!
+//! ```text
+//! +--------+---------+----------+-----+--------------+------------+---------+---------+
+//! | "LDCF" | version | record 0 | ... | record n - 1 | "LDCINDEX" | offsets | trailer |
+//! | 4      | 4       |          |     |              | 8          | 8 * n   | 24      |
+//! +--------+---------+----------+-----+--------------+------------+---------+---------+
+//!
+//! trailer: index offset (8) | number of records (8) | CRC-32 of the index (4) | "LDCX"
+//! ```
+//!
+//! All integers of the header and the footer are little endian, and the offsets are counted
+//! from the start of the file. The CRC-32 covers the index from its magic bytes to the last
+//! offset.
+//!
+//! The file does not record the frame layout; the same [`Builder`] must be used to write and
+//! read it.
+//!
+//! ```
+//! use length_delimited_codec::{FileReader, FileWriter};
+//! use std::io::Cursor;
+//!
+//! let mut writer = FileWriter::new(vec![]).unwrap();
+//! for record in [&b"first"[..], b"second", b"third"] {
+//!     writer.write_record(record).unwrap();
+//! }
+//! let bytes = writer.finish().unwrap();
+//!
+//! let mut reader = FileReader::new(Cursor::new(bytes)).unwrap();
+//! assert_eq!(reader.len(), 3);
+//! assert_eq!(reader.read_record(1).unwrap(), b"second");
+//!
+//! let backwards: Vec<_> = reader.records().rev().map(|x| x.unwrap()).collect();
+//! assert_eq!(backwards, vec![b"third".to_vec(), b"second".to_vec(), b"first".to_vec()]);
+//! ```
+
+use crate::{Builder, Decoder, Encoder, Error, FramedReader, checksum::crc32, config::Config};
+use std::{
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    iter::FusedIterator,
+    ops::Range,
+};
+
+/// Magic bytes at the start of the file.
+pub const MAGIC: [u8; 4] = *b"LDCF";
+
+/// Version of the file format written by [`FileWriter`].
+pub const VERSION: u32 = 1;
+
+/// Number of bytes of the file header; i.e., the offset of the first record.
+pub const HEADER_LEN: usize = 8;
+
+/// Magic bytes at the start of the index footer.
+pub const INDEX_MAGIC: [u8; 8] = *b"LDCINDEX";
+
+/// Magic bytes at the end of the file, closing the trailer of the index footer.
+pub const TRAILER_MAGIC: [u8; 4] = *b"LDCX";
+
+/// Number of bytes of the trailer at the end of the index footer.
+pub const TRAILER_LEN: usize = 24;
+
+/// Writes records to a file container, collecting their offsets for the index footer.
+///
+/// The file header is written on creation and the index footer by [`finish`](Self::finish).
+/// A file which is not finished can still be read with [`Builder::recover_file_reader`].
+#[derive(Debug)]
+pub struct FileWriter<W> {
+    encoder: Encoder,
+    writer: W,
+    buffer: Vec<u8>,
+    offsets: Vec<u64>,
+    /// Number of bytes written so far.
+    position: u64,
+}
+
+impl<W: Write> FileWriter<W> {
+    /// Creates a file writer with the default frame layout and writes the file header.
+    pub fn new(writer: W) -> io::Result<Self> {
+        Builder::new().new_file_writer(writer)
+    }
+
+    pub(crate) fn from_encoder(encoder: Encoder, mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        Ok(Self {
+            encoder,
+            writer,
+            buffer: vec![],
+            offsets: vec![],
+            position: HEADER_LEN as u64,
+        })
+    }
+
+    /// Writes the record of the `payload`.
+    ///
+    /// Nothing is written if the payload cannot be encoded, in which case an error of kind
+    /// `InvalidInput` is returned. If writing fails, a part of the record might have been
+    /// written and the file must be recovered.
+    pub fn write_record(&mut self, payload: &[u8]) -> io::Result<()> {
+        self.buffer.clear();
+        self.encoder
+            .encode(payload, &mut self.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.writer.write_all(&self.buffer)?;
+        self.offsets.push(self.position);
+        self.position += self.buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Number of records written so far.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns true if no record is written yet.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Writes the index footer, flushes and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&INDEX_MAGIC);
+        for offset in &self.offsets {
+            self.buffer.extend_from_slice(&offset.to_le_bytes());
+        }
+        let crc = crc32(&self.buffer);
+        self.buffer.extend_from_slice(&self.position.to_le_bytes());
+        self.buffer
+            .extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.buffer.extend_from_slice(&crc.to_le_bytes());
+        self.buffer.extend_from_slice(&TRAILER_MAGIC);
+
+        self.writer.write_all(&self.buffer)?;
+        self.finish_without_index()
+    }
+
+    /// Flushes and returns the underlying writer without writing the index footer.
+    ///
+    /// The records of the file can then only be read by [`Builder::recover_file_reader`].
+    pub fn finish_without_index(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+}
+
+/// Reads the records of a file container in any order by the offsets of its index.
+///
+/// Each record is read with a single seek, which is skipped when the records are read in
+/// order. Records are verified and stripped like the frames of a [`Decoder`].
+#[derive(Debug)]
+pub struct FileReader<R> {
+    decoder: Decoder,
+    reader: R,
+    offsets: Vec<u64>,
+    /// Offset of the end of the last record.
+    end: u64,
+    buffer: Vec<u8>,
+    /// Decompressed bytes of the current record, if compression is enabled.
+    unpacked: Vec<u8>,
+    /// Position of the underlying reader, if known.
+    cursor: Option<u64>,
+}
+
+impl<R: Read + Seek> FileReader<R> {
+    /// Creates a file reader with the default frame layout and reads the index footer.
+    pub fn new(reader: R) -> io::Result<Self> {
+        Builder::new().new_file_reader(reader)
+    }
+
+    pub(crate) fn from_config(config: Config, mut reader: R) -> io::Result<Self> {
+        read_file_header(&mut reader)?;
+        let (offsets, end) = read_index(&mut reader)?;
+        Ok(Self::from_parts(
+            Decoder::from_config(config),
+            reader,
+            offsets,
+            end,
+        ))
+    }
+
+    pub(crate) fn recover_from_config(config: Config, mut reader: R) -> io::Result<Self> {
+        read_file_header(&mut reader)?;
+
+        // the index footer, if any, is not a record; it is recognized by its magic bytes
+        let mut offsets = vec![];
+        let mut end = HEADER_LEN as u64;
+        let mut frames = FramedReader::from_config(config.clone(), BufReader::new(&mut reader));
+        loop {
+            match frames.read_raw_frame() {
+                Ok(Some((offset, frame))) if !frame.starts_with(&INDEX_MAGIC) => {
+                    let offset = (HEADER_LEN + offset) as u64;
+                    offsets.push(offset);
+                    end = offset + frame.len() as u64;
+                }
+                Ok(_) => break,
+                Err(e) if is_invalid_record(&e) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        drop(frames);
+
+        Ok(Self::from_parts(
+            Decoder::from_config(config),
+            reader,
+            offsets,
+            end,
+        ))
+    }
+
+    fn from_parts(decoder: Decoder, reader: R, offsets: Vec<u64>, end: u64) -> Self {
+        Self {
+            decoder,
+            reader,
+            offsets,
+            end,
+            buffer: vec![],
+            unpacked: vec![],
+            cursor: None,
+        }
+    }
+
+    /// Creates a reader of the same file through another `reader`, sharing the index without
+    /// reading it again; for instance, to read parts of the file on multiple threads.
+    pub fn with_reader<R2: Read + Seek>(&self, reader: R2) -> FileReader<R2> {
+        FileReader::from_parts(self.decoder.clone(), reader, self.offsets.clone(), self.end)
+    }
+
+    /// Number of records of the file.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns true if the file has no records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Offsets of the records from the start of the file.
+    pub fn offsets(&self) -> &[u64] {
+        &self.offsets
+    }
+
+    /// Offset of the end of the last record; i.e., the length of the file without the index
+    /// footer.
+    ///
+    /// After recovery, the file can be truncated to this length to drop an incomplete record,
+    /// after which new records can be appended.
+    pub fn records_end(&self) -> u64 {
+        self.end
+    }
+
+    /// Range of the bytes of the file holding the given range of `records`.
+    ///
+    /// When the file is in memory, the bytes of the range can be decoded by
+    /// [`Decoder::frames`] without copying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of the records of the file.
+    pub fn byte_range(&self, records: Range<usize>) -> Range<u64> {
+        assert!(records.start <= records.end && records.end <= self.len());
+        self.offset(records.start)..self.offset(records.end)
+    }
+
+    /// Splits the records into at most `num_parts` consecutive non-empty ranges holding
+    /// approximately the same number of bytes, to be read in parallel.
+    ///
+    /// ```
+    /// use length_delimited_codec::{FileReader, FileWriter};
+    /// use std::io::Cursor;
+    ///
+    /// let mut writer = FileWriter::new(vec![]).unwrap();
+    /// for i in 0..100u32 {
+    ///     writer.write_record(&i.to_le_bytes()).unwrap();
+    /// }
+    /// let bytes = writer.finish().unwrap();
+    ///
+    /// let reader = FileReader::new(Cursor::new(&bytes)).unwrap();
+    /// let sums: Vec<u32> = std::thread::scope(|s| {
+    ///     let handles: Vec<_> = reader
+    ///         .split(4)
+    ///         .into_iter()
+    ///         .map(|range| {
+    ///             let mut reader = reader.with_reader(Cursor::new(&bytes));
+    ///             s.spawn(move || {
+    ///                 let records = reader.records_in(range);
+    ///                 records.map(|x| u32::from_le_bytes(x.unwrap().try_into().unwrap())).sum()
+    ///             })
+    ///         })
+    ///         .collect();
+    ///     handles.into_iter().map(|x| x.join().unwrap()).collect()
+    /// });
+    /// assert_eq!(sums.len(), 4);
+    /// assert_eq!(sums.iter().sum::<u32>(), (0..100).sum::<u32>());
+    /// ```
+    pub fn split(&self, num_parts: usize) -> Vec<Range<usize>> {
+        let Some(&first) = self.offsets.first() else {
+            return vec![];
+        };
+        let num_parts = num_parts.max(1) as u64;
+        let total = self.end - first;
+
+        let mut ranges = vec![];
+        let mut start = 0;
+        for part in 1..=num_parts {
+            let boundary = first + (total as u128 * part as u128 / num_parts as u128) as u64;
+            let end = match part == num_parts {
+                true => self.len(),
+                false => self.offsets.partition_point(|x| *x < boundary),
+            };
+            if end > start {
+                ranges.push(start..end);
+                start = end;
+            }
+        }
+        ranges
+    }
+
+    /// Reads the record with the given `index`.
+    pub fn read_record(&mut self, index: usize) -> io::Result<&[u8]> {
+        if index >= self.len() {
+            let len = self.len();
+            return Err(Error::RecordOutOfRange { index, len }.into());
+        }
+
+        let start = self.offsets[index];
+        let len = (self.offset(index + 1) - start) as usize;
+        if self.cursor.take() != Some(start) {
+            self.reader.seek(SeekFrom::Start(start))?;
+        }
+        self.buffer.resize(len, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+        self.cursor = Some(start + len as u64);
+
+        let (frame, _) = self
+            .decoder
+            .decode_frame_with(&self.buffer, &mut self.unpacked)?;
+        Ok(frame)
+    }
+
+    /// Returns an iterator over all records, which can also be iterated backwards.
+    pub fn records(&mut self) -> Records<'_, R> {
+        let len = self.len();
+        self.records_in(0..len)
+    }
+
+    /// Returns an iterator over the given range of `records`, which can also be iterated
+    /// backwards.
+    pub fn records_in(&mut self, records: Range<usize>) -> Records<'_, R> {
+        Records {
+            reader: self,
+            records,
+        }
+    }
+
+    /// Offset of the record with the given `index`, or the end of the records if the index
+    /// is the number of records.
+    fn offset(&self, index: usize) -> u64 {
+        self.offsets.get(index).copied().unwrap_or(self.end)
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.cursor = None;
+        &mut self.reader
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Iterator over a range of the records of a [`FileReader`].
+///
+/// Created by [`FileReader::records`] and [`FileReader::records_in`].
+#[derive(Debug)]
+pub struct Records<'a, R> {
+    reader: &'a mut FileReader<R>,
+    records: Range<usize>,
+}
+
+impl<R: Read + Seek> Iterator for Records<'_, R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.records.next()?;
+        Some(self.reader.read_record(index).map(<[u8]>::to_vec))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+impl<R: Read + Seek> DoubleEndedIterator for Records<'_, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.records.next_back()?;
+        Some(self.reader.read_record(index).map(<[u8]>::to_vec))
+    }
+}
+
+impl<R: Read + Seek> ExactSizeIterator for Records<'_, R> {}
+
+impl<R: Read + Seek> FusedIterator for Records<'_, R> {}
+
+/// Returns true if the error ends the records of a file being recovered, rather than being
+/// an error of the underlying reader.
+fn is_invalid_record(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData
+    )
+}
+
+fn read_file_header<R: Read + Seek>(reader: &mut R) -> io::Result<()> {
+    let mut header = [0; HEADER_LEN];
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_exact(&mut header).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => Error::InvalidFileHeader.into(),
+        _ => e,
+    })?;
+    if header[..4] != MAGIC {
+        return Err(Error::InvalidFileHeader.into());
+    }
+    match u32::from_le_bytes(header[4..].try_into().expect("4 bytes")) {
+        VERSION => Ok(()),
+        version => Err(Error::UnsupportedFileVersion { version }.into()),
+    }
+}
+
+/// Reads the index footer, returning the offsets of the records and the end of the last one.
+fn read_index<R: Read + Seek>(reader: &mut R) -> io::Result<(Vec<u64>, u64)> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    if file_len < (HEADER_LEN + TRAILER_LEN) as u64 {
+        return Err(Error::MissingFileIndex.into());
+    }
+
+    let mut trailer = [0; TRAILER_LEN];
+    reader.seek(SeekFrom::Start(file_len - TRAILER_LEN as u64))?;
+    reader.read_exact(&mut trailer)?;
+    if trailer[20..] != TRAILER_MAGIC {
+        return Err(Error::MissingFileIndex.into());
+    }
+    let index_offset = u64::from_le_bytes(trailer[..8].try_into().expect("8 bytes"));
+    let num_records = u64::from_le_bytes(trailer[8..16].try_into().expect("8 bytes"));
+    let crc = u32::from_le_bytes(trailer[16..20].try_into().expect("4 bytes"));
+
+    let index_len = num_records
+        .checked_mul(8)
+        .and_then(|x| x.checked_add(INDEX_MAGIC.len() as u64));
+    let index_end = index_len.and_then(|x| x.checked_add(index_offset));
+    if index_offset < HEADER_LEN as u64 || index_end != Some(file_len - TRAILER_LEN as u64) {
+        return Err(Error::CorruptFileIndex.into());
+    }
+
+    let mut index = vec![0; index_len.expect("index_end is valid") as usize];
+    reader.seek(SeekFrom::Start(index_offset))?;
+    reader.read_exact(&mut index)?;
+    if index[..INDEX_MAGIC.len()] != INDEX_MAGIC || crc32(&index) != crc {
+        return Err(Error::CorruptFileIndex.into());
+    }
+
+    let offsets: Vec<u64> = index[INDEX_MAGIC.len()..]
+        .chunks_exact(8)
+        .map(|x| u64::from_le_bytes(x.try_into().expect("8 bytes")))
+        .collect();
+    let is_valid = offsets.first().is_none_or(|x| *x == HEADER_LEN as u64)
+        && offsets.windows(2).all(|x| x[0] < x[1])
+        && offsets.last().is_none_or(|x| *x < index_offset);
+    match is_valid {
+        true => Ok((offsets, index_offset)),
+        false => Err(Error::CorruptFileIndex.into()),
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    Builder, Error,
+    config::{Config, FrameHeader, Position, StreamState as State},
+};
+
+/// Resumable decoder of frames arriving in arbitrary fragments, which buffers them in a
+/// buffer provided by the caller rather than allocating.
+///
+/// Received bytes are either [`feed`](FixedStreamDecoder::feed)'d, which copies as many of
+/// them as fit in the buffer, or read directly into
+/// [`unfilled_mut`](FixedStreamDecoder::unfilled_mut) and then
+/// [`commit`](FixedStreamDecoder::commit)ted. Complete frames are pulled by
+/// [`next_frame`](FixedStreamDecoder::next_frame) as soon as all of their bytes are available, borrowed
+/// from the buffer.
+///
+/// Each frame must fit in the buffer. Frames which do not are treated as frames exceeding
+/// the maximum frame length, except that they fail with [`Error::BufferTooSmall`]: with
+/// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard), they are skipped without
+/// being buffered.
+///
+/// With compression, the frames are yielded as stored, as by
+/// [`Decoder::frames`](crate::Decoder::frames).
+///
+/// ```
+/// use length_delimited_codec::{Encoder, FixedStreamDecoder};
+///
+/// let encoder = Encoder::new();
+/// let mut bytes = [0; 32];
+/// let mut len = encoder.encode_to_slice(b"hello", &mut bytes).unwrap();
+/// len += encoder.encode_to_slice(b"world", &mut bytes[len..]).unwrap();
+///
+/// let mut buffer = [0; 16];
+/// let mut decoder = FixedStreamDecoder::new(&mut buffer);
+/// assert_eq!(decoder.feed(&bytes[..len]), 16); // the buffer holds 16 of the 18 bytes
+/// assert_eq!(decoder.next_frame(), Ok(Some(&b"hello"[..])));
+/// assert_eq!(decoder.next_frame(), Ok(None));
+///
+/// assert_eq!(decoder.feed(&bytes[16..len]), 2);
+/// assert_eq!(decoder.next_frame(), Ok(Some(&b"world"[..])));
+/// assert_eq!(decoder.finish(), Ok(()));
+/// ```
+#[derive(Debug)]
+pub struct FixedStreamDecoder<'a> {
+    config: Config,
+    buffer: &'a mut [u8],
+    /// Position of the first byte of the current frame in the buffer.
+    position: usize,
+    /// Number of bytes at the start of the buffer holding received bytes.
+    len: usize,
+    state: State,
+    /// Position of the current frame in the stream.
+    stream_position: Position,
+    num_discarded: usize,
+}
+
+impl<'a> FixedStreamDecoder<'a> {
+    /// Creates a decoder with the default frame layout buffering the received bytes in the
+    /// `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Builder::new().new_fixed_stream_decoder(buffer)
+    }
+
+    pub(crate) fn from_config(config: Config, buffer: &'a mut [u8]) -> Self {
+        Self {
+            config,
+            buffer,
+            position: 0,
+            len: 0,
+            state: State::Header,
+            stream_position: Position::default(),
+            num_discarded: 0,
+        }
+    }
+
+    /// Number of bytes of the buffer, which bounds the length of the frames.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of received bytes which are not yet yielded as part of a frame.
+    pub fn buffered_len(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Number of oversized frames skipped so far with
+    /// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard).
+    pub fn num_discarded(&self) -> usize {
+        self.num_discarded
+    }
+
+    /// Appends the longest prefix of the `chunk` of received bytes which fits in the buffer,
+    /// and returns its length.
+    ///
+    /// The bytes of an oversized frame being discarded are dropped without taking room in the
+    /// buffer. Unless [`next_frame`](FixedStreamDecoder::next_frame) fails, pulling the
+    /// complete frames makes room for more bytes.
+    pub fn feed(&mut self, chunk: &[u8]) -> usize {
+        let skipped = self.skip_discarded(chunk.len());
+        let chunk = &chunk[skipped..];
+        let unfilled = self.unfilled_mut();
+        let len = chunk.len().min(unfilled.len());
+        unfilled[..len].copy_from_slice(&chunk[..len]);
+        self.len += len;
+        skipped + len
+    }
+
+    /// Returns the free part of the buffer, so that received bytes can be read into it
+    /// without being copied, for instance, by a peripheral; the bytes are appended by
+    /// [`commit`](FixedStreamDecoder::commit).
+    pub fn unfilled_mut(&mut self) -> &mut [u8] {
+        // the bytes of the yielded frames are dropped only once room is needed
+        if self.position > 0 {
+            self.buffer.copy_within(self.position..self.len, 0);
+            self.len -= self.position;
+            self.position = 0;
+        }
+        &mut self.buffer[self.len..]
+    }
+
+    /// Appends the first `len` bytes of [`unfilled_mut`](FixedStreamDecoder::unfilled_mut),
+    /// into which received bytes are written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` exceeds the length of the unfilled part of the buffer.
+    pub fn commit(&mut self, len: usize) {
+        assert!(
+            len <= self.buffer.len() - self.len,
+            "committed length {len} exceeds the {} unfilled bytes",
+            self.buffer.len() - self.len
+        );
+        self.len += len;
+        self.position += self.skip_discarded(len);
+    }
+
+    /// Returns the next complete frame borrowed from the buffer, or None if more bytes are
+    /// required to complete it.
+    ///
+    /// Returns an error if the header of the next frame is invalid, for instance, when its
+    /// length exceeds the maximum frame length or the frame does not fit in the buffer, and
+    /// oversized frames are not discarded.
+    ///
+    /// A frame whose checksum does not match is consumed before the error is returned, so
+    /// that decoding can continue with the next frame.
+    pub fn next_frame(&mut self) -> Result<Option<&[u8]>, Error> {
+        loop {
+            let available = &self.buffer[self.position..self.len];
+
+            let header = match self.state {
+                State::Body(header) => header,
+                State::Discard { .. } => return Ok(None),
+                State::Header => match self.config.parse_header(available) {
+                    Ok(header) => match self.check_length(header) {
+                        Ok(header) => header,
+                        Err(_) if self.config.discard_oversized() => {
+                            self.discard(header);
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    },
+                    Err(Error::TruncatedHeader { .. }) => return Ok(None),
+                    Err(e) => return Err(e),
+                },
+            };
+
+            if available.len() < header.frame_len() {
+                self.state = State::Body(header);
+                return Ok(None);
+            }
+
+            let start = self.position;
+            let position = self.stream_position;
+            self.position += header.frame_len();
+            self.stream_position.advance(header);
+            self.state = State::Header;
+
+            let frame = &self.buffer[start..self.position];
+            self.config.verify(frame, header, position)?;
+            return Ok(Some(&frame[self.config.yielded_range(header)]));
+        }
+    }
+
+    /// Returns the `header` back if its body length does not exceed the maximum frame length
+    /// and the frame fits in the buffer.
+    fn check_length(&self, header: FrameHeader) -> Result<FrameHeader, Error> {
+        let header = self.config.check_length(header)?;
+        match header.frame_len() > self.buffer.len() {
+            true => Err(Error::BufferTooSmall {
+                needed: header.frame_len(),
+                available: self.buffer.len(),
+            }),
+            false => Ok(header),
+        }
+    }
+
+    /// Skips the buffered bytes of the oversized frame with the given `header`, and the rest
+    /// of it as it is received.
+    fn discard(&mut self, header: FrameHeader) {
+        let skipped = header.frame_len().min(self.buffered_len());
+        self.position += skipped;
+        self.stream_position.advance(header);
+        self.num_discarded += 1;
+        self.state = match header.frame_len() - skipped {
+            0 => State::Header,
+            remaining => State::Discard { header, remaining },
+        };
+    }
+
+    /// Returns the number of the next `len` received bytes which belong to the oversized
+    /// frame being discarded, if any.
+    fn skip_discarded(&mut self, len: usize) -> usize {
+        let State::Discard { header, remaining } = self.state else {
+            return 0;
+        };
+        let skipped = remaining.min(len);
+        self.state = match remaining - skipped {
+            0 => State::Header,
+            remaining => State::Discard { header, remaining },
+        };
+        skipped
+    }
+
+    /// Returns the error of the incomplete frame, if any, at the end of the stream.
+    pub fn finish(&self) -> Result<(), Error> {
+        let available = &self.buffer[self.position..self.len];
+        let (header, available) = match self.state {
+            State::Header if available.is_empty() => return Ok(()),
+            State::Header => (self.config.parse_header(available)?, available.len()),
+            State::Body(header) => (header, available.len()),
+            State::Discard { header, remaining } => (header, header.frame_len() - remaining),
+        };
+        match available < header.frame_len() {
+            true => Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: available - header.header_len,
+            }),
+            false => Ok(()),
+        }
+    }
+}
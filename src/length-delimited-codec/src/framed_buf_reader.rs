@@ -0,0 +1,214 @@
+use crate::{
+    BufferPool, Builder, Error, PooledBuffer,
+    config::{Config, FrameHeader, Position},
+};
+use std::io::{self, Read};
+
+/// Default capacity of the buffer of a [`FramedBufReader`], 64 KiB.
+pub const DEFAULT_BUF_CAPACITY: usize = 64 * 1024;
+
+/// Reads frames from a reader through a large buffer, yielding frames borrowed from the buffer.
+///
+/// The buffer is refilled with as many bytes as the reader provides, and the frames which are
+/// complete in the buffer are yielded without being copied. Only the bytes of a frame spanning
+/// the end of the buffer are moved to its beginning before the refill. The buffer grows if a
+/// frame is larger than its capacity.
+///
+/// Unlike [`FramedReader`](crate::FramedReader), this reader reads ahead of the current frame.
+///
+/// ```
+/// use length_delimited_codec::{Encoder, FramedBufReader};
+///
+/// let mut bytes = vec![];
+/// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+/// Encoder::new().encode(b"world", &mut bytes).unwrap();
+///
+/// let mut reader = FramedBufReader::new(bytes.as_slice());
+/// assert_eq!(reader.read_frame().unwrap(), Some(&b"hello"[..]));
+/// assert_eq!(reader.read_frame().unwrap(), Some(&b"world"[..]));
+/// assert_eq!(reader.read_frame().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct FramedBufReader<R> {
+    config: Config,
+    reader: R,
+    buffer: Vec<u8>,
+    /// Range of the buffered bytes which are not yet consumed.
+    start: usize,
+    end: usize,
+    /// Position of the next frame in the stream.
+    stream_position: Position,
+    num_discarded: usize,
+    /// Decompressed bytes of the current frame, if compression is enabled.
+    unpacked: Vec<u8>,
+}
+
+impl<R: Read> FramedBufReader<R> {
+    /// Creates a buffered framed reader with the default frame layout and buffer capacity.
+    pub fn new(reader: R) -> Self {
+        Builder::new().new_framed_buf_reader(reader)
+    }
+
+    pub(crate) fn from_config(config: Config, capacity: usize, reader: R) -> Self {
+        Self {
+            config,
+            reader,
+            buffer: vec![0; capacity.max(1)],
+            start: 0,
+            end: 0,
+            stream_position: Position::default(),
+            num_discarded: 0,
+            unpacked: vec![],
+        }
+    }
+
+    /// Reads the next frame and returns it without its first `num_skip` bytes.
+    ///
+    /// Returns None if the reader is at its end at a frame boundary, and an error of kind
+    /// `UnexpectedEof` if it ends in the middle of a frame. `Interrupted` reads are retried.
+    ///
+    /// A frame whose checksum does not match, or whose body fails to decompress, is consumed
+    /// before the error of kind `InvalidData` is returned, so that reading can continue with
+    /// the next frame.
+    pub fn read_frame(&mut self) -> io::Result<Option<&[u8]>> {
+        loop {
+            let available = &self.buffer[self.start..self.end];
+            let needed = match self.config.parse_header(available) {
+                Ok(header) => match self.config.check_length(header) {
+                    Ok(header) if available.len() >= header.frame_len() => {
+                        let frame = self.start..(self.start + header.frame_len());
+                        let position = self.stream_position;
+                        self.start = frame.end;
+                        self.stream_position.advance(header);
+
+                        let frame = &self.buffer[frame];
+                        self.config.verify(frame, header, position)?;
+                        let frame = self.config.yielded(frame, header, &mut self.unpacked)?;
+                        return Ok(Some(frame));
+                    }
+                    Ok(header) => header.frame_len(),
+                    Err(_) if self.config.discard_oversized() => {
+                        self.skip(header)?;
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                },
+                Err(Error::TruncatedHeader { needed, .. }) => needed,
+                Err(e) => return Err(e.into()),
+            };
+
+            if !self.refill(needed)? {
+                return self.end_of_stream();
+            }
+        }
+    }
+
+    /// Reads the next frame into a buffer of the `pool`, which returns to the pool once the
+    /// frame is dropped; see [`read_frame`](FramedBufReader::read_frame).
+    ///
+    /// Unlike frames borrowed from the reader, pooled frames can be kept or sent to other
+    /// threads while reading continues, without allocating a buffer for each frame.
+    ///
+    /// Since the reader reads ahead of the current frame, each frame is copied from its
+    /// buffer into the pooled buffer; [`FramedReader`](crate::FramedReader) reads the body
+    /// directly into the pooled buffer instead.
+    pub fn read_pooled_frame(&mut self, pool: &BufferPool) -> io::Result<Option<PooledBuffer>> {
+        Ok(self.read_frame()?.map(|frame| {
+            let mut buffer = pool.get();
+            buffer.extend_from_slice(frame);
+            buffer
+        }))
+    }
+
+    /// Reads more bytes, making room for a frame of `frame_len` bytes starting at the first
+    /// unconsumed byte; returns false if the reader is at its end.
+    fn refill(&mut self, frame_len: usize) -> io::Result<bool> {
+        if self.start + frame_len > self.buffer.len() {
+            // the only copy: the partial frame is moved to the beginning of the buffer
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+            if frame_len > self.buffer.len() {
+                self.buffer.resize(frame_len, 0);
+            }
+        }
+
+        loop {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => return Ok(false),
+                Ok(n) => {
+                    self.end += n;
+                    return Ok(true);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Drops the oversized frame with the given `header`, reading its bytes which are not yet
+    /// buffered without buffering them.
+    fn skip(&mut self, header: FrameHeader) -> io::Result<()> {
+        let buffered = (self.end - self.start).min(header.frame_len());
+        self.start += buffered;
+
+        let expected = (header.frame_len() - buffered) as u64;
+        let skipped = io::copy(&mut self.reader.by_ref().take(expected), &mut io::sink())?;
+        match skipped < expected {
+            true => Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: buffered + skipped as usize - header.header_len,
+            }
+            .into()),
+            false => {
+                self.stream_position.advance(header);
+                self.num_discarded += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Result of reaching the end of the reader with the unconsumed bytes in the buffer.
+    fn end_of_stream(&self) -> io::Result<Option<&[u8]>> {
+        let available = &self.buffer[self.start..self.end];
+        match available.is_empty() {
+            true => Ok(None),
+            false => {
+                let header = self.config.parse_header(available)?;
+                Err(Error::TruncatedPayload {
+                    expected: header.body_len,
+                    available: available.len() - header.header_len,
+                }
+                .into())
+            }
+        }
+    }
+
+    /// Number of oversized frames skipped so far with
+    /// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard).
+    pub fn num_discarded(&self) -> usize {
+        self.num_discarded
+    }
+
+    /// Current capacity of the buffer, which grows to fit the largest frame read.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader loses the bytes buffered ahead.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the underlying reader, dropping the bytes buffered ahead.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
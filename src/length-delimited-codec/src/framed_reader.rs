@@ -0,0 +1,260 @@
+use crate::{
+    BufferPool, Builder, Error, PooledBuffer,
+    config::{Config, FrameHeader, Position},
+};
+use std::io::{self, Read};
+
+/// Reads whole frames from a reader such as a file, pipe or `TcpStream`.
+///
+/// Frames are read into a buffer which is reused across frames. The reader never reads
+/// beyond the end of the current frame; since this requires small reads for the length
+/// field, unbuffered readers are better wrapped in a [`std::io::BufReader`].
+///
+/// ```
+/// use length_delimited_codec::{Encoder, FramedReader};
+///
+/// let mut bytes = vec![];
+/// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+/// Encoder::new().encode(b"world", &mut bytes).unwrap();
+///
+/// let mut reader = FramedReader::new(bytes.as_slice());
+/// assert_eq!(reader.read_frame().unwrap(), Some(&b"hello"[..]));
+/// assert_eq!(reader.read_frame().unwrap(), Some(&b"world"[..]));
+/// assert_eq!(reader.read_frame().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct FramedReader<R> {
+    config: Config,
+    reader: R,
+    buffer: Vec<u8>,
+    /// Position of the next frame in the stream.
+    stream_position: Position,
+    num_discarded: usize,
+    /// Decompressed bytes of the current frame, if compression is enabled.
+    unpacked: Vec<u8>,
+}
+
+impl<R: Read> FramedReader<R> {
+    /// Creates a framed reader with the default frame layout.
+    pub fn new(reader: R) -> Self {
+        Builder::new().new_framed_reader(reader)
+    }
+
+    pub(crate) fn from_config(config: Config, reader: R) -> Self {
+        Self {
+            config,
+            reader,
+            buffer: vec![],
+            stream_position: Position::default(),
+            num_discarded: 0,
+            unpacked: vec![],
+        }
+    }
+
+    /// Reads the next frame and returns it without its first `num_skip` bytes.
+    ///
+    /// Returns None if the reader is at its end at a frame boundary, and an error of kind
+    /// `UnexpectedEof` if it ends in the middle of a frame. `Interrupted` reads are retried.
+    ///
+    /// A frame whose checksum does not match, or whose body fails to decompress, is consumed
+    /// before the error of kind `InvalidData` is returned, so that reading can continue with
+    /// the next frame.
+    ///
+    /// With [`OversizePolicy::Discard`](crate::OversizePolicy::Discard), oversized frames are
+    /// skipped by reading their bytes without buffering them.
+    pub fn read_frame(&mut self) -> io::Result<Option<&[u8]>> {
+        Ok(match self.read_header_and_body()? {
+            Some((_, header)) => Some(self.config.yielded(
+                &self.buffer,
+                header,
+                &mut self.unpacked,
+            )?),
+            None => None,
+        })
+    }
+
+    /// Reads the next frame into a buffer of the `pool`, which returns to the pool once the
+    /// frame is dropped; see [`read_frame`](FramedReader::read_frame).
+    ///
+    /// Unlike frames borrowed from the reader, pooled frames can be kept or sent to other
+    /// threads while reading continues, without allocating a buffer for each frame.
+    ///
+    /// The body is read directly into the pooled buffer, and with compression, decompressed
+    /// into it; only the header is read into the buffer of the reader.
+    pub fn read_pooled_frame(&mut self, pool: &BufferPool) -> io::Result<Option<PooledBuffer>> {
+        if self.config.compression.is_some() {
+            return Ok(match self.read_header_and_body()? {
+                Some((_, header)) => {
+                    let mut buffer = pool.get();
+                    self.config.unpack(&self.buffer, header, &mut buffer)?;
+                    Some(buffer)
+                }
+                None => None,
+            });
+        }
+
+        let Some((filled, header)) = self.read_header()? else {
+            return Ok(None);
+        };
+
+        // the bytes before the yielded range stay in the buffer of the reader, and the
+        // yielded bytes of the header which are already read are copied to the pooled buffer
+        let yielded = self.config.yielded_range(header);
+        let frame_len = header.frame_len();
+        let mut filled = match filled < yielded.start {
+            true => self.fill(filled, yielded.start)?,
+            false => filled,
+        };
+        let mut buffer = pool.get();
+        if filled >= yielded.start {
+            buffer.extend_from_slice(&self.buffer[yielded.start..filled]);
+            let read = buffer.len();
+            buffer.resize(frame_len - yielded.start, 0);
+            let read = read + read_into(&mut self.reader, &mut buffer[read..])?;
+            filled = yielded.start + read;
+        }
+        if filled < frame_len {
+            return Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: filled - header.header_len,
+            }
+            .into());
+        }
+        let position = self.stream_position;
+        self.stream_position.advance(header);
+        self.config
+            .verify_split(&self.buffer[..yielded.start], &buffer, header, position)?;
+        buffer.truncate(yielded.len());
+        Ok(Some(buffer))
+    }
+
+    /// Reads the next frame and returns its offset in the stream together with all of its
+    /// bytes, including the header and the length field.
+    pub(crate) fn read_raw_frame(&mut self) -> io::Result<Option<(usize, &[u8])>> {
+        Ok(match self.read_header_and_body()? {
+            Some((position, header)) => Some((position.offset, &self.buffer[..header.frame_len()])),
+            None => None,
+        })
+    }
+
+    /// Reads the next frame into the buffer and returns its position and header.
+    fn read_header_and_body(&mut self) -> io::Result<Option<(Position, FrameHeader)>> {
+        let Some((filled, header)) = self.read_header()? else {
+            return Ok(None);
+        };
+
+        let frame_len = header.frame_len();
+        let filled = self.fill(filled, frame_len)?;
+        match filled < frame_len {
+            true => Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: filled - header.header_len,
+            }
+            .into()),
+            false => {
+                let position = self.stream_position;
+                self.stream_position.advance(header);
+                self.config.verify(&self.buffer, header, position)?;
+                Ok(Some((position, header)))
+            }
+        }
+    }
+
+    /// Reads the header of the next frame which is not discarded into the buffer, and returns
+    /// the number of bytes read into the buffer together with the header.
+    fn read_header(&mut self) -> io::Result<Option<(usize, FrameHeader)>> {
+        let mut filled = 0;
+
+        loop {
+            let header = match self.config.parse_header(&self.buffer[..filled]) {
+                Ok(header) => header,
+                Err(Error::TruncatedHeader { needed, .. }) => {
+                    filled = self.fill(filled, needed)?;
+                    if filled == 0 {
+                        return Ok(None);
+                    } else if filled < needed {
+                        return Err(Error::TruncatedHeader {
+                            needed,
+                            available: filled,
+                        }
+                        .into());
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            match self.config.check_length(header) {
+                Ok(header) => return Ok(Some((filled, header))),
+                Err(_) if self.config.discard_oversized() => {
+                    self.skip(header)?;
+                    filled = 0;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Reads and drops the body of the oversized frame with the given `header`.
+    fn skip(&mut self, header: FrameHeader) -> io::Result<()> {
+        let expected = header.body_len as u64;
+        let skipped = io::copy(&mut self.reader.by_ref().take(expected), &mut io::sink())?;
+        match skipped < expected {
+            true => Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: skipped as usize,
+            }
+            .into()),
+            false => {
+                self.stream_position.advance(header);
+                self.num_discarded += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Number of oversized frames skipped so far with
+    /// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard).
+    pub fn num_discarded(&self) -> usize {
+        self.num_discarded
+    }
+
+    /// Reads into the buffer, which holds `filled` bytes, until it holds `len` bytes or the
+    /// reader reaches its end; returns the number of bytes in the buffer.
+    fn fill(&mut self, filled: usize, len: usize) -> io::Result<usize> {
+        if self.buffer.len() < len {
+            self.buffer.resize(len, 0);
+        }
+        Ok(filled + read_into(&mut self.reader, &mut self.buffer[filled..len])?)
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Reads from the `reader` until `buf` is full or the reader reaches its end; returns the
+/// number of bytes read.
+fn read_into(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,210 @@
+use crate::{Builder, Encoder, Error};
+use std::{
+    io::{self, IoSlice, Write},
+    ops::Range,
+};
+
+/// Bodies up to this length are copied next to their headers, since an additional slice of
+/// a vectored write costs more than copying a small body.
+const MAX_COPIED_BODY_LEN: usize = 1024;
+
+/// Maximum number of frames encoded into a vectored write by
+/// [`write_frames`](FramedWriter::write_frames).
+const MAX_BATCH_LEN: usize = 256;
+
+/// Writes length delimited frames to a writer such as a file, pipe or `TcpStream`.
+///
+/// The header of each frame is encoded into a buffer which is reused across frames, and
+/// written together with the payload by vectored writes, without copying the payload; see
+/// [`Write::write_vectored`]. Short and `Interrupted` writes are retried. Payloads of at most
+/// 1 KiB are copied into the buffer instead.
+///
+/// Writers which do not implement vectored writes, as well as unbuffered writers of many
+/// small frames, benefit from [`write_frames`](FramedWriter::write_frames) or from being
+/// wrapped in a [`std::io::BufWriter`].
+///
+/// ```
+/// use length_delimited_codec::{Decoder, FramedWriter};
+///
+/// let mut writer = FramedWriter::new(vec![]);
+/// writer.write_frame(b"hello").unwrap();
+/// writer.write_frame(b"world").unwrap();
+///
+/// let frames = Decoder::new().decode_all(writer.get_ref()).unwrap();
+/// assert_eq!(frames, vec![b"hello".to_vec(), b"world".to_vec()]);
+/// ```
+#[derive(Debug)]
+pub struct FramedWriter<W> {
+    encoder: Encoder,
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+/// Part of the bytes of a batch of frames, written as one slice of a vectored write.
+enum Part<'a> {
+    Buffered(Range<usize>),
+    Body(&'a [u8]),
+}
+
+impl<W: Write> FramedWriter<W> {
+    /// Creates a framed writer with the default frame layout.
+    pub fn new(writer: W) -> Self {
+        Builder::new().new_framed_writer(writer)
+    }
+
+    pub(crate) fn from_encoder(encoder: Encoder, writer: W) -> Self {
+        Self {
+            encoder,
+            writer,
+            buffer: vec![],
+        }
+    }
+
+    /// Writes the frame of the `payload`.
+    ///
+    /// Nothing is written if the payload cannot be encoded, in which case an error of kind
+    /// `InvalidInput` is returned. If writing fails, a part of the frame might have been
+    /// written.
+    pub fn write_frame(&mut self, payload: &[u8]) -> io::Result<()> {
+        self.buffer.clear();
+        let packed = self.encoder.pack(payload).map_err(invalid_input)?;
+        match encode_split(&self.encoder, &packed, &mut self.buffer).map_err(invalid_input)? {
+            None => self.writer.write_all(&self.buffer),
+            Some((body, tail_start)) => {
+                let (head, tail) = self.buffer.split_at(tail_start);
+                let slices = &mut [IoSlice::new(head), IoSlice::new(body), IoSlice::new(tail)];
+                write_all_vectored(&mut self.writer, slices)
+            }
+        }
+    }
+
+    /// Writes the frames of all `payloads`, batching many frames into each vectored write.
+    ///
+    /// The result is identical to writing each frame with
+    /// [`write_frame`](FramedWriter::write_frame), while small frames cost a fraction of a
+    /// write each.
+    ///
+    /// If a payload cannot be encoded, the frames of the payloads preceding it are written
+    /// before an error of kind `InvalidInput` is returned.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Decoder, FramedWriter};
+    ///
+    /// let payloads = vec![b"small".to_vec(), vec![7; 1000], vec![]];
+    /// let mut writer = FramedWriter::new(vec![]);
+    /// writer.write_frames(&payloads).unwrap();
+    ///
+    /// let frames = Decoder::new().decode_all(writer.get_ref()).unwrap();
+    /// assert_eq!(frames, payloads);
+    /// ```
+    pub fn write_frames<P: AsRef<[u8]>>(&mut self, payloads: &[P]) -> io::Result<()> {
+        for batch in payloads.chunks(MAX_BATCH_LEN) {
+            // payloads are packed first, since the bodies are borrowed until the batch is written
+            let mut packed = Vec::with_capacity(batch.len());
+            let mut error = None;
+            for payload in batch {
+                match self.encoder.pack(payload.as_ref()) {
+                    Ok(x) => packed.push(x),
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            self.buffer.clear();
+            let mut parts = Vec::with_capacity(2 * packed.len() + 1);
+            for payload in &packed {
+                let start = self.buffer.len();
+                match encode_split(&self.encoder, payload, &mut self.buffer) {
+                    Ok(None) => push_buffered(&mut parts, start..self.buffer.len()),
+                    Ok(Some((body, tail_start))) => {
+                        push_buffered(&mut parts, start..tail_start);
+                        parts.push(Part::Body(body));
+                        push_buffered(&mut parts, tail_start..self.buffer.len());
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            let mut slices: Vec<_> = parts
+                .iter()
+                .map(|part| match part {
+                    Part::Buffered(range) => IoSlice::new(&self.buffer[range.clone()]),
+                    Part::Body(body) => IoSlice::new(body),
+                })
+                .collect();
+            write_all_vectored(&mut self.writer, &mut slices)?;
+
+            if let Some(e) = error {
+                return Err(invalid_input(e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn invalid_input(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+/// Appends the frame of the packed `payload` to `buffer`, except for a body which is not
+/// worth copying; such a body is returned together with the position of the bytes following
+/// it in the buffer.
+fn encode_split<'a>(
+    encoder: &Encoder,
+    payload: &'a [u8],
+    buffer: &mut Vec<u8>,
+) -> Result<Option<(&'a [u8], usize)>, Error> {
+    match payload.len() <= MAX_COPIED_BODY_LEN {
+        true => encoder.encode_packed(payload, buffer).map(|_| None),
+        false => encoder.encode_packed_split(payload, buffer).map(Some),
+    }
+}
+
+/// Appends the `range` of the buffer to the parts, merging it with a preceding range which
+/// it continues.
+fn push_buffered(parts: &mut Vec<Part>, range: Range<usize>) {
+    match parts.last_mut() {
+        _ if range.is_empty() => {}
+        Some(Part::Buffered(last)) if last.end == range.start => last.end = range.end,
+        _ => parts.push(Part::Buffered(range)),
+    }
+}
+
+/// Writes all bytes of the `slices`, retrying short and `Interrupted` writes.
+fn write_all_vectored<W: Write>(writer: &mut W, mut slices: &mut [IoSlice]) -> io::Result<()> {
+    IoSlice::advance_slices(&mut slices, 0);
+    while !slices.is_empty() {
+        match writer.write_vectored(slices) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => IoSlice::advance_slices(&mut slices, n),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
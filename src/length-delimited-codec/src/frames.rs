@@ -0,0 +1,49 @@
+use crate::{Decoder, Error, config::Position};
+
+/// Iterator over the frames of a buffer, borrowing each frame from the buffer.
+///
+/// Created by [`Decoder::frames`].
+#[derive(Clone, Debug)]
+pub struct Frames<'a> {
+    decoder: &'a Decoder,
+    src: &'a [u8],
+    position: Position,
+}
+
+impl<'a> Frames<'a> {
+    pub(crate) fn new(decoder: &'a Decoder, src: &'a [u8]) -> Self {
+        Self {
+            decoder,
+            src,
+            position: Position::default(),
+        }
+    }
+
+    /// Number of bytes of the buffer consumed by the frames yielded so far.
+    pub fn position(&self) -> usize {
+        self.position.offset
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<&'a [u8], Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let src = &self.src[self.position.offset..];
+        if src.is_empty() {
+            return None;
+        }
+
+        match self.decoder.locate(src, &mut self.position) {
+            Ok(Some((range, ..))) => Some(Ok(&src[range])),
+            Ok(None) => None,
+            Err(e) => {
+                // stops at the invalid frame
+                self.src = &self.src[..self.position.offset];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Frames<'_> {}
@@ -0,0 +1,124 @@
+//! Length delimited framing of byte payloads.
+//!
+//! Each frame is encoded as its length followed by its payload, which allows
+//! splitting a stream of bytes back into the frames it is composed of.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use length_delimited_codec::{Decoder, Encoder};
+//!
+//! let encoder = Encoder::new();
+//! let mut bytes = vec![];
+//! encoder.encode(b"hello", &mut bytes).unwrap();
+//! encoder.encode(b"world!", &mut bytes).unwrap();
+//!
+//! let frames = Decoder::new().decode_all(&bytes).unwrap();
+//! assert_eq!(frames, vec![b"hello".to_vec(), b"world!".to_vec()]);
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! The crate is `no_std`; without any features, it encodes frames into slices and decodes
+//! frames borrowed from slices, including with a [`FixedStreamDecoder`] over a buffer
+//! provided by the caller, without allocating.
+//!
+//! * `alloc` adds owned frames, the [`StreamDecoder`], [compression](Builder::compression),
+//!   and the typed, multiplexed and chunked codecs.
+//! * `std`, enabled by default, adds the readers and writers over `std::io`, the
+//!   [`BufferPool`], the [file container](file) and parallel encoding.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "async")]
+mod async_framed_reader;
+#[cfg(feature = "async")]
+mod async_framed_writer;
+#[cfg(feature = "std")]
+mod buffer_pool;
+mod builder;
+pub mod checksum;
+#[cfg(feature = "alloc")]
+pub mod chunk;
+#[cfg(feature = "alloc")]
+pub mod compression;
+mod config;
+mod decoder;
+mod encoder;
+mod endianness;
+mod error;
+#[cfg(feature = "std")]
+pub mod file;
+mod fixed_stream_decoder;
+#[cfg(feature = "std")]
+mod framed_buf_reader;
+#[cfg(feature = "std")]
+mod framed_reader;
+#[cfg(feature = "std")]
+mod framed_writer;
+mod frames;
+#[cfg(feature = "alloc")]
+pub mod mux;
+mod oversize_policy;
+mod resync;
+#[cfg(feature = "alloc")]
+pub mod serializer;
+#[cfg(feature = "alloc")]
+mod stream_decoder;
+#[cfg(feature = "alloc")]
+mod typed_decoder;
+#[cfg(feature = "alloc")]
+mod typed_encoder;
+pub mod varint;
+
+#[cfg(feature = "async")]
+pub use async_framed_reader::AsyncFramedReader;
+#[cfg(feature = "async")]
+pub use async_framed_writer::AsyncFramedWriter;
+#[cfg(feature = "std")]
+pub use buffer_pool::{BufferPool, PooledBuffer};
+pub use builder::Builder;
+pub use checksum::{Checksum, ChecksumPosition};
+#[cfg(feature = "alloc")]
+pub use chunk::{Chunker, Reassembler};
+#[cfg(feature = "alloc")]
+pub use compression::Compression;
+pub use decoder::Decoder;
+pub use encoder::Encoder;
+pub use endianness::Endianness;
+pub use error::Error;
+#[cfg(feature = "std")]
+pub use file::{FileReader, FileWriter};
+pub use fixed_stream_decoder::FixedStreamDecoder;
+#[cfg(feature = "std")]
+pub use framed_buf_reader::{DEFAULT_BUF_CAPACITY, FramedBufReader};
+#[cfg(feature = "std")]
+pub use framed_reader::FramedReader;
+#[cfg(feature = "std")]
+pub use framed_writer::FramedWriter;
+pub use frames::Frames;
+#[cfg(feature = "alloc")]
+pub use mux::{Demultiplexer, Multiplexer};
+pub use oversize_policy::OversizePolicy;
+pub use resync::{Resync, ResyncFrames};
+#[cfg(feature = "alloc")]
+pub use serializer::Serializer;
+#[cfg(feature = "alloc")]
+pub use stream_decoder::StreamDecoder;
+#[cfg(feature = "alloc")]
+pub use typed_decoder::TypedDecoder;
+#[cfg(feature = "alloc")]
+pub use typed_encoder::TypedEncoder;
+
+/// Default number of bytes of the length field prefixing each frame.
+pub const DEFAULT_LENGTH_FIELD_LEN: usize = 4;
+
+/// Default maximum length of the body of a frame, 8 MiB.
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// Maximum number of bytes of a [sync marker](Builder::sync_marker).
+pub const MAX_SYNC_MARKER_LEN: usize = 16;
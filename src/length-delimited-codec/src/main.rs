@@ -1,3 +1,98 @@
+use clap::{Parser, Subcommand};
+use std::{io, sync::OnceLock};
+
+mod cli;
+mod experiments;
+mod run_utils;
+
+/// Frames, unframes and inspects length delimited streams, or runs the benchmarks when no
+/// subcommand is given.
+#[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    bench: BenchArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    Frame(cli::frame::FrameArgs),
+    Unframe(cli::unframe::UnframeArgs),
+    Inspect(cli::inspect::InspectArgs),
+    Stats(cli::stats::StatsArgs),
+    /// Runs the benchmarks.
+    Bench(BenchArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct BenchArgs {
+    /// Amount of work (num times each frame is processed).
+    #[arg(long, default_value_t = 1)]
+    amount_of_work: usize,
+
+    /// Number of frames in the input of the experiments.
+    #[arg(long, default_value_t = 1_000_000)]
+    num_frames: usize,
+
+    /// Maximum length of the payloads; lengths are uniformly distributed in [0, max].
+    #[arg(long, default_value_t = 512)]
+    max_payload_len: usize,
+}
+
+fn args() -> &'static Args {
+    static ARGS: OnceLock<Args> = OnceLock::new();
+    ARGS.get_or_init(Args::parse)
+}
+
+fn bench_args() -> &'static BenchArgs {
+    match &args().command {
+        Some(Command::Bench(bench)) => bench,
+        _ => &args().bench,
+    }
+}
+
+pub fn amount_of_work() -> usize {
+    bench_args().amount_of_work
+}
+
+pub fn num_frames() -> usize {
+    bench_args().num_frames
+}
+
+pub fn max_payload_len() -> usize {
+    bench_args().max_payload_len
+}
+
+fn bench() -> io::Result<()> {
+    let seed = 42;
+
+    experiments::zero_copy::run_all(seed);
+    experiments::parallel_decode::run_all(seed);
+    experiments::parallel_encode::run_all(seed);
+    experiments::compression::run_all(seed);
+    experiments::vectored_io::run_all(seed);
+    Ok(())
+}
+
 fn main() {
-    println!("Hello, world!");
+    let result = match &args().command {
+        Some(Command::Frame(args)) => cli::frame::run(args),
+        Some(Command::Unframe(args)) => cli::unframe::run(args),
+        Some(Command::Inspect(args)) => cli::inspect::run(args),
+        Some(Command::Stats(args)) => cli::stats::run(args),
+        Some(Command::Bench(_)) | None => bench(),
+    };
+
+    match result {
+        // the output is closed early, for instance, by `head`
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+        Ok(()) => {}
+    }
 }
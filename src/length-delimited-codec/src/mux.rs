@@ -0,0 +1,328 @@
+//! Multiplexing of several logical channels over one stream of frames.
+//!
+//! Each message sent on a channel is split into fragments of at most `max_fragment_len`
+//! bytes, each fragment being the payload of a frame preceded by a mux header:
+//!
+//! ```text
+//! +-------+-------------------+---------------+
+//! | flags | channel id        | fragment data |
+//! | 1     | varint, 1-10      | 0..=max       |
+//! +-------+-------------------+---------------+
+//! ```
+//!
+//! The first fragment of a message is flagged by [`FLAG_START`], the following ones by
+//! [`FLAG_CONTINUATION`], and the last one additionally by [`FLAG_END`]; a message fitting
+//! in one fragment is flagged by both `FLAG_START` and `FLAG_END`. Fragments of messages on
+//! different channels may be interleaved arbitrarily, while the fragments of each channel
+//! arrive in order.
+
+use crate::{Encoder, Error, StreamDecoder, varint};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    vec,
+    vec::Vec,
+};
+
+/// Flag of the first fragment of a message.
+pub const FLAG_START: u8 = 0b001;
+
+/// Flag of a fragment following the first fragment of a message.
+pub const FLAG_CONTINUATION: u8 = 0b010;
+
+/// Flag of the last fragment of a message.
+pub const FLAG_END: u8 = 0b100;
+
+/// Fragment of a message sent on a channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fragment<'a> {
+    /// Id of the channel of the message.
+    pub channel: u64,
+    /// Flags of the position of the fragment in its message.
+    pub flags: u8,
+    /// Bytes of the message carried by the fragment.
+    pub data: &'a [u8],
+}
+
+/// Splits messages sent on channels into fragment frames.
+///
+/// ```
+/// use length_delimited_codec::Builder;
+///
+/// let builder = Builder::new();
+/// let mut mux = builder.new_multiplexer(4);
+///
+/// // the fragments of the two messages are interleaved
+/// let mut bytes = vec![];
+/// let first: Vec<_> = mux.fragments(1, b"hello world").collect();
+/// let second: Vec<_> = mux.fragments(2, b"bye").collect();
+/// mux.encode_fragment(&first[0], &mut bytes).unwrap();
+/// mux.encode_fragment(&second[0], &mut bytes).unwrap();
+/// mux.encode_fragment(&first[1], &mut bytes).unwrap();
+/// mux.encode_fragment(&first[2], &mut bytes).unwrap();
+///
+/// let mut demux = builder.new_demultiplexer(1024, 16);
+/// demux.feed(&bytes);
+/// assert_eq!(demux.next_message(), Ok(Some(2)));
+/// assert_eq!(demux.next_message(), Ok(Some(1)));
+/// assert_eq!(demux.next_message(), Ok(None));
+/// assert_eq!(demux.recv(1), Some(b"hello world".to_vec()));
+/// assert_eq!(demux.recv(2), Some(b"bye".to_vec()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Multiplexer {
+    encoder: Encoder,
+    max_fragment_len: usize,
+    /// Payload of the current fragment.
+    buffer: Vec<u8>,
+}
+
+impl Multiplexer {
+    pub(crate) fn from_encoder(encoder: Encoder, max_fragment_len: usize) -> Self {
+        assert!(max_fragment_len > 0, "fragments must carry at least a byte");
+        Self {
+            encoder,
+            max_fragment_len,
+            buffer: vec![],
+        }
+    }
+
+    /// Maximum number of bytes of a message carried by a fragment.
+    pub fn max_fragment_len(&self) -> usize {
+        self.max_fragment_len
+    }
+
+    /// Returns the fragments of the `message` sent on the `channel`, in order.
+    ///
+    /// An empty message is sent as a single empty fragment.
+    pub fn fragments<'a>(
+        &self,
+        channel: u64,
+        message: &'a [u8],
+    ) -> impl ExactSizeIterator<Item = Fragment<'a>> + use<'a> {
+        let num_fragments = message.len().div_ceil(self.max_fragment_len).max(1);
+        let max_fragment_len = self.max_fragment_len;
+        (0..num_fragments).map(move |i| {
+            let start = i * max_fragment_len;
+            let end = (start + max_fragment_len).min(message.len());
+            let position = match i {
+                0 => FLAG_START,
+                _ => FLAG_CONTINUATION,
+            };
+            let end_flag = match i + 1 == num_fragments {
+                true => FLAG_END,
+                false => 0,
+            };
+            Fragment {
+                channel,
+                flags: position | end_flag,
+                data: &message[start..end],
+            }
+        })
+    }
+
+    /// Appends the frame of the `fragment` to `dst`.
+    pub fn encode_fragment(&mut self, fragment: &Fragment, dst: &mut Vec<u8>) -> Result<(), Error> {
+        self.buffer.clear();
+        self.buffer.push(fragment.flags);
+        varint::encode(fragment.channel, &mut self.buffer);
+        self.buffer.extend_from_slice(fragment.data);
+        self.encoder.encode(&self.buffer, dst)
+    }
+
+    /// Appends the frames of all fragments of the `message` sent on the `channel` to `dst`.
+    pub fn encode(&mut self, channel: u64, message: &[u8], dst: &mut Vec<u8>) -> Result<(), Error> {
+        for fragment in self.fragments(channel, message) {
+            self.encode_fragment(&fragment, dst)?;
+        }
+        Ok(())
+    }
+}
+
+/// Routes the fragment frames arriving in arbitrary chunks to their channels, reassembling
+/// the messages of each channel into its queue.
+///
+/// Messages whose reassembled length exceeds `max_message_len` are rejected as soon as the
+/// limit is exceeded, without buffering the rest of their fragments.
+///
+/// A channel is tracked while it has a partial message, queued messages, or a dropped
+/// message whose fragments are being skipped. At most `max_channels` channels are tracked,
+/// which bounds the memory of the partial messages to `max_channels * max_message_len`
+/// bytes; fragments arriving on other channels are rejected until a channel is released by
+/// receiving its messages.
+#[derive(Clone, Debug)]
+pub struct Demultiplexer {
+    decoder: StreamDecoder,
+    max_message_len: usize,
+    max_channels: usize,
+    channels: BTreeMap<u64, Channel>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Channel {
+    /// Message whose last fragment is awaited.
+    partial: Option<Vec<u8>>,
+    /// Reassembled messages which are not yet received.
+    messages: VecDeque<Vec<u8>>,
+    /// Whether the fragments of the current message are dropped after an error.
+    dropping: bool,
+}
+
+impl Demultiplexer {
+    pub(crate) fn from_decoder(
+        decoder: StreamDecoder,
+        max_message_len: usize,
+        max_channels: usize,
+    ) -> Self {
+        Self {
+            decoder,
+            max_message_len,
+            max_channels,
+            channels: BTreeMap::new(),
+        }
+    }
+
+    /// Appends the `chunk` of received bytes.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.decoder.feed(chunk);
+    }
+
+    /// Routes the complete frames to their channels until a message is reassembled; returns
+    /// the channel of the message, which is queued to be [`recv`](Demultiplexer::recv)'d, or
+    /// None if more bytes are required.
+    ///
+    /// Returns an error if a frame or its mux header is invalid, a fragment does not continue
+    /// the message of its channel as flagged, a message exceeds the maximum message length,
+    /// or a fragment arrives on a new channel while the maximum number of channels is
+    /// tracked. The invalid fragment is consumed together with the partial message of its
+    /// channel, and the remaining fragments of a dropped message are skipped; so that
+    /// demultiplexing can continue with the following frames. No state is kept for a channel
+    /// whose fragment is rejected because of the maximum number of channels.
+    pub fn next_message(&mut self) -> Result<Option<u64>, Error> {
+        while let Some(frame) = self.decoder.next_frame()? {
+            if let Some(channel) = self.route(&frame)? {
+                return Ok(Some(channel));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Pops the oldest reassembled message of the `channel`.
+    pub fn recv(&mut self, channel: u64) -> Option<Vec<u8>> {
+        let state = self.channels.get_mut(&channel)?;
+        let message = state.messages.pop_front();
+        // the state of an idle channel is dropped, so that it does not outlive its use
+        if state.is_idle() {
+            self.channels.remove(&channel);
+        }
+        message
+    }
+
+    /// Number of channels with a partial message, queued messages or a message being dropped.
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Number of reassembled messages of the `channel` which are not yet received.
+    pub fn num_queued(&self, channel: u64) -> usize {
+        self.channels.get(&channel).map_or(0, |x| x.messages.len())
+    }
+
+    /// Returns the channels with queued messages in increasing order of their ids.
+    pub fn ready_channels(&self) -> impl Iterator<Item = u64> + '_ {
+        self.channels
+            .iter()
+            .filter(|(_, x)| !x.messages.is_empty())
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns an error if the stream cannot end here, since a frame or a message of a
+    /// channel is incomplete.
+    pub fn finish(&self) -> Result<(), Error> {
+        self.decoder.finish()?;
+        match self.channels.iter().find(|(_, x)| x.partial.is_some()) {
+            Some((channel, _)) => Err(Error::IncompleteMessage { channel: *channel }),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends the fragment of the `frame` to the message of its channel; returns the channel
+    /// if the message is complete.
+    fn route(&mut self, frame: &[u8]) -> Result<Option<u64>, Error> {
+        let (&flags, rest) = frame.split_first().ok_or(Error::InvalidMuxHeader)?;
+        let (channel, len) = varint::decode(rest).map_err(|_| Error::InvalidMuxHeader)?;
+        let data = &rest[len..];
+        let is_start = match flags & !FLAG_END {
+            FLAG_START => true,
+            FLAG_CONTINUATION => false,
+            _ => return Err(Error::InvalidMuxHeader),
+        };
+
+        if !self.channels.contains_key(&channel) && self.channels.len() >= self.max_channels {
+            return Err(Error::TooManyChannels {
+                channel,
+                max: self.max_channels,
+            });
+        }
+        let state = self.channels.entry(channel).or_default();
+        let result = state.push(channel, flags, is_start, data, self.max_message_len);
+        // a channel left idle, for instance by an error, is not tracked
+        if state.is_idle() {
+            self.channels.remove(&channel);
+        }
+        Ok(result?.then_some(channel))
+    }
+}
+
+impl Channel {
+    fn is_idle(&self) -> bool {
+        self.messages.is_empty() && self.partial.is_none() && !self.dropping
+    }
+
+    /// Appends the fragment `data` to the message of the `channel`; returns whether the
+    /// message is complete and queued.
+    fn push(
+        &mut self,
+        channel: u64,
+        flags: u8,
+        is_start: bool,
+        data: &[u8],
+        max_message_len: usize,
+    ) -> Result<bool, Error> {
+        if self.dropping {
+            match is_start {
+                true => self.dropping = false,
+                false => {
+                    self.dropping = flags & FLAG_END == 0;
+                    return Ok(false);
+                }
+            }
+        }
+
+        let partial = match (is_start, self.partial.take()) {
+            (true, None) => vec![],
+            (false, Some(partial)) => partial,
+            // a start interrupting a partial message, or a continuation without one
+            _ => {
+                self.dropping = flags & FLAG_END == 0;
+                return Err(Error::UnexpectedFragment { channel });
+            }
+        };
+
+        let len = partial.len() + data.len();
+        if len > max_message_len {
+            self.dropping = flags & FLAG_END == 0;
+            return Err(Error::MessageTooLarge {
+                len,
+                max: max_message_len,
+            });
+        }
+
+        let mut message = partial;
+        message.extend_from_slice(data);
+        match flags & FLAG_END != 0 {
+            true => self.messages.push_back(message),
+            false => self.partial = Some(message),
+        }
+        Ok(flags & FLAG_END != 0)
+    }
+}
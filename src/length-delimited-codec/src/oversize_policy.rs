@@ -0,0 +1,12 @@
+/// Action taken by the decoders on a frame longer than the maximum frame length.
+///
+/// In either case, the length is validated before any memory is allocated for the frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OversizePolicy {
+    /// Fails with [`Error::FrameTooLarge`](crate::Error::FrameTooLarge).
+    #[default]
+    Error,
+    /// Skips the bytes of the oversized frame without buffering them and continues with
+    /// the next frame.
+    Discard,
+}
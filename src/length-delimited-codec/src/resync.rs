@@ -0,0 +1,87 @@
+use crate::{Decoder, Error, config::Position};
+use core::ops::Range;
+
+/// Item of a decoding which resynchronizes on the sync markers after a corruption.
+///
+/// Created by [`Decoder::resync_frames`],
+/// [`StreamDecoder::next_resync`](crate::StreamDecoder::next_resync) and
+/// [`StreamDecoder::finish_resync`](crate::StreamDecoder::finish_resync).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resync<T> {
+    /// A valid frame.
+    Frame(T),
+    /// Bytes which are skipped since no valid frame starts in them.
+    Skipped {
+        /// Range of the skipped bytes in the buffer or stream.
+        range: Range<usize>,
+        /// Error of decoding the frame at the start of the range.
+        error: Error,
+    },
+}
+
+/// Iterator over the frames of a buffer which skips to the next sync marker whenever the
+/// frame at the current position is invalid, borrowing each frame from the buffer.
+///
+/// Created by [`Decoder::resync_frames`].
+#[derive(Clone, Debug)]
+pub struct ResyncFrames<'a> {
+    decoder: &'a Decoder,
+    src: &'a [u8],
+    position: Position,
+    /// Start of the bytes being skipped together with the error at the start.
+    skipping: Option<(usize, Error)>,
+}
+
+impl<'a> ResyncFrames<'a> {
+    pub(crate) fn new(decoder: &'a Decoder, src: &'a [u8]) -> Self {
+        Self {
+            decoder,
+            src,
+            position: Position::default(),
+            skipping: None,
+        }
+    }
+}
+
+impl<'a> Iterator for ResyncFrames<'a> {
+    type Item = Resync<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.position.offset;
+            let src = &self.src[start.min(self.src.len())..];
+            if src.is_empty() {
+                return self
+                    .skipping
+                    .take()
+                    .map(|(skip_start, error)| Resync::Skipped {
+                        range: skip_start..start,
+                        error,
+                    });
+            }
+
+            let mut position = self.position;
+            match self.decoder.locate(src, &mut position) {
+                Ok(found) => {
+                    // the skipped bytes are reported before the frame following them
+                    if let Some((skip_start, error)) = self.skipping.take() {
+                        return Some(Resync::Skipped {
+                            range: skip_start..start,
+                            error,
+                        });
+                    }
+                    self.position = position;
+                    if let Some((range, ..)) = found {
+                        return Some(Resync::Frame(&src[range]));
+                    }
+                }
+                Err(e) => {
+                    self.skipping.get_or_insert((start, e));
+                    self.position.offset += self.decoder.config().skip_to_sync_marker(src);
+                }
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for ResyncFrames<'_> {}
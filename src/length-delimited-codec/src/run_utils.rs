@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+pub fn run<F, L, T>(name: &'static str, fun: F, log: L)
+where
+    F: Fn() -> T,
+    L: Fn(T),
+{
+    println!("> {name}");
+    let start = Instant::now();
+
+    let result = fun();
+
+    let elapsed = start.elapsed();
+
+    println!("  elapsed = {elapsed:?}");
+    log(result);
+    println!();
+}
+
+/// Runs the experiment similar to [`run`], additionally reporting the throughput of
+/// processing `num_bytes` bytes in `num_frames` frames.
+pub fn run_throughput<F, L, T>(
+    name: &'static str,
+    num_bytes: usize,
+    num_frames: usize,
+    fun: F,
+    log: L,
+) where
+    F: Fn() -> T,
+    L: Fn(T),
+{
+    println!("> {name}");
+    let start = Instant::now();
+
+    let result = fun();
+
+    let elapsed = start.elapsed();
+    let seconds = elapsed.as_secs_f64();
+
+    println!("  elapsed = {elapsed:?}");
+    println!(
+        "  throughput = {:.3} GB/s, {:.0} frames/s",
+        num_bytes as f64 / seconds / 1e9,
+        num_frames as f64 / seconds
+    );
+    log(result);
+    println!();
+}
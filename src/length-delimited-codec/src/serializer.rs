@@ -0,0 +1,356 @@
+//! Serialization of the messages of the typed encoders and decoders.
+//!
+//! A [`Serializer`] converts messages to the payloads of frames and back. The built-in
+//! [`Binary`] serializer writes the types implementing [`FixedLayout`]; with the `json` and
+//! `bincode` features, [`Json`] and [`Bincode`] serialize the types implementing serde's
+//! `Serialize` and `Deserialize`.
+
+use crate::Error;
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Converts messages of type `T` to the payloads of frames and back.
+pub trait Serializer<T> {
+    /// Appends the serialized `message` to `dst`.
+    fn serialize(&self, message: &T, dst: &mut Vec<u8>) -> Result<(), Error>;
+
+    /// Deserializes a message from all bytes of `src`.
+    fn deserialize(&self, src: &[u8]) -> Result<T, Error>;
+}
+
+/// Type with a fixed binary layout, written by the [`Binary`] serializer.
+///
+/// Integers and floats are written in little endian with their own width, `usize` and
+/// `isize` as 8 bytes, `bool` as a byte of 0 or 1 and `char` as its 4-byte code point.
+/// Sequences and strings are preceded by their number of elements as 8 bytes, an `Option`
+/// by a byte of 0 or 1, while arrays and tuples are written as their elements.
+///
+/// ```
+/// use length_delimited_codec::{Error, serializer::FixedLayout};
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+///     label: String,
+/// }
+///
+/// impl FixedLayout for Point {
+///     fn write(&self, dst: &mut Vec<u8>) {
+///         self.x.write(dst);
+///         self.y.write(dst);
+///         self.label.write(dst);
+///     }
+///
+///     fn read(src: &mut &[u8]) -> Result<Self, Error> {
+///         Ok(Self {
+///             x: i32::read(src)?,
+///             y: i32::read(src)?,
+///             label: String::read(src)?,
+///         })
+///     }
+/// }
+/// ```
+pub trait FixedLayout: Sized {
+    /// Appends the bytes of the value to `dst`.
+    fn write(&self, dst: &mut Vec<u8>);
+
+    /// Reads a value from the beginning of `src`, advancing `src` past its bytes.
+    fn read(src: &mut &[u8]) -> Result<Self, Error>;
+}
+
+/// Serializer of the types implementing [`FixedLayout`].
+///
+/// ```
+/// use length_delimited_codec::{Builder, serializer::Binary};
+///
+/// let builder = Builder::new();
+/// let mut encoder = builder.new_typed_encoder(Binary);
+/// let mut bytes = vec![];
+/// encoder.encode(&(7u32, "seven".to_string()), &mut bytes).unwrap();
+///
+/// let decoder = builder.new_typed_decoder::<(u32, String), _>(Binary);
+/// let (message, _) = decoder.decode_frame(&bytes).unwrap();
+/// assert_eq!(message, (7, "seven".to_string()));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Binary;
+
+impl<T: FixedLayout> Serializer<T> for Binary {
+    fn serialize(&self, message: &T, dst: &mut Vec<u8>) -> Result<(), Error> {
+        message.write(dst);
+        Ok(())
+    }
+
+    fn deserialize(&self, mut src: &[u8]) -> Result<T, Error> {
+        let message = T::read(&mut src)?;
+        match src.is_empty() {
+            true => Ok(message),
+            false => Err(invalid(format!("{} trailing bytes", src.len()))),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidMessage {
+        reason: reason.into(),
+    }
+}
+
+/// Takes the first `len` bytes of `src`.
+fn take<'a>(src: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    match src.split_at_checked(len) {
+        Some((bytes, rest)) => {
+            *src = rest;
+            Ok(bytes)
+        }
+        None => Err(invalid(format!(
+            "{len} bytes are required but {} are left",
+            src.len()
+        ))),
+    }
+}
+
+fn read_array<const N: usize>(src: &mut &[u8]) -> Result<[u8; N], Error> {
+    Ok(take(src, N)?.try_into().expect("N bytes"))
+}
+
+/// Reads the number of elements of a sequence.
+fn read_len(src: &mut &[u8]) -> Result<usize, Error> {
+    let len = u64::from_le_bytes(read_array(src)?);
+    usize::try_from(len).map_err(|_| invalid(format!("length {len} does not fit in usize")))
+}
+
+macro_rules! impl_numbers {
+    ($($t:ty),*) => {
+        $(
+            impl FixedLayout for $t {
+                fn write(&self, dst: &mut Vec<u8>) {
+                    dst.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read(src: &mut &[u8]) -> Result<Self, Error> {
+                    Ok(Self::from_le_bytes(read_array(src)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_numbers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl FixedLayout for usize {
+    fn write(&self, dst: &mut Vec<u8>) {
+        (*self as u64).write(dst);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let value = u64::read(src)?;
+        Self::try_from(value).map_err(|_| invalid(format!("{value} does not fit in usize")))
+    }
+}
+
+impl FixedLayout for isize {
+    fn write(&self, dst: &mut Vec<u8>) {
+        (*self as i64).write(dst);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let value = i64::read(src)?;
+        Self::try_from(value).map_err(|_| invalid(format!("{value} does not fit in isize")))
+    }
+}
+
+impl FixedLayout for bool {
+    fn write(&self, dst: &mut Vec<u8>) {
+        dst.push(*self as u8);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        match u8::read(src)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(invalid(format!("{x} is not a bool"))),
+        }
+    }
+}
+
+impl FixedLayout for char {
+    fn write(&self, dst: &mut Vec<u8>) {
+        (*self as u32).write(dst);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let value = u32::read(src)?;
+        Self::from_u32(value).ok_or_else(|| invalid(format!("{value:#x} is not a char")))
+    }
+}
+
+impl FixedLayout for () {
+    fn write(&self, _: &mut Vec<u8>) {}
+
+    fn read(_: &mut &[u8]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl FixedLayout for String {
+    fn write(&self, dst: &mut Vec<u8>) {
+        self.len().write(dst);
+        dst.extend_from_slice(self.as_bytes());
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let len = read_len(src)?;
+        let bytes = take(src, len)?;
+        match core::str::from_utf8(bytes) {
+            Ok(x) => Ok(x.to_string()),
+            Err(e) => Err(invalid(e.to_string())),
+        }
+    }
+}
+
+impl<T: FixedLayout> FixedLayout for Vec<T> {
+    fn write(&self, dst: &mut Vec<u8>) {
+        self.len().write(dst);
+        for x in self {
+            x.write(dst);
+        }
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let len = read_len(src)?;
+        // each element takes at least a byte, unless it is zero-sized
+        let mut values = Vec::with_capacity(len.min(src.len()));
+        for _ in 0..len {
+            values.push(T::read(src)?);
+        }
+        Ok(values)
+    }
+}
+
+impl<T: FixedLayout> FixedLayout for Option<T> {
+    fn write(&self, dst: &mut Vec<u8>) {
+        match self {
+            None => dst.push(0),
+            Some(x) => {
+                dst.push(1);
+                x.write(dst);
+            }
+        }
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        match u8::read(src)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::read(src)?)),
+            x => Err(invalid(format!("{x} is not an option tag"))),
+        }
+    }
+}
+
+impl<T: FixedLayout> FixedLayout for Box<T> {
+    fn write(&self, dst: &mut Vec<u8>) {
+        self.as_ref().write(dst);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        T::read(src).map(Box::new)
+    }
+}
+
+impl<T: FixedLayout, const N: usize> FixedLayout for [T; N] {
+    fn write(&self, dst: &mut Vec<u8>) {
+        for x in self {
+            x.write(dst);
+        }
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        let mut values = Vec::with_capacity(N);
+        for _ in 0..N {
+            values.push(T::read(src)?);
+        }
+        Ok(values.try_into().ok().expect("N values"))
+    }
+}
+
+macro_rules! impl_tuples {
+    ($(($($t:ident),+)),*) => {
+        $(
+            impl<$($t: FixedLayout),+> FixedLayout for ($($t,)+) {
+                #[allow(non_snake_case)]
+                fn write(&self, dst: &mut Vec<u8>) {
+                    let ($($t,)+) = self;
+                    $($t.write(dst);)+
+                }
+
+                fn read(src: &mut &[u8]) -> Result<Self, Error> {
+                    Ok(($($t::read(src)?,)+))
+                }
+            }
+        )*
+    };
+}
+
+impl_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F)
+);
+
+/// Serializer of the serde types as JSON.
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl<T> Serializer<T> for Json
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    fn serialize(&self, message: &T, dst: &mut Vec<u8>) -> Result<(), Error> {
+        serde_json::to_writer(dst, message).map_err(|e| Error::SerializationFailed {
+            reason: e.to_string(),
+        })
+    }
+
+    fn deserialize(&self, src: &[u8]) -> Result<T, Error> {
+        serde_json::from_slice(src).map_err(|e| invalid(e.to_string()))
+    }
+}
+
+/// Serializer of the serde types in the standard bincode format, with varint integers.
+#[cfg(feature = "bincode")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl<T> Serializer<T> for Bincode
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    fn serialize(&self, message: &T, dst: &mut Vec<u8>) -> Result<(), Error> {
+        let config = bincode::config::standard();
+        bincode::serde::encode_into_std_write(message, dst, config)
+            .map(|_| ())
+            .map_err(|e| Error::SerializationFailed {
+                reason: e.to_string(),
+            })
+    }
+
+    fn deserialize(&self, src: &[u8]) -> Result<T, Error> {
+        let config = bincode::config::standard();
+        match bincode::serde::decode_from_slice(src, config) {
+            Ok((message, len)) if len == src.len() => Ok(message),
+            Ok((_, len)) => Err(invalid(format!("{} trailing bytes", src.len() - len))),
+            Err(e) => Err(invalid(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,361 @@
+#[cfg(feature = "std")]
+use crate::{BufferPool, PooledBuffer};
+use crate::{
+    Builder, Error, Resync,
+    config::{Config, FrameHeader, Position, StreamState as State},
+};
+use alloc::{vec, vec::Vec};
+
+/// Resumable decoder of frames arriving in arbitrary fragments.
+///
+/// Chunks of bytes are [`feed`](StreamDecoder::feed)'d as they arrive, and complete frames
+/// are pulled by [`next_frame`](StreamDecoder::next_frame) as soon as all of their bytes are
+/// available. The header of a frame is parsed once; while waiting for the rest of its body,
+/// the consumed bytes are not parsed again.
+///
+/// ```
+/// use length_delimited_codec::{Encoder, StreamDecoder};
+///
+/// let mut bytes = vec![];
+/// Encoder::new().encode(b"hello", &mut bytes).unwrap();
+///
+/// let mut decoder = StreamDecoder::new();
+/// decoder.feed(&bytes[..2]); // splits the length field
+/// assert_eq!(decoder.next_frame(), Ok(None));
+///
+/// decoder.feed(&bytes[2..]);
+/// assert_eq!(decoder.next_frame(), Ok(Some(b"hello".to_vec())));
+/// assert_eq!(decoder.next_frame(), Ok(None));
+/// assert_eq!(decoder.finish(), Ok(()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamDecoder {
+    config: Config,
+    buffer: Vec<u8>,
+    /// Position of the first byte of the current frame in the buffer.
+    position: usize,
+    state: State,
+    /// Position of the current frame in the stream.
+    stream_position: Position,
+    num_discarded: usize,
+    /// Position in the stream where the bytes being skipped by resynchronization start,
+    /// together with the error at that position.
+    skipping: Option<(usize, Error)>,
+    /// Position in the stream up to which no sync marker within the claimed body of the
+    /// current frame starts a complete frame with a matching checksum.
+    scanned: usize,
+}
+
+impl Default for StreamDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamDecoder {
+    /// Creates a stream decoder with the default frame layout.
+    pub fn new() -> Self {
+        Builder::new().new_stream_decoder()
+    }
+
+    pub(crate) fn from_config(config: Config) -> Self {
+        Self {
+            config,
+            buffer: vec![],
+            position: 0,
+            state: State::Header,
+            stream_position: Position::default(),
+            num_discarded: 0,
+            skipping: None,
+            scanned: 0,
+        }
+    }
+
+    /// Number of received bytes which are not yet yielded as part of a frame.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Number of oversized frames skipped so far with
+    /// [`OversizePolicy::Discard`](crate::OversizePolicy::Discard).
+    pub fn num_discarded(&self) -> usize {
+        self.num_discarded
+    }
+
+    /// Appends the `chunk` of received bytes.
+    pub fn feed(&mut self, mut chunk: &[u8]) {
+        // bytes of an oversized frame are dropped without being buffered
+        if let State::Discard { header, remaining } = self.state {
+            let skipped = remaining.min(chunk.len());
+            chunk = &chunk[skipped..];
+            self.state = match remaining - skipped {
+                0 => State::Header,
+                remaining => State::Discard { header, remaining },
+            };
+        }
+
+        // drop the consumed bytes once they dominate the buffer, so that each byte is moved
+        // at most a constant number of times on average
+        if self.position > 0 && self.position >= self.buffer.len() / 2 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+
+        if let State::Body(header) = self.state {
+            let missing = header.frame_len().saturating_sub(self.buffered_len());
+            self.buffer.reserve(missing.max(chunk.len()));
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Returns the next complete frame, or None if more bytes are required to complete it.
+    ///
+    /// Returns an error if the header of the next frame is invalid, for instance, when its
+    /// length exceeds the maximum frame length and oversized frames are not discarded.
+    ///
+    /// A frame whose checksum does not match, or whose body fails to decompress, is consumed
+    /// before the error is returned, so that decoding can continue with the next frame.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        let mut frame = vec![];
+        Ok(self.next_frame_into(&mut frame)?.then_some(frame))
+    }
+
+    /// Returns the next complete frame in a buffer of the `pool`, which returns to the pool
+    /// once the frame is dropped; see [`next_frame`](StreamDecoder::next_frame).
+    #[cfg(feature = "std")]
+    pub fn next_pooled_frame(&mut self, pool: &BufferPool) -> Result<Option<PooledBuffer>, Error> {
+        let mut frame = pool.get();
+        Ok(self.next_frame_into(&mut frame)?.then_some(frame))
+    }
+
+    /// Appends the next complete frame to the empty `frame`; returns false if more bytes are
+    /// required to complete it.
+    fn next_frame_into(&mut self, frame: &mut Vec<u8>) -> Result<bool, Error> {
+        loop {
+            let available = &self.buffer[self.position..];
+
+            let header = match self.state {
+                State::Body(header) => header,
+                State::Discard { .. } => return Ok(false),
+                State::Header => match self.config.parse_header(available) {
+                    Ok(header) => match self.config.check_length(header) {
+                        Ok(header) => header,
+                        Err(_) if self.config.discard_oversized() => {
+                            self.discard(header);
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    },
+                    Err(Error::TruncatedHeader { .. }) => return Ok(false),
+                    Err(e) => return Err(e),
+                },
+            };
+
+            return match available.len() >= header.frame_len() {
+                true => {
+                    let position = self.stream_position;
+                    self.position += header.frame_len();
+                    self.stream_position.advance(header);
+                    self.state = State::Header;
+
+                    self.config.verify(available, header, position)?;
+                    self.config.unpack(available, header, frame)?;
+                    Ok(true)
+                }
+                false => {
+                    self.state = State::Body(header);
+                    Ok(false)
+                }
+            };
+        }
+    }
+
+    /// Returns the next complete frame, or the range of the bytes skipped to resynchronize
+    /// at the next [sync marker](crate::Builder::sync_marker) after an invalid frame; returns
+    /// None if more bytes are required.
+    ///
+    /// Positions in the stream are counted from the first byte fed, and the skipped bytes are
+    /// reported once the valid frame following them is complete. See
+    /// [`Decoder::resync_frames`](crate::Decoder::resync_frames) for the frames considered
+    /// invalid.
+    ///
+    /// A corrupted length field which does not exceed the
+    /// [maximum frame length](crate::Builder::max_frame_length) cannot be told apart from a
+    /// frame whose body is still arriving. With a [checksum](crate::Builder::checksum), the
+    /// frame is skipped as soon as a complete frame with a matching checksum starts at a sync
+    /// marker within its claimed body; otherwise, the frames following it are withheld until
+    /// the claimed length arrives, or the stream ends and
+    /// [`finish_resync`](StreamDecoder::finish_resync) yields them.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Builder, Resync};
+    ///
+    /// let builder = Builder::new().sync_marker(b"SYNC");
+    /// let mut bytes = b"garbage".to_vec();
+    /// builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    ///
+    /// let mut decoder = builder.new_stream_decoder();
+    /// for byte in &bytes[..bytes.len() - 1] {
+    ///     decoder.feed(std::slice::from_ref(byte));
+    ///     assert_eq!(decoder.next_resync(), None);
+    /// }
+    /// decoder.feed(&bytes[bytes.len() - 1..]);
+    /// assert!(matches!(decoder.next_resync(), Some(Resync::Skipped { range, .. }) if range == (0..7)));
+    /// assert_eq!(decoder.next_resync(), Some(Resync::Frame(b"hello".to_vec())));
+    /// assert_eq!(decoder.next_resync(), None);
+    /// ```
+    pub fn next_resync(&mut self) -> Option<Resync<Vec<u8>>> {
+        self.resync(false)
+    }
+
+    /// Returns the next of the items remaining at the end of the stream, once
+    /// [`next_resync`](StreamDecoder::next_resync) returns None; returns None once all are
+    /// returned.
+    ///
+    /// The incomplete frame, if any, is skipped as invalid and the frames following it are
+    /// yielded, as [`Decoder::resync_frames`](crate::Decoder::resync_frames) does at the end
+    /// of a buffer. The bytes skipped at the end of the stream are reported last.
+    ///
+    /// ```
+    /// use length_delimited_codec::{Builder, Resync};
+    ///
+    /// let builder = Builder::new().sync_marker(b"SYNC");
+    /// let mut bytes = vec![];
+    /// builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    /// bytes.extend_from_slice(b"garbage");
+    ///
+    /// let mut decoder = builder.new_stream_decoder();
+    /// decoder.feed(&bytes);
+    /// assert_eq!(decoder.next_resync(), Some(Resync::Frame(b"hello".to_vec())));
+    /// assert_eq!(decoder.next_resync(), None);
+    /// assert!(decoder.finish().is_err());
+    ///
+    /// let end = bytes.len();
+    /// assert!(matches!(decoder.finish_resync(), Some(Resync::Skipped { range, .. }) if range == (end - 7..end)));
+    /// assert_eq!(decoder.finish_resync(), None);
+    /// ```
+    pub fn finish_resync(&mut self) -> Option<Resync<Vec<u8>>> {
+        self.resync(true)
+    }
+
+    /// Returns the next item of [`next_resync`](StreamDecoder::next_resync), or of
+    /// [`finish_resync`](StreamDecoder::finish_resync) `at_end` of the stream.
+    fn resync(&mut self, at_end: bool) -> Option<Resync<Vec<u8>>> {
+        loop {
+            if let State::Discard { .. } = self.state {
+                return None;
+            }
+
+            let available = &self.buffer[self.position..];
+            let start = self.stream_position.offset;
+            if at_end && available.is_empty() {
+                return self
+                    .skipping
+                    .take()
+                    .map(|(skip_start, error)| Resync::Skipped {
+                        range: skip_start..start,
+                        error,
+                    });
+            }
+
+            let result = match self.config.parse_header(available) {
+                Ok(header) => match self.config.check_length(header) {
+                    Ok(header) if available.len() < header.frame_len() => {
+                        let truncated = |available: usize| Error::TruncatedPayload {
+                            expected: header.body_len,
+                            available: available.saturating_sub(header.header_len),
+                        };
+                        let from = self.scanned.saturating_sub(start);
+                        match self
+                            .config
+                            .find_checked_frame(available, from, self.stream_position)
+                        {
+                            Err(_) if at_end => Err(truncated(available.len())),
+                            // the frame overlaps a valid one, hence its length is corrupted
+                            Ok(next) => Err(truncated(next)),
+                            Err(scanned) => {
+                                self.scanned = start + scanned;
+                                return None;
+                            }
+                        }
+                    }
+                    Ok(header) => {
+                        let mut frame = vec![];
+                        self.config
+                            .verify(available, header, self.stream_position)
+                            .and_then(|_| self.config.unpack(available, header, &mut frame))
+                            .map(|_| (header, Some(frame)))
+                    }
+                    Err(_) if self.config.discard_oversized() => Ok((header, None)),
+                    Err(e) => Err(e),
+                },
+                Err(Error::TruncatedHeader { .. }) if !at_end => return None,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok((header, frame)) => {
+                    // the skipped bytes are reported before the frame following them
+                    if let Some((skip_start, error)) = self.skipping.take() {
+                        return Some(Resync::Skipped {
+                            range: skip_start..start,
+                            error,
+                        });
+                    }
+                    let Some(frame) = frame else {
+                        self.discard(header);
+                        continue;
+                    };
+
+                    self.position += header.frame_len();
+                    self.stream_position.advance(header);
+                    self.state = State::Header;
+                    return Some(Resync::Frame(frame));
+                }
+                Err(e) => {
+                    self.skipping.get_or_insert((start, e));
+                    let skipped = self.config.skip_to_sync_marker(available);
+                    self.position += skipped;
+                    self.stream_position.offset += skipped;
+                    self.state = State::Header;
+                }
+            }
+        }
+    }
+
+    /// Skips the buffered bytes of the oversized frame with the given `header`, and the rest
+    /// of it as it is fed.
+    fn discard(&mut self, header: FrameHeader) {
+        let skipped = header.frame_len().min(self.buffered_len());
+        self.position += skipped;
+        self.stream_position.advance(header);
+        self.num_discarded += 1;
+        self.state = match header.frame_len() - skipped {
+            0 => State::Header,
+            remaining => State::Discard { header, remaining },
+        };
+    }
+
+    /// Returns the error of the incomplete frame, if any, at the end of the stream, or of the
+    /// bytes skipped by [`next_resync`](StreamDecoder::next_resync) which are not reported
+    /// yet; see [`finish_resync`](StreamDecoder::finish_resync) to report them.
+    pub fn finish(&self) -> Result<(), Error> {
+        if let Some((_, error)) = &self.skipping {
+            return Err(error.clone());
+        }
+        let available = &self.buffer[self.position..];
+        let (header, available) = match self.state {
+            State::Header if available.is_empty() => return Ok(()),
+            State::Header => (self.config.parse_header(available)?, available.len()),
+            State::Body(header) => (header, available.len()),
+            State::Discard { header, remaining } => (header, header.frame_len() - remaining),
+        };
+        match available < header.frame_len() {
+            true => Err(Error::TruncatedPayload {
+                expected: header.body_len,
+                available: available - header.header_len,
+            }),
+            false => Ok(()),
+        }
+    }
+}
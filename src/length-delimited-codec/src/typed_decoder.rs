@@ -0,0 +1,78 @@
+use crate::{Decoder, Error, serializer::Serializer};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+/// Decodes length delimited frames into messages of type `T`, deserializing the payload of
+/// each frame by the serializer `S`.
+///
+/// Frames read by the readers and the stream decoder are deserialized by
+/// [`deserialize`](TypedDecoder::deserialize).
+///
+/// ```
+/// use length_delimited_codec::{Builder, serializer::Binary};
+///
+/// let builder = Builder::new();
+/// let mut encoder = builder.new_typed_encoder(Binary);
+/// let mut bytes = vec![];
+/// encoder.encode(&(1u8, Some('a')), &mut bytes).unwrap();
+/// encoder.encode(&(2u8, None), &mut bytes).unwrap();
+///
+/// let decoder = builder.new_typed_decoder::<(u8, Option<char>), _>(Binary);
+/// let mut stream = builder.new_stream_decoder();
+/// stream.feed(&bytes);
+/// while let Some(frame) = stream.next_frame().unwrap() {
+///     let (index, label) = decoder.deserialize(&frame).unwrap();
+///     assert_eq!(label.is_some(), index == 1);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TypedDecoder<T, S> {
+    decoder: Decoder,
+    serializer: S,
+    message: PhantomData<fn() -> T>,
+}
+
+impl<T, S: Serializer<T>> TypedDecoder<T, S> {
+    pub(crate) fn from_decoder(decoder: Decoder, serializer: S) -> Self {
+        Self {
+            decoder,
+            serializer,
+            message: PhantomData,
+        }
+    }
+
+    /// Decodes the frame at the beginning of `src` and deserializes its message.
+    ///
+    /// Returns the message together with the number of bytes of `src` the frame occupies.
+    pub fn decode_frame(&self, src: &[u8]) -> Result<(T, usize), Error> {
+        let mut unpacked = vec![];
+        let (frame, len) = self.decoder.decode_frame_with(src, &mut unpacked)?;
+        Ok((self.serializer.deserialize(frame)?, len))
+    }
+
+    /// Splits `src` into its frames and deserializes their messages.
+    ///
+    /// `src` must end at a frame boundary; otherwise, the error of the incomplete last frame is returned.
+    pub fn decode_all(&self, src: &[u8]) -> Result<Vec<T>, Error> {
+        let frames = self.decoder.decode_all(src)?;
+        frames
+            .iter()
+            .map(|x| self.serializer.deserialize(x))
+            .collect()
+    }
+
+    /// Deserializes the message of a `frame` yielded by a decoder or reader.
+    pub fn deserialize(&self, frame: &[u8]) -> Result<T, Error> {
+        self.serializer.deserialize(frame)
+    }
+
+    /// Returns a reference to the underlying decoder.
+    pub fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    /// Returns a reference to the serializer.
+    pub fn serializer(&self) -> &S {
+        &self.serializer
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Encoder, Error, serializer::Serializer};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+/// Encodes messages of type `T` into length delimited frames, serializing each message by
+/// the serializer `S` into the payload of its frame.
+///
+/// The serialized message is the whole payload; therefore, the typed encoders and decoders
+/// are meant for layouts without header bytes preceding the length field, and with the
+/// default `num_skip`.
+///
+/// ```
+/// use length_delimited_codec::{Builder, serializer::Binary};
+///
+/// let builder = Builder::new();
+/// let mut encoder = builder.new_typed_encoder(Binary);
+/// let mut bytes = vec![];
+/// encoder.encode(&vec![1u16, 2, 3], &mut bytes).unwrap();
+/// assert_eq!(bytes.len(), 4 + 8 + 3 * 2);
+///
+/// let decoder = builder.new_typed_decoder::<Vec<u16>, _>(Binary);
+/// assert_eq!(decoder.decode_all(&bytes).unwrap(), vec![vec![1, 2, 3]]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TypedEncoder<T, S> {
+    encoder: Encoder,
+    serializer: S,
+    /// Serialized bytes of the current message.
+    buffer: Vec<u8>,
+    message: PhantomData<fn(&T)>,
+}
+
+impl<T, S: Serializer<T>> TypedEncoder<T, S> {
+    pub(crate) fn from_encoder(encoder: Encoder, serializer: S) -> Self {
+        Self {
+            encoder,
+            serializer,
+            buffer: vec![],
+            message: PhantomData,
+        }
+    }
+
+    /// Serializes the `message` and appends its frame to `dst`.
+    ///
+    /// Nothing is appended to `dst` if the message cannot be serialized or its payload
+    /// cannot be encoded.
+    pub fn encode(&mut self, message: &T, dst: &mut Vec<u8>) -> Result<(), Error> {
+        self.buffer.clear();
+        self.serializer.serialize(message, &mut self.buffer)?;
+        self.encoder.encode(&self.buffer, dst)
+    }
+
+    /// Serializes the `message` into a payload, to be framed by a writer.
+    pub fn serialize(&self, message: &T) -> Result<Vec<u8>, Error> {
+        let mut payload = vec![];
+        self.serializer.serialize(message, &mut payload)?;
+        Ok(payload)
+    }
+
+    /// Returns a reference to the underlying encoder.
+    pub fn encoder(&self) -> &Encoder {
+        &self.encoder
+    }
+
+    /// Returns a reference to the serializer.
+    pub fn serializer(&self) -> &S {
+        &self.serializer
+    }
+}
@@ -0,0 +1,78 @@
+//! Unsigned LEB128 variable-length integers, as used by protobuf to delimit messages.
+//!
+//! Each byte holds 7 bits of the value, least significant group first, and its most
+//! significant bit is set if more bytes follow.
+
+use crate::Error;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Maximum number of bytes of a varint encoding a `u64`.
+pub const MAX_VARINT_LEN: usize = 10;
+
+/// Number of bytes of the varint encoding of the `value`.
+pub fn encoded_len(value: u64) -> usize {
+    match value {
+        0 => 1,
+        x => (64 - x.leading_zeros() as usize).div_ceil(7),
+    }
+}
+
+/// Appends the varint encoding of the `value` to `dst`.
+#[cfg(feature = "alloc")]
+pub fn encode(mut value: u64, dst: &mut Vec<u8>) {
+    while value >= 0x80 {
+        dst.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    dst.push(value as u8);
+}
+
+/// Writes the varint encoding of the `value` to the beginning of `dst`, returning its
+/// number of bytes.
+///
+/// # Panics
+///
+/// Panics if `dst` is shorter than [`encoded_len`] of the value.
+pub fn encode_to_slice(mut value: u64, dst: &mut [u8]) -> usize {
+    let mut len = 0;
+    while value >= 0x80 {
+        dst[len] = (value as u8) | 0x80;
+        value >>= 7;
+        len += 1;
+    }
+    dst[len] = value as u8;
+    len + 1
+}
+
+/// Decodes the varint at the beginning of `src`, returning its value and number of bytes.
+///
+/// Returns
+/// * [`Error::TruncatedHeader`] if `src` ends before the last byte of the varint,
+/// * [`Error::VarintOverlong`] if the value is not encoded with the minimum number of bytes,
+/// * [`Error::VarintOverflow`] if the value does not fit in a `u64`.
+pub fn decode(src: &[u8]) -> Result<(u64, usize), Error> {
+    let mut value = 0u64;
+    for (i, byte) in src.iter().take(MAX_VARINT_LEN).enumerate() {
+        let bits = (byte & 0x7F) as u64;
+        if i == MAX_VARINT_LEN - 1 && bits > 1 {
+            return Err(Error::VarintOverflow);
+        }
+        value |= bits << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return match i > 0 && *byte == 0 {
+                true => Err(Error::VarintOverlong { len: i + 1 }),
+                false => Ok((value, i + 1)),
+            };
+        }
+    }
+
+    match src.len() >= MAX_VARINT_LEN {
+        true => Err(Error::VarintOverflow),
+        false => Err(Error::TruncatedHeader {
+            needed: src.len() + 1,
+            available: src.len(),
+        }),
+    }
+}
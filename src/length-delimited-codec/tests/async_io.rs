@@ -0,0 +1,150 @@
+#![cfg(feature = "async")]
+
+use bytes::Bytes;
+use futures_util::{FutureExt, SinkExt, StreamExt, TryStreamExt};
+use length_delimited_codec::{Builder, Checksum, Encoder, OversizePolicy};
+use std::io;
+use tokio::io::{AsyncWriteExt, duplex};
+
+mod common;
+
+#[tokio::test]
+async fn round_trip_over_duplex() {
+    let payloads = common::payloads(200, 0..300);
+    let payloads: Vec<_> = payloads.into_iter().map(Bytes::from).collect();
+    let builders = [
+        Builder::new(),
+        Builder::new().varint().little_endian(),
+        Builder::new().checksum(Checksum::Crc32c),
+    ];
+    for builder in builders {
+        for (pipe_len, capacity) in [(1, 1), (16, 7), (64, 1024), (4096, 64)] {
+            let (client, server) = duplex(pipe_len);
+            let mut sink = builder.new_async_framed_writer_with_capacity(capacity, client);
+            let stream = builder.new_async_framed_reader_with_capacity(capacity, server);
+
+            let send = async {
+                for payload in payloads.iter().cloned() {
+                    sink.feed(payload).await.unwrap();
+                }
+                sink.close().await.unwrap();
+            };
+            let (_, frames) = tokio::join!(send, stream.try_collect::<Vec<_>>());
+            assert_eq!(frames.unwrap(), payloads, "pipe_len = {pipe_len}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn sink_applies_backpressure() {
+    let (client, server) = duplex(64);
+    let capacity = 256;
+    let mut sink = Builder::new().new_async_framed_writer_with_capacity(capacity, client);
+    let payload = Bytes::from(vec![7; 100]);
+
+    // nobody reads; the sink accepts frames only until the pipe and its buffer are full
+    let mut num_accepted = 0;
+    while sink.feed(payload.clone()).now_or_never().is_some() {
+        num_accepted += 1;
+        assert!(num_accepted < 100, "the sink must stop accepting frames");
+    }
+    assert!(sink.buffered_len() < capacity + 104);
+    assert!(num_accepted * 104 <= 64 + capacity + 104);
+
+    let stream = Builder::new().new_async_framed_reader(server);
+    let send = async {
+        for _ in 0..100 {
+            sink.feed(payload.clone()).await.unwrap();
+        }
+        sink.close().await.unwrap();
+    };
+    let (_, frames) = tokio::join!(send, stream.try_collect::<Vec<_>>());
+    // the frame of the pending feed is lost when its future is dropped
+    let frames = frames.unwrap();
+    assert!(frames.len() >= num_accepted + 100);
+    assert!(frames.iter().all(|x| *x == payload));
+}
+
+#[tokio::test]
+async fn end_of_stream_in_the_middle_of_a_frame() {
+    let mut bytes = vec![];
+    Encoder::new().encode(b"hello", &mut bytes).unwrap();
+    Encoder::new().encode(b"world", &mut bytes).unwrap();
+
+    for (len, num_complete) in [(bytes.len() - 1, 1), (11, 1), (10, 1), (8, 0), (2, 0)] {
+        let (mut client, server) = duplex(64);
+        client.write_all(&bytes[..len]).await.unwrap();
+        drop(client);
+
+        let mut stream = Builder::new().new_async_framed_reader(server);
+        if num_complete == 1 {
+            assert_eq!(stream.next().await.unwrap().unwrap(), &b"hello"[..]);
+        }
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof, "len = {len}");
+        assert!(stream.next().await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn checksum_mismatch_continues_with_the_next_frame() {
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let encoder = builder.new_encoder();
+    let mut bytes = vec![];
+    for payload in [&b"ab"[..], b"cd", b"ef"] {
+        encoder.encode(payload, &mut bytes).unwrap();
+    }
+    bytes[10 + 5] ^= 1;
+
+    let frames: Vec<_> = builder
+        .new_async_framed_reader(bytes.as_slice())
+        .collect()
+        .await;
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].as_ref().unwrap(), &b"ab"[..]);
+    assert_eq!(
+        frames[1].as_ref().unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert_eq!(frames[2].as_ref().unwrap(), &b"ef"[..]);
+}
+
+#[tokio::test]
+async fn oversized_frames() {
+    let mut bytes = vec![];
+    for payload in [&b"ab"[..], &[1; 20], b"cd"] {
+        Encoder::new().encode(payload, &mut bytes).unwrap();
+    }
+
+    let builder = Builder::new().max_frame_length(8);
+    let frames: Vec<_> = builder
+        .new_async_framed_reader(bytes.as_slice())
+        .collect()
+        .await;
+    assert_eq!(frames.len(), 2, "the stream ends at the oversized frame");
+    assert_eq!(
+        frames[1].as_ref().unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+
+    let builder = builder.oversize_policy(OversizePolicy::Discard);
+    let mut stream = builder.new_async_framed_reader_with_capacity(3, bytes.as_slice());
+    let mut frames = vec![];
+    while let Some(frame) = stream.try_next().await.unwrap() {
+        frames.push(frame);
+    }
+    assert_eq!(frames, vec![&b"ab"[..], b"cd"]);
+    assert_eq!(stream.num_discarded(), 1);
+}
+
+#[tokio::test]
+async fn invalid_payload_is_not_written() {
+    let mut sink = Builder::new()
+        .length_field_length(1)
+        .new_async_framed_writer(vec![]);
+    sink.send(Bytes::from_static(b"ok")).await.unwrap();
+    let error = sink.send(Bytes::from(vec![0; 300])).await.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    sink.close().await.unwrap();
+    assert_eq!(sink.get_ref(), &[2, b'o', b'k']);
+}
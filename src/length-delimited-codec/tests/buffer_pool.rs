@@ -0,0 +1,171 @@
+#![cfg(feature = "std")]
+
+use length_delimited_codec::{
+    BufferPool, Builder, Checksum, ChecksumPosition, Compression, Encoder, PooledBuffer,
+};
+use std::io::{self, Cursor};
+
+mod common;
+
+use common::encode_all;
+
+#[test]
+fn pooled_frames_of_all_readers() {
+    let payloads = common::payloads(500, 0..700);
+    let builders = [
+        Builder::new(),
+        Builder::new().checksum(Checksum::Crc32).varint(),
+        Builder::new().compression(Compression::Lz4),
+    ];
+    for builder in builders {
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        let pool = BufferPool::new(4);
+
+        let mut reader = builder.new_framed_reader(Cursor::new(&bytes));
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+            frames.push(frame.to_vec());
+        }
+        assert_eq!(frames, payloads);
+
+        let mut reader = builder.new_framed_buf_reader(Cursor::new(&bytes));
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+            frames.push(frame.to_vec());
+        }
+        assert_eq!(frames, payloads);
+
+        let mut decoder = builder.new_stream_decoder();
+        let mut frames = vec![];
+        for chunk in bytes.chunks(100) {
+            decoder.feed(chunk);
+            while let Some(frame) = decoder.next_pooled_frame(&pool).unwrap() {
+                frames.push(frame.to_vec());
+            }
+        }
+        assert_eq!(frames, payloads);
+
+        // each frame is dropped before the next one is read
+        assert_eq!(pool.num_allocated(), 1);
+        assert_eq!(pool.num_idle(), 1);
+    }
+}
+
+#[test]
+fn pooled_frames_match_borrowed_frames() {
+    let payloads = common::payloads(50, 0..700);
+    let builders = [
+        Builder::new().checksum(Checksum::Crc32c),
+        Builder::new()
+            .checksum(Checksum::XxHash64)
+            .checksum_position(ChecksumPosition::Prepend),
+        Builder::new().checksum(Checksum::Crc32).num_skip(0),
+        Builder::new()
+            .checksum(Checksum::Crc32)
+            .checksum_position(ChecksumPosition::Prepend)
+            .num_skip(6),
+        Builder::new().varint().num_skip(1),
+        Builder::new().sync_marker(b"SYNC").num_skip(2),
+        Builder::new().num_skip(1000),
+    ];
+    for builder in builders {
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        let pool = BufferPool::new(4);
+
+        let mut borrowed = builder.new_framed_reader(bytes.as_slice());
+        let mut pooled = builder.new_framed_reader(bytes.as_slice());
+        while let Some(frame) = borrowed.read_frame().unwrap() {
+            let pooled_frame = pooled.read_pooled_frame(&pool).unwrap().unwrap();
+            assert_eq!(pooled_frame.as_slice(), frame);
+        }
+        assert!(pooled.read_pooled_frame(&pool).unwrap().is_none());
+
+        // truncated at any byte of the last frame
+        let last_len = builder.new_encoder().encoded_len(payloads[49].len());
+        for len in bytes.len() - last_len + 1..bytes.len() {
+            let mut reader = builder.new_framed_reader(&bytes[..len]);
+            for _ in 0..49 {
+                reader.read_pooled_frame(&pool).unwrap().unwrap();
+            }
+            let error = reader.read_pooled_frame(&pool).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+}
+
+#[test]
+fn corrupted_pooled_frame_is_consumed() {
+    for position in [ChecksumPosition::Append, ChecksumPosition::Prepend] {
+        let builder = Builder::new()
+            .checksum(Checksum::Crc32)
+            .checksum_position(position);
+        let mut bytes = encode_all(&builder.new_encoder(), &[&b"first"[..], b"second"]);
+        bytes[6] ^= 1;
+        let pool = BufferPool::new(4);
+
+        let mut reader = builder.new_framed_reader(bytes.as_slice());
+        let error = reader.read_pooled_frame(&pool).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let frame = reader.read_pooled_frame(&pool).unwrap().unwrap();
+        assert_eq!(frame.as_slice(), b"second");
+    }
+}
+
+#[test]
+fn buffers_in_use_and_idle_limit() {
+    let payloads = common::payloads(500, 0..700);
+    let bytes = encode_all(&Encoder::new(), &payloads);
+    let pool = BufferPool::new(8);
+
+    let mut reader = Builder::new().new_framed_buf_reader(bytes.as_slice());
+    let mut held = vec![];
+    for _ in 0..20 {
+        held.push(reader.read_pooled_frame(&pool).unwrap().unwrap());
+    }
+    assert_eq!(pool.num_allocated(), 20);
+    assert_eq!(pool.num_idle(), 0);
+    for (frame, payload) in held.iter().zip(&payloads) {
+        assert_eq!(frame.as_slice(), payload.as_slice());
+    }
+
+    // only `max_idle` buffers are kept, with their capacity
+    drop(held);
+    assert_eq!(pool.num_idle(), 8);
+    let buffer = pool.get();
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() > 0);
+
+    // a detached buffer does not return to the pool
+    let vec = buffer.into_vec();
+    assert!(vec.capacity() > 0);
+    assert_eq!(pool.num_idle(), 7);
+}
+
+#[test]
+fn frames_dropped_on_other_threads() {
+    let payloads = common::payloads(500, 0..700);
+    let bytes = encode_all(&Encoder::new(), &payloads);
+    let pool = BufferPool::new(64);
+
+    let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+    let received = std::thread::scope(|s| {
+        let consumer = s.spawn(move || {
+            receiver
+                .into_iter()
+                .map(|frame: PooledBuffer| frame.len())
+                .sum::<usize>()
+        });
+
+        let mut reader = Builder::new().new_framed_reader(bytes.as_slice());
+        while let Some(frame) = reader.read_pooled_frame(&pool).unwrap() {
+            sender.send(frame).unwrap();
+        }
+        drop(sender);
+        consumer.join().unwrap()
+    });
+
+    assert_eq!(received, payloads.iter().map(|x| x.len()).sum::<usize>());
+    // at most the frames in the channel and the ones being sent or consumed are in use
+    assert!(pool.num_allocated() <= 16 + 3);
+    assert_eq!(pool.num_idle(), pool.num_allocated());
+}
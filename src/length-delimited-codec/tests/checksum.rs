@@ -0,0 +1,234 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, ChecksumPosition, Error,
+    checksum::{crc32, crc32c, xxh64},
+};
+#[cfg(feature = "std")]
+use std::io;
+
+const CHECKSUMS: [Checksum; 3] = [Checksum::Crc32, Checksum::Crc32c, Checksum::XxHash64];
+const POSITIONS: [ChecksumPosition; 2] = [ChecksumPosition::Prepend, ChecksumPosition::Append];
+
+mod common;
+
+use common::length_field_variants;
+
+/// Payloads long enough to hold the two header bytes of the offset layout.
+fn builders() -> Vec<(Builder, usize)> {
+    let mut builders = vec![];
+    for checksum in CHECKSUMS {
+        for position in POSITIONS {
+            let builder = Builder::new()
+                .checksum(checksum)
+                .checksum_position(position);
+            builders.extend(length_field_variants(builder));
+        }
+    }
+    builders
+}
+
+/// Encodes the payloads, returning the bytes and the offset of each frame.
+fn encode_all(builder: &Builder, payloads: &[Vec<u8>]) -> (Vec<u8>, Vec<usize>) {
+    let (bytes, ranges) = common::encode_with_ranges(&builder.new_encoder(), payloads);
+    (bytes, ranges.into_iter().map(|x| x.start).collect())
+}
+
+#[test]
+fn known_values() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    assert_eq!(crc32c(b"123456789"), 0xE3069283);
+    assert_eq!(xxh64(b"", 0), 0xEF46DB3751D8E999);
+    assert_eq!(xxh64(b"abc", 0), 0x44BC2CF5AD770999);
+    assert_eq!(
+        xxh64(b"Nobody inspects the spammish repetition", 0),
+        0xFBCEA83C8A378BF1
+    );
+}
+
+#[test]
+fn compute_over_parts_matches_contiguous() {
+    let data: Vec<u8> = (0..1000).map(|x| (x * 31 % 251) as u8).collect();
+    for split in [0, 1, 7, 31, 32, 33, 500, 999, 1000] {
+        let parts = [&data[..split], &data[split..]];
+        assert_eq!(Checksum::Crc32.compute(&parts), crc32(&data) as u64);
+        assert_eq!(Checksum::Crc32c.compute(&parts), crc32c(&data) as u64);
+        assert_eq!(Checksum::XxHash64.compute(&parts), xxh64(&data, 0));
+    }
+}
+
+#[test]
+fn round_trip() {
+    let payloads = common::payloads(20, 2..102);
+    for (builder, offset) in builders() {
+        let (bytes, _) = encode_all(&builder, &payloads);
+        let expected: Vec<_> = payloads.iter().map(|x| x[offset..].to_vec()).collect();
+
+        assert_eq!(builder.new_decoder().decode_all(&bytes).unwrap(), expected);
+
+        let mut decoder = builder.new_stream_decoder();
+        let mut frames = vec![];
+        for byte in &bytes {
+            decoder.feed(std::slice::from_ref(byte));
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames, expected);
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = builder.new_framed_reader(bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+
+            let mut reader = builder.new_framed_buf_reader_with_capacity(16, bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+        }
+    }
+}
+
+#[test]
+fn checksum_is_stripped_from_the_body() {
+    let payload = b"hello".to_vec();
+    for checksum in CHECKSUMS {
+        for position in POSITIONS {
+            let builder = Builder::new()
+                .checksum(checksum)
+                .checksum_position(position);
+            let (bytes, _) = encode_all(&builder, std::slice::from_ref(&payload));
+            assert_eq!(bytes.len(), 4 + payload.len() + checksum.size());
+            assert_eq!(
+                builder.new_decoder().decode_all(&bytes).unwrap(),
+                vec![payload.clone()]
+            );
+        }
+    }
+}
+
+#[test]
+fn corrupted_body_is_reported_with_index_and_offset() {
+    let payloads = common::payloads(20, 2..102);
+    for checksum in CHECKSUMS {
+        for position in POSITIONS {
+            let builder = Builder::new()
+                .checksum(checksum)
+                .checksum_position(position);
+            let (bytes, offsets) = encode_all(&builder, &payloads);
+
+            for (frame_index, offset) in offsets.iter().enumerate() {
+                // every byte after the length field, including the checksum itself
+                let frame_len = builder
+                    .new_encoder()
+                    .encoded_len(payloads[frame_index].len());
+                for i in (offset + 4)..(offset + frame_len) {
+                    let mut corrupted = bytes.clone();
+                    corrupted[i] ^= 0x10;
+                    match builder.new_decoder().decode_all(&corrupted) {
+                        Err(Error::ChecksumMismatch {
+                            frame_index: index,
+                            offset: o,
+                            ..
+                        }) => assert_eq!((index, o), (frame_index, *offset)),
+                        x => panic!("unexpected result {x:?} for corrupted byte {i}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn corrupted_length_field_is_detected() {
+    let payloads = common::payloads(20, 2..102);
+    let builder = Builder::new().checksum(Checksum::Crc32c);
+    let (bytes, offsets) = encode_all(&builder, &payloads);
+    let frames = builder.new_decoder().decode_all(&bytes).unwrap();
+
+    for offset in offsets {
+        for i in offset..offset + 4 {
+            for bit in 0..8 {
+                let mut corrupted = bytes.clone();
+                corrupted[i] ^= 1 << bit;
+                let result = builder.new_decoder().decode_all(&corrupted);
+                assert_ne!(result, Ok(frames.clone()));
+            }
+        }
+    }
+}
+
+#[test]
+fn stream_decoder_continues_after_mismatch() {
+    let payloads = common::payloads(20, 2..102);
+    let builder = Builder::new().checksum(Checksum::XxHash64);
+    let (mut bytes, offsets) = encode_all(&builder, &payloads);
+    bytes[offsets[3] + 5] ^= 1;
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes);
+    let mut frames = vec![];
+    let mut errors = vec![];
+    loop {
+        match decoder.next_frame() {
+            Ok(Some(frame)) => frames.push(frame),
+            Ok(None) => break,
+            Err(e) => errors.push(e),
+        }
+    }
+
+    assert_eq!(frames.len(), payloads.len() - 1);
+    assert!(matches!(
+        errors.as_slice(),
+        [Error::ChecksumMismatch { frame_index: 3, offset, .. }] if *offset == offsets[3]
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn framed_readers_report_mismatch() {
+    let payloads = common::payloads(20, 2..102);
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let (mut bytes, offsets) = encode_all(&builder, &payloads);
+    bytes[offsets[2] + 6] ^= 1;
+
+    let mut reader = builder.new_framed_reader(bytes.as_slice());
+    for _ in 0..2 {
+        assert!(reader.read_frame().unwrap().is_some());
+    }
+    let error = reader.read_frame().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(matches!(
+        error.into_inner().unwrap().downcast::<Error>().as_deref(),
+        Ok(Error::ChecksumMismatch { frame_index: 2, .. })
+    ));
+    assert_eq!(reader.read_frame().unwrap(), Some(&payloads[3][..]));
+
+    let mut reader = builder.new_framed_buf_reader_with_capacity(64, bytes.as_slice());
+    for _ in 0..2 {
+        assert!(reader.read_frame().unwrap().is_some());
+    }
+    let error = reader.read_frame().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(reader.read_frame().unwrap(), Some(&payloads[3][..]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn parallel_encoding_with_checksum() {
+    let payloads = common::payloads(20, 2..102);
+    for (builder, _) in builders() {
+        let (bytes, _) = encode_all(&builder, &payloads);
+        assert_eq!(
+            builder.new_encoder().encode_all_par(&payloads).unwrap(),
+            bytes
+        );
+    }
+}
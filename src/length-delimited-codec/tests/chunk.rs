@@ -0,0 +1,223 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Error,
+    chunk::{FLAG_FIRST, FLAG_LAST},
+    varint,
+};
+
+mod common;
+
+use common::XorShift;
+
+fn messages(rng: &mut XorShift) -> Vec<Vec<u8>> {
+    let mut messages: Vec<Vec<u8>> = [0, 1, 63, 64, 65, 128, 10_000]
+        .into_iter()
+        .map(|len| (0..len).map(|x| (x % 251) as u8).collect())
+        .collect();
+    for _ in 0..30 {
+        let len = rng.below(5000);
+        messages.push((0..len).map(|_| rng.next() as u8).collect());
+    }
+    messages
+}
+
+/// Chunks of at most 64 bytes, which fit in frames of at most 80 bytes.
+fn builder() -> Builder {
+    Builder::new().max_frame_length(80)
+}
+
+fn encode_all(builder: &Builder, messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut chunker = builder.new_chunker(64);
+    let mut bytes = vec![];
+    for message in messages {
+        chunker.encode(message, &mut bytes).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn messages_larger_than_frames_round_trip() {
+    let mut rng = XorShift(0xDEAD_BEEF);
+    let messages = messages(&mut rng);
+    let builders = [
+        builder(),
+        builder().varint().checksum(Checksum::XxHash64),
+        builder().length_field_length(1),
+    ];
+    for builder in builders {
+        let bytes = encode_all(&builder, &messages);
+
+        let mut reassembler = builder.new_reassembler(10_000, 200);
+        reassembler.feed(&bytes);
+        let mut received = vec![];
+        while let Some(message) = reassembler.next_message().unwrap() {
+            received.push(message);
+        }
+        assert_eq!(received, messages);
+        assert_eq!(reassembler.finish(), Ok(()));
+        assert_eq!(reassembler.buffered_len(), 0);
+    }
+}
+
+#[test]
+fn streams_cut_at_arbitrary_positions() {
+    let mut rng = XorShift(12345);
+    let messages = messages(&mut rng);
+    let builder = builder().checksum(Checksum::Crc32c);
+    let bytes = encode_all(&builder, &messages);
+
+    for _ in 0..20 {
+        let mut reassembler = builder.new_reassembler(10_000, 200);
+        let mut received = vec![];
+        let mut rest = bytes.as_slice();
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at(rest.len().min(rng.below(150)));
+            rest = tail;
+            reassembler.feed(piece);
+            while let Some(message) = reassembler.next_message().unwrap() {
+                received.push(message);
+            }
+        }
+        assert_eq!(received, messages);
+        assert_eq!(reassembler.finish(), Ok(()));
+    }
+
+    // single bytes, including the cut between every pair of bytes
+    let mut reassembler = builder.new_reassembler(10_000, 200);
+    let mut received = vec![];
+    for byte in &bytes {
+        reassembler.feed(std::slice::from_ref(byte));
+        while let Some(message) = reassembler.next_message().unwrap() {
+            received.push(message);
+        }
+    }
+    assert_eq!(received, messages);
+}
+
+#[test]
+fn truncated_stream_is_incomplete() {
+    let builder = builder();
+    let bytes = encode_all(&builder, &[vec![1; 500]]);
+    for len in 1..bytes.len() {
+        let mut reassembler = builder.new_reassembler(1000, 100);
+        reassembler.feed(&bytes[..len]);
+        assert_eq!(reassembler.next_message(), Ok(None));
+        assert!(reassembler.finish().is_err());
+    }
+}
+
+fn first_chunk(declared_len: u64, is_last: bool, data: &[u8]) -> Vec<u8> {
+    let flags = match is_last {
+        true => FLAG_FIRST | FLAG_LAST,
+        false => FLAG_FIRST,
+    };
+    let mut chunk = vec![flags];
+    varint::encode(declared_len, &mut chunk);
+    chunk.extend_from_slice(data);
+    chunk
+}
+
+#[test]
+fn huge_declared_length_is_rejected_before_buffering() {
+    let builder = builder();
+    // the chunks of a message claiming 1 TiB, followed by a valid message
+    let mut payloads = vec![first_chunk(1 << 40, false, &[0; 64])];
+    payloads.extend((0..1000).map(|i| {
+        let mut chunk = vec![if i == 999 { FLAG_LAST } else { 0 }];
+        chunk.extend_from_slice(&[0; 64]);
+        chunk
+    }));
+    payloads.push(first_chunk(3, true, b"end"));
+    let bytes = common::encode_all(&builder.new_encoder(), &payloads);
+
+    let mut reassembler = builder.new_reassembler(1 << 20, 100);
+    let mut max_buffered = 0;
+    let mut results = vec![];
+    for piece in bytes.chunks(100) {
+        reassembler.feed(piece);
+        max_buffered = max_buffered.max(reassembler.buffered_len());
+        loop {
+            match reassembler.next_message() {
+                Ok(None) => break,
+                result => results.push(result),
+            }
+        }
+    }
+    assert_eq!(
+        results,
+        vec![
+            Err(Error::MessageTooLarge {
+                len: 1 << 40,
+                max: 1 << 20
+            }),
+            Ok(Some(b"end".to_vec())),
+        ]
+    );
+    assert!(max_buffered <= 200);
+}
+
+#[test]
+fn too_many_chunks_in_flight() {
+    let builder = builder();
+    let mut chunker = builder.new_chunker(1);
+    let mut bytes = vec![];
+    chunker.encode(&[1; 20], &mut bytes).unwrap();
+    chunker.encode(&[2; 10], &mut bytes).unwrap();
+
+    let mut reassembler = builder.new_reassembler(1000, 10);
+    reassembler.feed(&bytes);
+    assert_eq!(
+        reassembler.next_message(),
+        Err(Error::TooManyChunks { max: 10 })
+    );
+    // the rest of the dropped message is skipped
+    assert_eq!(reassembler.next_message(), Ok(Some(vec![2; 10])));
+    assert_eq!(reassembler.buffered_len(), 0);
+    assert_eq!(reassembler.finish(), Ok(()));
+}
+
+#[test]
+fn inconsistent_chunks_are_rejected() {
+    let builder = builder();
+    let cases: Vec<Vec<Vec<u8>>> = vec![
+        // more bytes than declared
+        vec![first_chunk(2, false, b"ab"), vec![FLAG_LAST, b'c']],
+        // fewer bytes than declared
+        vec![first_chunk(4, false, b"ab"), vec![FLAG_LAST, b'c']],
+        // a chunk without a first chunk
+        vec![vec![FLAG_LAST, b'c']],
+        // a first chunk interrupting a message
+        vec![first_chunk(4, false, b"ab"), first_chunk(1, true, b"x")],
+        // unknown flags, and a missing header
+        vec![vec![0b100, b'c']],
+        vec![vec![]],
+        vec![vec![FLAG_FIRST]],
+    ];
+    for payloads in cases {
+        let mut payloads = payloads;
+        payloads.push(first_chunk(2, true, b"ok"));
+        let bytes = common::encode_all(&builder.new_encoder(), &payloads);
+
+        let mut reassembler = builder.new_reassembler(100, 100);
+        reassembler.feed(&bytes);
+        assert_eq!(reassembler.next_message(), Err(Error::InvalidChunk));
+        assert_eq!(reassembler.next_message(), Ok(Some(b"ok".to_vec())));
+        assert_eq!(reassembler.finish(), Ok(()));
+    }
+}
+
+#[test]
+fn chunks_exceeding_the_frame_length_are_not_encoded() {
+    let builder = builder();
+    let mut chunker = builder.new_chunker(100);
+    let mut bytes = vec![];
+    chunker.encode(b"small", &mut bytes).unwrap();
+    let len = bytes.len();
+
+    assert!(matches!(
+        chunker.encode(&[0; 1000], &mut bytes),
+        Err(Error::FrameTooLarge { .. })
+    ));
+    assert_eq!(bytes.len(), len);
+}
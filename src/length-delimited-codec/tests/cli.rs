@@ -0,0 +1,203 @@
+use length_delimited_codec::{Builder, Checksum, Compression, Encoder};
+use std::{
+    io::{self, Write},
+    process::{Command, Output, Stdio},
+};
+
+mod common;
+
+use common::encode_all;
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_length-delimited-codec"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = child.stdin.take().unwrap();
+    let stdin = stdin.to_vec();
+    let writer = std::thread::spawn(move || input.write_all(&stdin));
+    let output = child.wait_with_output().unwrap();
+    // the child may exit without reading all of its input, as on invalid arguments
+    if let Err(e) = writer.join().unwrap() {
+        assert_eq!(e.kind(), io::ErrorKind::BrokenPipe);
+    }
+    output
+}
+
+#[test]
+fn frame_and_unframe_lines() {
+    let framed = run(&["frame"], b"hello\n\nworld\nlast");
+    assert!(framed.status.success());
+    let payloads: [&[u8]; 4] = [b"hello", b"", b"world", b"last"];
+    assert_eq!(framed.stdout, encode_all(&Encoder::new(), &payloads));
+
+    let unframed = run(&["unframe", "--lines"], &framed.stdout);
+    assert!(unframed.status.success());
+    assert_eq!(unframed.stdout, b"hello\n\nworld\nlast\n");
+
+    let unframed = run(&["unframe"], &framed.stdout);
+    assert_eq!(unframed.stdout, b"helloworldlast");
+}
+
+#[test]
+fn frame_fixed_size_records() {
+    let input: Vec<u8> = (0..25).collect();
+    let framed = run(&["frame", "--record-size", "10"], &input);
+    assert!(framed.status.success());
+    let payloads: [&[u8]; 3] = [&input[..10], &input[10..20], &input[20..]];
+    assert_eq!(framed.stdout, encode_all(&Encoder::new(), &payloads));
+
+    assert!(
+        !run(&["frame", "--record-size", "0"], &input)
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn layout_options_match_the_library() {
+    let layouts: [(&[&str], Builder); 4] = [
+        (
+            &["--length-field-length", "2", "--little-endian"],
+            Builder::new().length_field_length(2).little_endian(),
+        ),
+        (
+            &["--varint", "--checksum", "xxhash64"],
+            Builder::new().varint().checksum(Checksum::XxHash64),
+        ),
+        (
+            &[
+                "--sync-marker",
+                "a55a",
+                "--checksum",
+                "crc32",
+                "--checksum-position",
+                "prepend",
+            ],
+            Builder::new()
+                .sync_marker(&[0xA5, 0x5A])
+                .checksum(Checksum::Crc32)
+                .checksum_position(length_delimited_codec::ChecksumPosition::Prepend),
+        ),
+        (
+            &["--compression", "lz4", "--length-adjustment", "-1"],
+            Builder::new()
+                .compression(Compression::Lz4)
+                .length_adjustment(-1),
+        ),
+    ];
+    let lines = "the same line\n".repeat(20);
+    let payloads: Vec<&[u8]> = lines.lines().map(str::as_bytes).collect();
+    for (options, builder) in layouts {
+        let framed = run(&[&["frame"], options].concat(), lines.as_bytes());
+        assert!(framed.status.success(), "{options:?}");
+        assert_eq!(
+            framed.stdout,
+            encode_all(&builder.new_encoder(), &payloads),
+            "{options:?}"
+        );
+
+        let unframed = run(&[&["unframe", "--lines"], options].concat(), &framed.stdout);
+        assert_eq!(unframed.stdout, lines.as_bytes(), "{options:?}");
+        let inspected = run(&[&["inspect"], options].concat(), &framed.stdout);
+        assert!(inspected.status.success(), "{options:?}");
+    }
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let invalid: [&[&str]; 5] = [
+        &["frame", "--length-field-length", "5"],
+        &["frame", "--varint", "--length-field-length", "2"],
+        &["frame", "--sync-marker", "a5a"],
+        &["frame", "--checksum-position", "prepend"],
+        &["inspect", "--checksum", "md5"],
+    ];
+    for args in invalid {
+        let output = run(args, b"");
+        assert!(!output.status.success(), "{args:?}");
+        assert!(!output.stderr.is_empty());
+    }
+}
+
+#[test]
+fn inspect_reports_invalid_frames() {
+    let builder = Builder::new().checksum(Checksum::Crc32c);
+    let mut bytes = encode_all(
+        &builder.new_encoder(),
+        &[&b"first"[..], b"second", b"third"],
+    );
+    bytes[4 + 5 + 4 + 4] ^= 0xFF; // corrupts the body of the second frame
+    bytes.extend_from_slice(&[0, 0, 0, 9, b'x']); // truncated frame
+
+    let output = run(&["inspect", "--checksum", "crc32c"], &bytes);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|x| x.split_whitespace().collect())
+        .collect();
+    assert_eq!(lines[1], ["0", "0", "13", "5", "ok"]);
+    assert_eq!(lines[2][..4], ["1", "13", "14", "-"]);
+    assert_eq!(lines[2][4], "checksum");
+    assert_eq!(lines[3], ["2", "27", "13", "5", "ok"]);
+    assert_eq!(lines[4][..4], ["-", "40", "5", "-"]);
+    assert_eq!(lines[4][4], "truncated");
+    assert!(stdout.ends_with("frames = 3, invalid = 2, bytes = 45\n"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: 2 invalid"));
+
+    let output = run(
+        &["inspect", "--checksum", "crc32c", "--errors-only"],
+        &bytes,
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().filter(|x| x.ends_with(" ok")).count(), 0);
+    assert_eq!(stdout.lines().count(), 1 + 2 + 2);
+}
+
+#[test]
+fn stats_histogram() {
+    let payloads: Vec<Vec<u8>> = (0..100).map(|i| vec![7; i * 10]).collect();
+    let payloads: Vec<&[u8]> = payloads.iter().map(|x| x.as_slice()).collect();
+    let bytes = encode_all(&Encoder::new(), &payloads);
+
+    let output = run(&["stats"], &bytes);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(&format!("frames = 100, bytes = {}\n", bytes.len())));
+    assert!(stdout.contains("frame length: min = 4, max = 994, mean = 499.0"));
+    let buckets: Vec<(&str, usize)> = stdout
+        .lines()
+        .filter(|x| x.trim_start().starts_with('['))
+        .map(|x| {
+            let (range, rest) = x.trim().split_once(')').unwrap();
+            let count = rest.split_whitespace().next().unwrap();
+            (range.trim_start_matches('['), count.parse().unwrap())
+        })
+        .collect();
+    assert_eq!(
+        buckets,
+        [
+            ("4, 8", 1),
+            ("8, 16", 1),
+            ("16, 32", 1),
+            ("32, 64", 3),
+            ("64, 128", 7),
+            ("128, 256", 13),
+            ("256, 512", 25),
+            ("512, 1024", 49),
+        ]
+    );
+
+    let output = run(&["stats"], &bytes[..bytes.len() - 1]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("frames = 99,")
+    );
+}
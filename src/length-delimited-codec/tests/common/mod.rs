@@ -0,0 +1,124 @@
+//! Helpers shared by the integration tests, each of which uses a subset of them.
+#![allow(dead_code)]
+
+use length_delimited_codec::{Builder, Checksum, Encoder};
+use std::ops::Range;
+
+/// Deterministic pseudo random numbers, so that failures are reproducible.
+pub struct XorShift(pub u64);
+
+impl XorShift {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// `n` payloads whose lengths are spread over the `lengths`.
+pub fn payloads(n: usize, lengths: Range<usize>) -> Vec<Vec<u8>> {
+    (0..n)
+        .map(|i| {
+            let len = lengths.start + (i * 37) % lengths.len();
+            (0..len).map(|x| (x + i) as u8).collect()
+        })
+        .collect()
+}
+
+/// Bytes counting up from zero.
+const RAMP: [u8; 200] = {
+    let mut bytes = [0; 200];
+    let mut i = 0;
+    while i < bytes.len() {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+};
+
+/// A few payloads to split at every position.
+pub const SHORT_PAYLOADS: [&[u8]; 4] = [
+    b"first",
+    // empty body when two header bytes precede the length field
+    b"ab",
+    &RAMP,
+    b"last frame",
+];
+
+pub fn short_payloads() -> Vec<Vec<u8>> {
+    SHORT_PAYLOADS.iter().map(|x| x.to_vec()).collect()
+}
+
+/// Encodes the `payloads` one after the other into `dst` without allocating, and returns
+/// the encoded bytes.
+pub fn encode_to_slice_all<'a>(
+    encoder: &Encoder,
+    payloads: &[impl AsRef<[u8]>],
+    dst: &'a mut [u8],
+) -> &'a [u8] {
+    let mut len = 0;
+    for payload in payloads {
+        len += encoder
+            .encode_to_slice(payload.as_ref(), &mut dst[len..])
+            .unwrap();
+    }
+    &dst[..len]
+}
+
+#[cfg(feature = "alloc")]
+pub fn encode_all(encoder: &Encoder, payloads: &[impl AsRef<[u8]>]) -> Vec<u8> {
+    encode_with_ranges(encoder, payloads).0
+}
+
+/// Encodes the `payloads` one after the other, and returns the range of each frame.
+#[cfg(feature = "alloc")]
+pub fn encode_with_ranges(
+    encoder: &Encoder,
+    payloads: &[impl AsRef<[u8]>],
+) -> (Vec<u8>, Vec<Range<usize>>) {
+    let mut bytes = vec![];
+    let mut ranges = vec![];
+    for payload in payloads {
+        let start = bytes.len();
+        encoder.encode(payload.as_ref(), &mut bytes).unwrap();
+        ranges.push(start..bytes.len());
+    }
+    (bytes, ranges)
+}
+
+/// Variants of the `builder` with different length fields, together with the number of
+/// header bytes preceding the length field, which the payloads must start with.
+pub fn length_field_variants(builder: Builder) -> Vec<(Builder, usize)> {
+    vec![
+        (builder.clone(), 0),
+        (builder.clone().varint().little_endian(), 0),
+        (builder.length_field_offset(2).length_adjustment(3), 2),
+    ]
+}
+
+/// Frame layouts covering the length field widths, byte orders and varints, header bytes
+/// preceding the length field, which the payloads must have, and sync markers.
+pub fn layouts() -> Vec<Builder> {
+    vec![
+        Builder::new(),
+        Builder::new().length_field_length(2).little_endian(),
+        Builder::new().varint(),
+        Builder::new()
+            .length_field_offset(2)
+            .length_field_length(3)
+            .length_adjustment(-3)
+            .num_skip(5),
+        Builder::new()
+            .sync_marker(b"SYNC")
+            .checksum(Checksum::Crc32c),
+    ]
+}
@@ -0,0 +1,335 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Compression, Error,
+    compression::{FLAG_LZ4, FLAG_RAW, lz4_compress, lz4_decompress},
+    varint,
+};
+#[cfg(feature = "std")]
+use std::io::{self, Cursor};
+
+mod common;
+
+use common::{XorShift, length_field_variants};
+
+/// Text of words drawn from a small vocabulary, which compresses well.
+fn text(rng: &mut XorShift, len: usize) -> Vec<u8> {
+    const WORDS: [&str; 8] = [
+        "frame ", "length ", "field ", "payload ", "header ", "body ", "codec ", "stream ",
+    ];
+    let mut text = vec![];
+    while text.len() < len {
+        text.extend_from_slice(WORDS[rng.next() as usize % WORDS.len()].as_bytes());
+    }
+    text.truncate(len);
+    text
+}
+
+fn lz4_round_trip(src: &[u8]) -> Vec<u8> {
+    let mut compressed = vec![];
+    lz4_compress(src, &mut compressed);
+
+    let mut decompressed = b"prefix".to_vec();
+    lz4_decompress(&compressed, src.len(), &mut decompressed).unwrap();
+    assert_eq!(&decompressed[..6], b"prefix");
+    assert_eq!(&decompressed[6..], src);
+    compressed
+}
+
+#[test]
+fn lz4_edge_cases_round_trip() {
+    let mut rng = XorShift(5);
+    let inputs = [
+        vec![],
+        vec![7],
+        vec![0; 12],
+        vec![0; 13],
+        vec![0; 100_000],
+        b"ab".repeat(1000),
+        b"abcdefgh".repeat(10_000),
+        rng.bytes(17),
+        rng.bytes(5000),
+        text(&mut rng, 1024),
+        text(&mut rng, 1025),
+        text(&mut rng, 300_000),
+        [rng.bytes(300), vec![1; 300], rng.bytes(300)].concat(),
+    ];
+    for input in inputs {
+        lz4_round_trip(&input);
+    }
+}
+
+#[test]
+fn lz4_compresses_repetitions() {
+    assert!(lz4_round_trip(&[0; 100_000]).len() < 500);
+    assert!(lz4_round_trip(&b"abcdefgh".repeat(10_000)).len() < 500);
+    assert!(lz4_round_trip(&text(&mut XorShift(9), 100_000)).len() < 60_000);
+}
+
+#[test]
+fn lz4_standard_block() {
+    // a literal followed by an overlapping match of 15 bytes and 5 final literals
+    let block = [0x1B, b'a', 0x01, 0x00, 0x50, b'a', b'a', b'a', b'a', b'a'];
+    let mut decompressed = vec![];
+    lz4_decompress(&block, 21, &mut decompressed).unwrap();
+    assert_eq!(decompressed, [b'a'; 21]);
+}
+
+#[test]
+fn malformed_lz4_blocks_are_rejected() {
+    let src = text(&mut XorShift(1), 2000);
+    let mut block = vec![];
+    lz4_compress(&src, &mut block);
+
+    let decompress = |block: &[u8], len: usize| lz4_decompress(block, len, &mut vec![]);
+    assert_eq!(
+        decompress(&block, src.len() - 1),
+        Err(Error::InvalidCompression)
+    );
+    assert_eq!(
+        decompress(&block, src.len() + 1),
+        Err(Error::InvalidCompression)
+    );
+    for len in 0..block.len() {
+        assert_eq!(
+            decompress(&block[..len], src.len()),
+            Err(Error::InvalidCompression)
+        );
+    }
+
+    // zero offset, and offset beyond the decompressed bytes
+    assert_eq!(
+        decompress(&[0x10, b'a', 0x00, 0x00, 0x00], 5),
+        Err(Error::InvalidCompression)
+    );
+    assert_eq!(
+        decompress(&[0x10, b'a', 0x02, 0x00, 0x00], 5),
+        Err(Error::InvalidCompression)
+    );
+    // a huge length is rejected without allocating it
+    assert_eq!(
+        decompress(&[0x10, b'a', 0x01, 0x00, 0x00], usize::MAX),
+        Err(Error::InvalidCompression)
+    );
+}
+
+fn payloads() -> Vec<Vec<u8>> {
+    let mut rng = XorShift(17);
+    (0..40)
+        .map(|i| match i % 3 {
+            0 => text(&mut rng, i * 50),
+            1 => rng.bytes(i * 7),
+            _ => vec![i as u8; i * 100 + 3],
+        })
+        .collect()
+}
+
+fn builders() -> Vec<(Builder, usize)> {
+    let builder = Builder::new().compression(Compression::Lz4);
+    let mut builders = length_field_variants(builder.clone());
+    builders.push((builder.clone().checksum(Checksum::Crc32c), 0));
+    builders.push((
+        builder
+            .sync_marker(b"\xA5\x5A")
+            .checksum(Checksum::XxHash64),
+        0,
+    ));
+    builders
+}
+
+#[test]
+fn compressed_frames_round_trip() {
+    let payloads: Vec<_> = payloads().into_iter().filter(|x| x.len() >= 2).collect();
+    for (builder, offset) in builders() {
+        let encoder = builder.new_encoder();
+        let mut bytes = vec![];
+        for payload in &payloads {
+            let start = bytes.len();
+            encoder.encode(payload, &mut bytes).unwrap();
+            assert!(bytes.len() - start <= encoder.encoded_len(payload.len()));
+            assert_eq!(encoder.frame_len(payload), Ok(bytes.len() - start));
+        }
+        #[cfg(feature = "std")]
+        assert_eq!(encoder.encode_all_par(&payloads).unwrap(), bytes);
+
+        let expected: Vec<_> = payloads.iter().map(|x| x[offset..].to_vec()).collect();
+
+        let decoder = builder.new_decoder();
+        assert_eq!(decoder.decode_all(&bytes).unwrap(), expected);
+        let (frame, _) = decoder.decode_frame(&bytes).unwrap();
+        assert_eq!(frame, expected[0]);
+
+        let stored: Vec<_> = decoder
+            .frames(&bytes)
+            .map(|x| decoder.decompress(x.unwrap()).unwrap())
+            .collect();
+        assert_eq!(stored, expected);
+
+        let mut decoder = builder.new_stream_decoder();
+        let mut frames = vec![];
+        for chunk in bytes.chunks(100) {
+            decoder.feed(chunk);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames, expected);
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = builder.new_framed_reader(bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+
+            let mut reader = builder.new_framed_buf_reader_with_capacity(64, bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+        }
+    }
+}
+
+#[test]
+fn incompressible_bodies_are_stored_raw() {
+    let builder = Builder::new().compression(Compression::Lz4);
+    let encoder = builder.new_encoder();
+
+    let random = XorShift(3).bytes(1000);
+    let mut bytes = vec![];
+    encoder.encode(&random, &mut bytes).unwrap();
+    assert_eq!(bytes.len(), 4 + 1 + random.len());
+    assert_eq!(bytes[4], FLAG_RAW);
+    assert_eq!(bytes.len(), encoder.encoded_len(random.len()));
+
+    let mut bytes = vec![];
+    encoder.encode(b"", &mut bytes).unwrap();
+    assert_eq!(bytes, [0, 0, 0, 1, FLAG_RAW]);
+
+    let zeros = vec![0; 1000];
+    let mut bytes = vec![];
+    encoder.encode(&zeros, &mut bytes).unwrap();
+    assert_eq!(bytes[4], FLAG_LZ4);
+    assert!(bytes.len() < 50);
+
+    let decoder = builder.new_decoder();
+    assert_eq!(decoder.decode_all(&bytes).unwrap(), vec![zeros]);
+}
+
+#[test]
+fn num_skip_counts_decompressed_bytes() {
+    let payload = b"0123456789".repeat(20);
+    let builder = Builder::new().compression(Compression::Lz4).num_skip(4 + 3);
+    let mut bytes = vec![];
+    builder.new_encoder().encode(&payload, &mut bytes).unwrap();
+
+    let (frame, _) = builder.new_decoder().decode_frame(&bytes).unwrap();
+    assert_eq!(frame, payload[3..]);
+}
+
+/// Encodes a body which is already flagged, bypassing the compression of the encoder.
+fn encode_flagged(body: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![];
+    Builder::new()
+        .new_encoder()
+        .encode(body, &mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn decompression_bomb_is_rejected() {
+    let mut body = vec![FLAG_LZ4];
+    varint::encode(1 << 30, &mut body);
+    lz4_compress(&vec![0; 1 << 16], &mut body);
+    let bytes = encode_flagged(&body);
+
+    let builder = Builder::new()
+        .compression(Compression::Lz4)
+        .max_frame_length(1 << 20);
+    let error = Error::FrameTooLarge {
+        len: 1 << 30,
+        max: 1 << 20,
+    };
+    assert_eq!(builder.new_decoder().decode_all(&bytes), Err(error.clone()));
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes);
+    assert_eq!(decoder.next_frame(), Err(error));
+    assert_eq!(decoder.buffered_len(), 0);
+}
+
+#[test]
+fn oversized_payloads_are_rejected_before_compression() {
+    let builder = Builder::new()
+        .compression(Compression::Lz4)
+        .max_frame_length(100);
+    let mut bytes = vec![];
+    assert_eq!(
+        builder.new_encoder().encode(&[0; 200], &mut bytes),
+        Err(Error::FrameTooLarge { len: 200, max: 100 })
+    );
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn invalid_bodies_are_consumed() {
+    let mut bytes = encode_flagged(&[7, 1, 2, 3]);
+    let builder = Builder::new().compression(Compression::Lz4);
+    builder.new_encoder().encode(b"next", &mut bytes).unwrap();
+
+    assert_eq!(
+        builder.new_decoder().decode_all(&bytes),
+        Err(Error::InvalidCompression)
+    );
+    assert_eq!(
+        builder.new_decoder().decompress(&[FLAG_LZ4, 3, 0x30]),
+        Err(Error::InvalidCompression)
+    );
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes);
+    assert_eq!(decoder.next_frame(), Err(Error::InvalidCompression));
+    assert_eq!(decoder.next_frame(), Ok(Some(b"next".to_vec())));
+
+    #[cfg(feature = "std")]
+    {
+        let mut reader = builder.new_framed_reader(bytes.as_slice());
+        let error = reader.read_frame().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(reader.read_frame().unwrap(), Some(&b"next"[..]));
+
+        let mut reader = builder.new_framed_buf_reader(bytes.as_slice());
+        assert!(reader.read_frame().is_err());
+        assert_eq!(reader.read_frame().unwrap(), Some(&b"next"[..]));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn compressed_file_records() {
+    let payloads = payloads();
+    let builder = Builder::new()
+        .compression(Compression::Lz4)
+        .checksum(Checksum::Crc32);
+
+    let mut writer = builder.new_file_writer(vec![]).unwrap();
+    for payload in &payloads {
+        writer.write_record(payload).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+    let uncompressed: usize = payloads.iter().map(|x| x.len()).sum();
+    assert!(bytes.len() < uncompressed);
+
+    let mut reader = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.read_record(30).unwrap(), payloads[30]);
+    let records: Vec<_> = reader.records().map(|x| x.unwrap()).collect();
+    assert_eq!(records, payloads);
+
+    let mut reader = builder.recover_file_reader(Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.records().count(), payloads.len());
+}
@@ -0,0 +1,273 @@
+#![cfg(feature = "std")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Error, FileReader,
+    file::{HEADER_LEN, TRAILER_LEN},
+};
+use std::io::{self, Cursor};
+
+mod common;
+
+fn write_file(builder: &Builder, payloads: &[Vec<u8>], with_index: bool) -> Vec<u8> {
+    let mut writer = builder.new_file_writer(vec![]).unwrap();
+    for payload in payloads {
+        writer.write_record(payload).unwrap();
+    }
+    assert_eq!(writer.len(), payloads.len());
+    match with_index {
+        true => writer.finish().unwrap(),
+        false => writer.finish_without_index().unwrap(),
+    }
+}
+
+fn error_of(error: io::Error) -> Error {
+    *error.into_inner().unwrap().downcast::<Error>().unwrap()
+}
+
+#[test]
+fn random_access_and_iteration() {
+    let payloads = common::payloads(60, 0..300);
+    let builders = [
+        Builder::new(),
+        Builder::new().varint().little_endian(),
+        Builder::new().checksum(Checksum::XxHash64),
+    ];
+    for builder in builders {
+        let bytes = write_file(&builder, &payloads, true);
+        let mut reader = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+        assert_eq!(reader.len(), payloads.len());
+
+        for i in [7, 0, 59, 58, 3, 3, 4] {
+            assert_eq!(reader.read_record(i).unwrap(), payloads[i]);
+        }
+
+        let forward: Vec<_> = reader.records().map(|x| x.unwrap()).collect();
+        assert_eq!(forward, payloads);
+
+        let mut backward: Vec<_> = reader.records().rev().map(|x| x.unwrap()).collect();
+        backward.reverse();
+        assert_eq!(backward, payloads);
+
+        let mut records = reader.records_in(10..20);
+        assert_eq!(records.len(), 10);
+        assert_eq!(records.next().unwrap().unwrap(), payloads[10]);
+        assert_eq!(records.next_back().unwrap().unwrap(), payloads[19]);
+        assert_eq!(records.len(), 8);
+    }
+}
+
+#[test]
+fn empty_file() {
+    let bytes = write_file(&Builder::new(), &[], true);
+    assert_eq!(bytes.len(), HEADER_LEN + 8 + TRAILER_LEN);
+
+    let mut reader = FileReader::new(Cursor::new(&bytes)).unwrap();
+    assert!(reader.is_empty());
+    assert_eq!(reader.records().count(), 0);
+    assert_eq!(reader.split(4), vec![]);
+    assert_eq!(
+        error_of(reader.read_record(0).unwrap_err()),
+        Error::RecordOutOfRange { index: 0, len: 0 }
+    );
+}
+
+#[test]
+fn record_out_of_range() {
+    let bytes = write_file(&Builder::new(), &common::payloads(60, 0..300), true);
+    let mut reader = FileReader::new(Cursor::new(&bytes)).unwrap();
+    let error = reader.read_record(60).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(
+        reader.read_record(59).unwrap(),
+        common::payloads(60, 0..300)[59]
+    );
+}
+
+#[test]
+fn invalid_file_header() {
+    let bytes = write_file(&Builder::new(), &common::payloads(60, 0..300), true);
+
+    for len in [0, 3, 7] {
+        let error = FileReader::new(Cursor::new(&bytes[..len])).unwrap_err();
+        assert_eq!(error_of(error), Error::InvalidFileHeader);
+    }
+
+    let mut corrupted = bytes.clone();
+    corrupted[0] = b'X';
+    let error = FileReader::new(Cursor::new(&corrupted)).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let mut corrupted = bytes.clone();
+    corrupted[4] = 2;
+    let error = Builder::new()
+        .recover_file_reader(Cursor::new(&corrupted))
+        .unwrap_err();
+    assert_eq!(
+        error_of(error),
+        Error::UnsupportedFileVersion { version: 2 }
+    );
+}
+
+#[test]
+fn corrupt_index_is_detected() {
+    let payloads = common::payloads(60, 0..300);
+    let bytes = write_file(&Builder::new(), &payloads, true);
+    let index_offset = bytes.len() - TRAILER_LEN - 8 * (payloads.len() + 1);
+
+    for i in index_offset..bytes.len() - 4 {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 0x40;
+        let error = FileReader::new(Cursor::new(&corrupted)).unwrap_err();
+        assert_eq!(error_of(error), Error::CorruptFileIndex, "byte {i}");
+    }
+
+    let mut corrupted = bytes.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    let error = FileReader::new(Cursor::new(&corrupted)).unwrap_err();
+    assert_eq!(error_of(error), Error::MissingFileIndex);
+}
+
+#[test]
+fn recover_file_without_index() {
+    let payloads = common::payloads(60, 0..300);
+    let builder = Builder::new().varint();
+    let bytes = write_file(&builder, &payloads, false);
+
+    let error = builder.new_file_reader(Cursor::new(&bytes)).unwrap_err();
+    assert_eq!(error_of(error), Error::MissingFileIndex);
+
+    let mut reader = builder.recover_file_reader(Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.records_end(), bytes.len() as u64);
+    let records: Vec<_> = reader.records().map(|x| x.unwrap()).collect();
+    assert_eq!(records, payloads);
+}
+
+#[test]
+fn recover_truncated_file_at_every_length() {
+    let payloads: Vec<_> = common::payloads(60, 0..300).into_iter().take(12).collect();
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let bytes = write_file(&builder, &payloads, true);
+    let indexed = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+    let offsets = indexed.offsets().to_vec();
+    let ends: Vec<_> = offsets[1..]
+        .iter()
+        .copied()
+        .chain([indexed.records_end()])
+        .collect();
+
+    for len in HEADER_LEN..=bytes.len() {
+        let truncated = &bytes[..len];
+        let mut reader = builder.recover_file_reader(Cursor::new(truncated)).unwrap();
+
+        let num_complete = ends.iter().filter(|x| **x <= len as u64).count();
+        assert_eq!(reader.len(), num_complete, "len = {len}");
+        assert_eq!(reader.offsets(), &offsets[..num_complete]);
+        let records: Vec<_> = reader.records().map(|x| x.unwrap()).collect();
+        assert_eq!(records, payloads[..num_complete], "len = {len}");
+
+        if len < bytes.len() {
+            assert!(builder.new_file_reader(Cursor::new(truncated)).is_err());
+        }
+    }
+}
+
+#[test]
+fn recovery_stops_at_a_corrupted_record() {
+    let payloads = common::payloads(60, 0..300);
+    let builder = Builder::new().checksum(Checksum::Crc32c);
+    let mut bytes = write_file(&builder, &payloads, false);
+    let offsets = builder
+        .recover_file_reader(Cursor::new(&bytes))
+        .unwrap()
+        .offsets()
+        .to_vec();
+    bytes[offsets[20] as usize + 6] ^= 1;
+
+    let mut reader = builder.recover_file_reader(Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.len(), 20);
+    assert_eq!(reader.records_end(), offsets[20]);
+    assert_eq!(reader.read_record(19).unwrap(), payloads[19]);
+}
+
+#[test]
+fn corrupted_record_fails_to_read() {
+    let payloads = common::payloads(60, 0..300);
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let mut bytes = write_file(&builder, &payloads, true);
+    let mut reader = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+    let offset = reader.offsets()[5] as usize;
+    assert_eq!(reader.read_record(5).unwrap(), payloads[5]);
+
+    bytes[offset + 4] ^= 1;
+    let mut reader = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+    let error = reader.read_record(5).unwrap_err();
+    assert!(matches!(error_of(error), Error::ChecksumMismatch { .. }));
+    assert_eq!(reader.read_record(6).unwrap(), payloads[6]);
+}
+
+#[test]
+fn split_covers_the_records() {
+    let payloads = common::payloads(60, 0..300);
+    let bytes = write_file(&Builder::new(), &payloads, true);
+    let reader = FileReader::new(Cursor::new(&bytes)).unwrap();
+
+    for num_parts in [0, 1, 2, 3, 7, 60, 100] {
+        let ranges = reader.split(num_parts);
+        assert!(ranges.len() <= num_parts.max(1));
+        assert!(ranges.iter().all(|x| !x.is_empty()));
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, payloads.len());
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+    }
+
+    // parts are balanced by bytes up to the length of a record
+    let ranges = reader.split(4);
+    assert_eq!(ranges.len(), 4);
+    let total = reader.byte_range(0..payloads.len());
+    let max_record = 4 + 299;
+    for range in ranges {
+        let bytes = reader.byte_range(range);
+        let target = (total.end - total.start) / 4;
+        assert!((bytes.end - bytes.start).abs_diff(target) <= max_record);
+    }
+}
+
+#[test]
+fn parallel_reading_of_parts() {
+    let payloads = common::payloads(60, 0..300);
+    let builder = Builder::new().checksum(Checksum::Crc32c);
+    let bytes = write_file(&builder, &payloads, true);
+    let reader = builder.new_file_reader(Cursor::new(&bytes)).unwrap();
+    let ranges = reader.split(4);
+
+    let parts: Vec<Vec<Vec<u8>>> = std::thread::scope(|s| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                let mut reader = reader.with_reader(Cursor::new(&bytes));
+                let range = range.clone();
+                s.spawn(move || reader.records_in(range).map(|x| x.unwrap()).collect())
+            })
+            .collect();
+        handles.into_iter().map(|x| x.join().unwrap()).collect()
+    });
+    assert_eq!(parts.concat(), payloads);
+
+    // in memory, the byte ranges are decoded without copying
+    let decoder = builder.new_decoder();
+    let parts: Vec<Vec<&[u8]>> = std::thread::scope(|s| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                let range = reader.byte_range(range.clone());
+                let part = &bytes[range.start as usize..range.end as usize];
+                let decoder = &decoder;
+                s.spawn(move || decoder.frames(part).map(|x| x.unwrap()).collect())
+            })
+            .collect();
+        handles.into_iter().map(|x| x.join().unwrap()).collect()
+    });
+    assert_eq!(parts.concat(), payloads);
+}
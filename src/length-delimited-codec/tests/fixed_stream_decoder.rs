@@ -0,0 +1,185 @@
+use length_delimited_codec::{
+    Builder, Checksum, Encoder, Error, FixedStreamDecoder, OversizePolicy,
+};
+
+mod common;
+
+use common::{SHORT_PAYLOADS, encode_to_slice_all, layouts};
+
+fn drain(decoder: &mut FixedStreamDecoder, frames: &mut Vec<Vec<u8>>) {
+    while let Some(frame) = decoder.next_frame().unwrap() {
+        frames.push(frame.to_vec());
+    }
+}
+
+/// Feeds all of the `chunk`, pulling the complete frames whenever the buffer is full.
+fn feed_all(decoder: &mut FixedStreamDecoder, mut chunk: &[u8], frames: &mut Vec<Vec<u8>>) {
+    while !chunk.is_empty() {
+        let len = decoder.feed(chunk);
+        assert!(len > 0, "buffer is full");
+        chunk = &chunk[len..];
+        drain(decoder, frames);
+    }
+}
+
+/// Frames of the `bytes` as yielded by the decoder of the `builder`.
+fn decode_all(builder: &Builder, bytes: &[u8]) -> Vec<Vec<u8>> {
+    let decoder = builder.new_decoder();
+    decoder.frames(bytes).map(|x| x.unwrap().to_vec()).collect()
+}
+
+#[test]
+fn every_split_point() {
+    for builder in layouts() {
+        let mut encoded = [0; 512];
+        let bytes = encode_to_slice_all(&builder.new_encoder(), &SHORT_PAYLOADS, &mut encoded);
+        let expected = decode_all(&builder, bytes);
+        let max_frame_len = builder.new_encoder().encoded_len(200);
+
+        for capacity in [max_frame_len, 256, 1024] {
+            for split in 0..=bytes.len() {
+                let mut buffer = [0; 1024];
+                let mut decoder = builder.new_fixed_stream_decoder(&mut buffer[..capacity]);
+                let mut frames = vec![];
+
+                feed_all(&mut decoder, &bytes[..split], &mut frames);
+                feed_all(&mut decoder, &bytes[split..], &mut frames);
+
+                assert_eq!(frames, expected, "capacity = {capacity}, split = {split}");
+                assert_eq!(decoder.buffered_len(), 0);
+                assert_eq!(decoder.finish(), Ok(()));
+            }
+        }
+    }
+}
+
+#[test]
+fn one_byte_at_a_time_into_unfilled() {
+    for builder in layouts() {
+        let mut encoded = [0; 512];
+        let bytes = encode_to_slice_all(&builder.new_encoder(), &SHORT_PAYLOADS, &mut encoded);
+        let expected = decode_all(&builder, bytes);
+
+        let mut buffer = [0; 256];
+        let mut decoder = builder.new_fixed_stream_decoder(&mut buffer);
+        let mut frames = vec![];
+        for byte in bytes {
+            decoder.unfilled_mut()[0] = *byte;
+            decoder.commit(1);
+            drain(&mut decoder, &mut frames);
+        }
+        assert_eq!(frames, expected);
+    }
+}
+
+#[test]
+fn frame_yielded_as_soon_as_complete() {
+    let mut encoded = [0; 7];
+    let bytes = encode_to_slice_all(&Encoder::new(), &[b"abc"], &mut encoded);
+    let mut buffer = [0; 7];
+    let mut decoder = FixedStreamDecoder::new(&mut buffer);
+    assert_eq!(decoder.capacity(), 7);
+
+    decoder.feed(&bytes[..bytes.len() - 1]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+
+    decoder.feed(&bytes[bytes.len() - 1..]);
+    assert_eq!(decoder.next_frame(), Ok(Some(&b"abc"[..])));
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(decoder.unfilled_mut().len(), 7);
+}
+
+#[test]
+fn frame_longer_than_the_buffer() {
+    let payloads: [&[u8]; 3] = [&[1; 4], &[2; 20], &[3; 4]];
+    let mut encoded = [0; 40];
+    let bytes = encode_to_slice_all(&Encoder::new(), &payloads, &mut encoded);
+
+    let mut buffer = [0; 16];
+    let mut decoder = FixedStreamDecoder::new(&mut buffer);
+    assert_eq!(decoder.feed(bytes), 16);
+    assert_eq!(decoder.next_frame(), Ok(Some(payloads[0])));
+    let error = Error::BufferTooSmall {
+        needed: 24,
+        available: 16,
+    };
+    assert_eq!(decoder.next_frame(), Err(error.clone()));
+    assert_eq!(decoder.next_frame(), Err(error));
+
+    // oversized frames are discarded as they are received
+    let builder = Builder::new()
+        .max_frame_length(10)
+        .oversize_policy(OversizePolicy::Discard);
+    for capacity in [8, 16, 64] {
+        let mut buffer = [0; 64];
+        let mut decoder = builder.new_fixed_stream_decoder(&mut buffer[..capacity]);
+        let mut frames = vec![];
+        for byte in bytes {
+            feed_all(&mut decoder, std::slice::from_ref(byte), &mut frames);
+        }
+        assert_eq!(frames, [payloads[0], payloads[2]], "capacity = {capacity}");
+        assert_eq!(decoder.num_discarded(), 1);
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+}
+
+#[test]
+fn checksum_mismatch_consumes_the_frame() {
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let payloads: [&[u8]; 3] = [b"first", b"second", b"third"];
+    let mut encoded = [0; 40];
+    let len = encode_to_slice_all(&builder.new_encoder(), &payloads, &mut encoded).len();
+    let bytes = &mut encoded[..len];
+    bytes[13 + 4] ^= 1;
+
+    let mut buffer = [0; 32];
+    let mut decoder = builder.new_fixed_stream_decoder(&mut buffer);
+    feed_all(&mut decoder, &bytes[..13], &mut vec![]);
+    decoder.feed(&bytes[13..]);
+    assert!(matches!(
+        decoder.next_frame(),
+        Err(Error::ChecksumMismatch {
+            frame_index: 1,
+            offset: 13,
+            ..
+        })
+    ));
+    assert_eq!(decoder.next_frame(), Ok(Some(&b"third"[..])));
+}
+
+#[test]
+fn finish_reports_incomplete_frame() {
+    let mut encoded = [0; 10];
+    let bytes = encode_to_slice_all(&Encoder::new(), &[b"abcdef"], &mut encoded);
+
+    let mut buffer = [0; 16];
+    let mut decoder = FixedStreamDecoder::new(&mut buffer);
+    decoder.feed(&bytes[..2]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(
+        decoder.finish(),
+        Err(Error::TruncatedHeader {
+            needed: 4,
+            available: 2
+        })
+    );
+
+    decoder.feed(&bytes[2..7]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(
+        decoder.finish(),
+        Err(Error::TruncatedPayload {
+            expected: 6,
+            available: 3
+        })
+    );
+}
+
+#[test]
+#[should_panic]
+fn commit_beyond_the_buffer() {
+    let mut buffer = [0; 4];
+    let mut decoder = FixedStreamDecoder::new(&mut buffer);
+    decoder.commit(3);
+    decoder.commit(2);
+}
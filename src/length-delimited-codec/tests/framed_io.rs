@@ -0,0 +1,316 @@
+#![cfg(feature = "std")]
+
+use length_delimited_codec::{
+    Builder, Checksum, ChecksumPosition, Compression, Encoder, FramedReader, FramedWriter,
+};
+use std::io::{self, Cursor, Read, Write};
+
+mod common;
+
+use common::encode_all;
+
+fn payloads() -> Vec<Vec<u8>> {
+    vec![
+        b"first".to_vec(),
+        vec![],
+        (0..1000).map(|x| x as u8).collect(),
+        b"last frame".to_vec(),
+    ]
+}
+
+fn read_all<R: Read>(reader: &mut FramedReader<R>) -> io::Result<Vec<Vec<u8>>> {
+    let mut frames = vec![];
+    while let Some(frame) = reader.read_frame()? {
+        frames.push(frame.to_vec());
+    }
+    Ok(frames)
+}
+
+/// Reader returning at most `chunk_len` bytes per read, and failing every other read
+/// with `Interrupted`.
+struct ChunkedReader<R> {
+    inner: R,
+    chunk_len: usize,
+    interrupt: bool,
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        match self.interrupt {
+            true => Err(io::ErrorKind::Interrupted.into()),
+            false => {
+                let len = buf.len().min(self.chunk_len);
+                self.inner.read(&mut buf[..len])
+            }
+        }
+    }
+}
+
+/// Writer accepting at most `chunk_len` bytes per write, and failing every other write
+/// with `Interrupted`.
+struct ChunkedWriter {
+    inner: Vec<u8>,
+    chunk_len: usize,
+    interrupt: bool,
+}
+
+impl Write for ChunkedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        match self.interrupt {
+            true => Err(io::ErrorKind::Interrupted.into()),
+            false => {
+                let len = buf.len().min(self.chunk_len);
+                self.inner.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn read_from_cursor() {
+    let bytes = encode_all(&Encoder::new(), &payloads());
+    let mut reader = FramedReader::new(Cursor::new(bytes));
+    assert_eq!(read_all(&mut reader).unwrap(), payloads());
+    assert_eq!(reader.read_frame().unwrap(), None);
+}
+
+#[test]
+fn read_in_chunks_with_interrupts() {
+    for builder in [Builder::new(), Builder::new().varint()] {
+        let bytes = encode_all(&builder.new_encoder(), &payloads());
+        for chunk_len in [1, 2, 3, 7, 64, 4096] {
+            let chunked = ChunkedReader {
+                inner: Cursor::new(&bytes),
+                chunk_len,
+                interrupt: false,
+            };
+            let mut reader = builder.new_framed_reader(chunked);
+            assert_eq!(read_all(&mut reader).unwrap(), payloads());
+        }
+    }
+}
+
+#[test]
+fn reader_stops_at_frame_boundary() {
+    let mut bytes = encode_all(&Encoder::new(), &[b"frame".to_vec()]);
+    bytes.extend_from_slice(b"trailing");
+
+    let mut reader = FramedReader::new(Cursor::new(bytes));
+    assert_eq!(reader.read_frame().unwrap(), Some(&b"frame"[..]));
+
+    let mut rest = vec![];
+    reader.into_inner().read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"trailing");
+}
+
+#[test]
+fn eof_in_the_middle_of_a_frame() {
+    for builder in [Builder::new(), Builder::new().varint()] {
+        let payloads = payloads();
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        let boundaries: Vec<_> = payloads
+            .iter()
+            .scan(0, |end, x| {
+                *end += builder.new_encoder().encoded_len(x.len());
+                Some(*end)
+            })
+            .collect();
+
+        for len in 0..bytes.len() {
+            let mut reader = builder.new_framed_reader(&bytes[..len]);
+            let num_complete = boundaries.iter().filter(|x| **x <= len).count();
+            let result = read_all(&mut reader);
+            match boundaries.contains(&len) || len == 0 {
+                true => assert_eq!(result.unwrap(), payloads[..num_complete]),
+                false => {
+                    let error = result.unwrap_err();
+                    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof, "len = {len}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_frame_is_invalid_data() {
+    let mut reader = Builder::new()
+        .max_frame_length(4)
+        .new_framed_reader(&[0u8, 0, 0, 5, 1, 2, 3, 4, 5][..]);
+    let error = reader.read_frame().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn write_frames() {
+    let mut writer = FramedWriter::new(vec![]);
+    for payload in payloads() {
+        writer.write_frame(&payload).unwrap();
+    }
+    writer.flush().unwrap();
+    assert_eq!(
+        writer.into_inner(),
+        encode_all(&Encoder::new(), &payloads())
+    );
+}
+
+#[test]
+fn short_and_interrupted_writes() {
+    for chunk_len in [1, 3, 64] {
+        let chunked = ChunkedWriter {
+            inner: vec![],
+            chunk_len,
+            interrupt: false,
+        };
+        let mut writer = FramedWriter::new(chunked);
+        for payload in payloads() {
+            writer.write_frame(&payload).unwrap();
+        }
+        let bytes = writer.into_inner().inner;
+        assert_eq!(bytes, encode_all(&Encoder::new(), &payloads()));
+    }
+}
+
+#[test]
+fn unencodable_payload_writes_nothing() {
+    let mut writer = Builder::new().max_frame_length(4).new_framed_writer(vec![]);
+    let error = writer.write_frame(b"too long").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(writer.get_ref().is_empty());
+}
+
+#[test]
+fn write_then_read() {
+    let builder = Builder::new().length_field_length(2).little_endian();
+    let mut writer = builder.new_framed_writer(vec![]);
+    for payload in payloads() {
+        writer.write_frame(&payload).unwrap();
+    }
+
+    let mut reader = builder.new_framed_reader(Cursor::new(writer.into_inner()));
+    assert_eq!(read_all(&mut reader).unwrap(), payloads());
+}
+
+/// Writer accepting at most `chunk_len` bytes per vectored write, and counting the writes.
+struct VectoredWriter {
+    inner: Vec<u8>,
+    chunk_len: usize,
+    num_writes: usize,
+}
+
+impl Write for VectoredWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[io::IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        self.num_writes += 1;
+        let mut len = 0;
+        for buf in bufs {
+            let n = buf.len().min(self.chunk_len - len);
+            self.inner.extend_from_slice(&buf[..n]);
+            len += n;
+            if len == self.chunk_len {
+                break;
+            }
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn vectored_writes_of_all_layouts() {
+    let builders = [
+        Builder::new(),
+        Builder::new().checksum(Checksum::Crc32c),
+        Builder::new()
+            .checksum(Checksum::XxHash64)
+            .checksum_position(ChecksumPosition::Prepend),
+        Builder::new().length_field_offset(2).varint(),
+        Builder::new()
+            .sync_marker(b"SYNC")
+            .compression(Compression::Lz4),
+    ];
+    let payloads = common::payloads(1000, 2..1502);
+    for builder in builders {
+        let expected = encode_all(&builder.new_encoder(), &payloads);
+        for chunk_len in [5, 1000, usize::MAX] {
+            let vectored = || VectoredWriter {
+                inner: vec![],
+                chunk_len,
+                num_writes: 0,
+            };
+
+            let mut writer = builder.new_framed_writer(vectored());
+            for payload in &payloads {
+                writer.write_frame(payload).unwrap();
+            }
+            assert_eq!(writer.into_inner().inner, expected);
+
+            let mut writer = builder.new_framed_writer(vectored());
+            writer.write_frames(&payloads).unwrap();
+            assert_eq!(writer.into_inner().inner, expected);
+        }
+    }
+}
+
+#[test]
+fn frames_are_batched() {
+    let payloads = common::payloads(1000, 2..1502);
+    let mut writer = FramedWriter::new(VectoredWriter {
+        inner: vec![],
+        chunk_len: usize::MAX,
+        num_writes: 0,
+    });
+    writer.write_frames(&payloads).unwrap();
+    writer.write_frames(&[b"last"]).unwrap();
+    writer.write_frames::<Vec<u8>>(&[]).unwrap();
+
+    let writer = writer.into_inner();
+    assert_eq!(writer.num_writes, payloads.len().div_ceil(256) + 1);
+    let mut expected = encode_all(&Encoder::new(), &payloads);
+    Encoder::new().encode(b"last", &mut expected).unwrap();
+    assert_eq!(writer.inner, expected);
+}
+
+#[test]
+fn batch_stops_at_unencodable_payload() {
+    let payloads = [vec![1; 10], vec![2; 300], vec![3; 5000], vec![4; 10]];
+    for compression in [None, Some(Compression::Lz4)] {
+        let mut builder = Builder::new().max_frame_length(1000);
+        if let Some(compression) = compression {
+            builder = builder.compression(compression);
+        }
+        let mut writer = builder.new_framed_writer(vec![]);
+        let error = writer.write_frames(&payloads).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        let frames = builder.new_decoder().decode_all(writer.get_ref()).unwrap();
+        assert_eq!(frames, payloads[..2]);
+    }
+}
+
+#[test]
+fn short_and_interrupted_batched_writes() {
+    for chunk_len in [1, 3, 64] {
+        let chunked = ChunkedWriter {
+            inner: vec![],
+            chunk_len,
+            interrupt: false,
+        };
+        let mut writer = FramedWriter::new(chunked);
+        writer.write_frames(&payloads()).unwrap();
+        let bytes = writer.into_inner().inner;
+        assert_eq!(bytes, encode_all(&Encoder::new(), &payloads()));
+    }
+}
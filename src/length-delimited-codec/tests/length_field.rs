@@ -0,0 +1,309 @@
+use length_delimited_codec::{Builder, Error};
+
+/// Encodes the `payload` with the `builder` and checks the bytes against `expected`;
+/// then decodes them and checks the frame against `decoded`.
+fn check(builder: Builder, payload: &[u8], expected: &[u8], decoded: &[u8]) {
+    let mut bytes = [0; 300];
+    let len = builder
+        .new_encoder()
+        .encode_to_slice(payload, &mut bytes)
+        .unwrap();
+    assert_eq!(&bytes[..len], expected);
+
+    let (frame, len) = builder.new_decoder().decode_frame_ref(expected).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(len, expected.len());
+
+    // trailing bytes of the next frame are not consumed
+    bytes[len..len + 2].copy_from_slice(&[0xAA, 0xBB]);
+    let with_next = &bytes[..len + 2];
+    let (frame, len) = builder.new_decoder().decode_frame_ref(with_next).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(len, expected.len());
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut bytes = vec![];
+        builder.new_encoder().encode(payload, &mut bytes).unwrap();
+        assert_eq!(bytes, expected);
+
+        for src in [expected, with_next] {
+            let (frame, len) = builder.new_decoder().decode_frame(src).unwrap();
+            assert_eq!(frame, decoded);
+            assert_eq!(len, expected.len());
+        }
+    }
+}
+
+#[test]
+fn widths_and_endianness() {
+    let body = b"abc";
+
+    check(
+        Builder::new().length_field_length(1),
+        body,
+        b"\x03abc",
+        body,
+    );
+
+    check(
+        Builder::new().length_field_length(2).big_endian(),
+        body,
+        b"\x00\x03abc",
+        body,
+    );
+    check(
+        Builder::new().length_field_length(2).little_endian(),
+        body,
+        b"\x03\x00abc",
+        body,
+    );
+
+    check(
+        Builder::new().length_field_length(3).big_endian(),
+        body,
+        b"\x00\x00\x03abc",
+        body,
+    );
+    check(
+        Builder::new().length_field_length(3).little_endian(),
+        body,
+        b"\x03\x00\x00abc",
+        body,
+    );
+
+    check(
+        Builder::new().length_field_length(4).big_endian(),
+        body,
+        b"\x00\x00\x00\x03abc",
+        body,
+    );
+    check(
+        Builder::new().length_field_length(4).little_endian(),
+        body,
+        b"\x03\x00\x00\x00abc",
+        body,
+    );
+
+    check(
+        Builder::new().length_field_length(8).big_endian(),
+        body,
+        b"\x00\x00\x00\x00\x00\x00\x00\x03abc",
+        body,
+    );
+    check(
+        Builder::new().length_field_length(8).little_endian(),
+        body,
+        b"\x03\x00\x00\x00\x00\x00\x00\x00abc",
+        body,
+    );
+}
+
+#[test]
+fn multi_byte_values() {
+    let body = vec![7u8; 0x0102];
+    let expected = |prefix: &[u8]| [prefix, &body].concat();
+
+    check(
+        Builder::new().length_field_length(2).big_endian(),
+        &body,
+        &expected(&[0x01, 0x02]),
+        &body,
+    );
+    check(
+        Builder::new().length_field_length(2).little_endian(),
+        &body,
+        &expected(&[0x02, 0x01]),
+        &body,
+    );
+    check(
+        Builder::new().length_field_length(3).big_endian(),
+        &body,
+        &expected(&[0x00, 0x01, 0x02]),
+        &body,
+    );
+    check(
+        Builder::new().length_field_length(3).little_endian(),
+        &body,
+        &expected(&[0x02, 0x01, 0x00]),
+        &body,
+    );
+}
+
+#[test]
+fn length_field_offset() {
+    // 2-byte header, header is stripped by default
+    check(
+        Builder::new().length_field_offset(2).length_field_length(2),
+        b"\xCA\xFEhello",
+        b"\xCA\xFE\x00\x05hello",
+        b"hello",
+    );
+
+    // header and length field are kept
+    check(
+        Builder::new()
+            .length_field_offset(2)
+            .length_field_length(2)
+            .num_skip(0),
+        b"\xCA\xFEhello",
+        b"\xCA\xFE\x00\x05hello",
+        b"\xCA\xFE\x00\x05hello",
+    );
+
+    // only the header is stripped
+    check(
+        Builder::new()
+            .length_field_offset(2)
+            .length_field_length(2)
+            .num_skip(2),
+        b"\xCA\xFEhello",
+        b"\xCA\xFE\x00\x05hello",
+        b"\x00\x05hello",
+    );
+}
+
+#[test]
+fn length_adjustment() {
+    // length counts the length field itself
+    check(
+        Builder::new().length_field_length(2).length_adjustment(-2),
+        b"hello",
+        b"\x00\x07hello",
+        b"hello",
+    );
+
+    // length counts the header and the length field, entire frame is yielded
+    check(
+        Builder::new()
+            .length_field_offset(1)
+            .length_field_length(2)
+            .little_endian()
+            .length_adjustment(-3)
+            .num_skip(0),
+        b"\x09hello",
+        b"\x09\x08\x00hello",
+        b"\x09\x08\x00hello",
+    );
+
+    // a 2-byte trailer follows the body counted by the length field
+    check(
+        Builder::new().length_field_length(1).length_adjustment(2),
+        b"hello\xAB\xCD",
+        b"\x05hello\xAB\xCD",
+        b"hello\xAB\xCD",
+    );
+}
+
+#[test]
+fn offset_adjustment_and_skip_combined() {
+    // 1-byte version, 3-byte big endian length counting everything after the
+    // length field except a 1-byte trailing flag, skip only the version byte
+    check(
+        Builder::new()
+            .length_field_offset(1)
+            .length_field_length(3)
+            .length_adjustment(1)
+            .num_skip(1),
+        b"\x01data\xFF",
+        b"\x01\x00\x00\x04data\xFF",
+        b"\x00\x00\x04data\xFF",
+    );
+
+    // 4-byte little endian length at offset 4 counting the entire frame
+    check(
+        Builder::new()
+            .length_field_offset(4)
+            .length_field_length(4)
+            .little_endian()
+            .length_adjustment(-8),
+        b"HEADxy",
+        b"HEAD\x0A\x00\x00\x00xy",
+        b"xy",
+    );
+}
+
+#[test]
+fn truncated_header_with_offset() {
+    let decoder = Builder::new()
+        .length_field_offset(3)
+        .length_field_length(2)
+        .new_decoder();
+    assert_eq!(
+        decoder.decode_frame_ref(b"\x01\x02\x03\x00"),
+        Err(Error::TruncatedHeader {
+            needed: 5,
+            available: 4
+        })
+    );
+
+    let encoder = Builder::new().length_field_offset(3).new_encoder();
+    let error = Error::TruncatedHeader {
+        needed: 3,
+        available: 2,
+    };
+    assert_eq!(
+        encoder.encode_to_slice(b"\x01\x02", &mut [0; 16]),
+        Err(error.clone())
+    );
+    #[cfg(feature = "alloc")]
+    assert_eq!(encoder.encode(b"\x01\x02", &mut vec![]), Err(error));
+}
+
+#[test]
+fn invalid_lengths() {
+    // length counting the length field cannot be smaller than the length field
+    let decoder = Builder::new()
+        .length_field_length(2)
+        .length_adjustment(-2)
+        .new_decoder();
+    assert_eq!(
+        decoder.decode_frame_ref(b"\x00\x01"),
+        Err(Error::InvalidLength { value: -1 })
+    );
+
+    // body length does not fit into a 1-byte length field
+    let encoder = Builder::new().length_field_length(1).new_encoder();
+    let mut bytes = [0; 300];
+    assert_eq!(
+        encoder.encode_to_slice(&[0; 256], &mut bytes),
+        Err(Error::InvalidLength { value: 256 })
+    );
+    assert!(encoder.encode_to_slice(&[0; 255], &mut bytes).is_ok());
+    #[cfg(feature = "alloc")]
+    {
+        assert_eq!(
+            encoder.encode(&[0; 256], &mut vec![]),
+            Err(Error::InvalidLength { value: 256 })
+        );
+        assert!(encoder.encode(&[0; 255], &mut vec![]).is_ok());
+    }
+}
+
+#[test]
+fn max_frame_length() {
+    let builder = Builder::new().max_frame_length(4);
+    assert_eq!(
+        builder
+            .new_encoder()
+            .encode_to_slice(b"hello", &mut [0; 16]),
+        Err(Error::FrameTooLarge { len: 5, max: 4 })
+    );
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        builder.new_encoder().encode(b"hello", &mut vec![]),
+        Err(Error::FrameTooLarge { len: 5, max: 4 })
+    );
+    assert_eq!(
+        builder
+            .new_decoder()
+            .decode_frame_ref(b"\x00\x00\x00\x05hello"),
+        Err(Error::FrameTooLarge { len: 5, max: 4 })
+    );
+}
+
+#[test]
+#[should_panic]
+fn unsupported_length_field_length() {
+    let _ = Builder::new().length_field_length(5);
+}
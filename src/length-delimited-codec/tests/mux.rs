@@ -0,0 +1,294 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Error,
+    mux::{FLAG_CONTINUATION, FLAG_END, FLAG_START, Fragment},
+};
+use std::io::{self, Read, Write};
+
+mod common;
+
+use common::XorShift;
+
+/// Messages of each of the `num_channels` channels, identified by their channel and index.
+fn messages(rng: &mut XorShift, num_channels: usize, num_messages: usize) -> Vec<Vec<Vec<u8>>> {
+    (0..num_channels)
+        .map(|channel| {
+            (0..num_messages)
+                .map(|i| {
+                    let len = rng.below(300);
+                    let seed = (channel * 31 + i) as u8;
+                    (0..len).map(|x| seed.wrapping_add(x as u8)).collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Encodes the fragments of all messages, interleaving the channels randomly while keeping
+/// the order of the fragments of each channel.
+fn interleave(
+    builder: &Builder,
+    rng: &mut XorShift,
+    max_fragment_len: usize,
+    messages: &[Vec<Vec<u8>>],
+) -> Vec<u8> {
+    let mut mux = builder.new_multiplexer(max_fragment_len);
+    let mut queues: Vec<Vec<Fragment>> = messages
+        .iter()
+        .enumerate()
+        .map(|(channel, messages)| {
+            let mut fragments: Vec<_> = messages
+                .iter()
+                .flat_map(|x| mux.fragments(channel as u64 * 1000, x))
+                .collect();
+            fragments.reverse();
+            fragments
+        })
+        .collect();
+
+    let mut bytes = vec![];
+    while queues.iter().any(|x| !x.is_empty()) {
+        let queue = &mut queues[rng.below(messages.len())];
+        if let Some(fragment) = queue.pop() {
+            mux.encode_fragment(&fragment, &mut bytes).unwrap();
+        }
+    }
+    bytes
+}
+
+fn demux_all(builder: &Builder, chunks: impl Iterator<Item = Vec<u8>>) -> Vec<Vec<Vec<u8>>> {
+    let mut demux = builder.new_demultiplexer(1 << 20, 16);
+    let mut received: Vec<Vec<Vec<u8>>> = vec![];
+    for chunk in chunks {
+        demux.feed(&chunk);
+        while let Some(channel) = demux.next_message().unwrap() {
+            let index = (channel / 1000) as usize;
+            if received.len() <= index {
+                received.resize(index + 1, vec![]);
+            }
+            received[index].push(demux.recv(channel).unwrap());
+        }
+    }
+    assert_eq!(demux.finish(), Ok(()));
+    received
+}
+
+#[test]
+fn interleaved_messages_are_reassembled() {
+    let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+    let builders = [
+        Builder::new(),
+        Builder::new().varint().checksum(Checksum::Crc32c),
+        Builder::new().length_field_length(2).little_endian(),
+    ];
+    for builder in builders {
+        for max_fragment_len in [1, 7, 64, 1000] {
+            let messages = messages(&mut rng, 8, 20);
+            let bytes = interleave(&builder, &mut rng, max_fragment_len, &messages);
+
+            let chunks = bytes.chunks(1 + rng.below(100)).map(<[u8]>::to_vec);
+            assert_eq!(demux_all(&builder, chunks), messages);
+        }
+    }
+}
+
+#[test]
+fn interleaved_messages_through_a_pipe() {
+    let mut rng = XorShift(99);
+    let builder = Builder::new().checksum(Checksum::Crc32);
+    let messages = messages(&mut rng, 16, 50);
+    let bytes = interleave(&builder, &mut rng, 37, &messages);
+
+    let (mut reader, mut writer) = io::pipe().unwrap();
+    let received = std::thread::scope(|s| {
+        s.spawn(|| {
+            let mut rest = bytes.as_slice();
+            let mut rng = XorShift(5);
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rest.len().min(1 + rng.below(500)));
+                writer.write_all(chunk).unwrap();
+                rest = tail;
+            }
+            drop(writer);
+        });
+
+        let chunks = std::iter::from_fn(|| {
+            let mut chunk = vec![0; 256];
+            let len = reader.read(&mut chunk).unwrap();
+            chunk.truncate(len);
+            (len > 0).then_some(chunk)
+        });
+        demux_all(&builder, chunks)
+    });
+    assert_eq!(received, messages);
+}
+
+#[test]
+fn channel_queues() {
+    let builder = Builder::new();
+    let mut mux = builder.new_multiplexer(2);
+    let mut bytes = vec![];
+    mux.encode(3, b"first", &mut bytes).unwrap();
+    mux.encode(1, b"", &mut bytes).unwrap();
+    mux.encode(3, b"second", &mut bytes).unwrap();
+    mux.encode(u64::MAX, b"max", &mut bytes).unwrap();
+
+    let mut demux = builder.new_demultiplexer(100, 16);
+    demux.feed(&bytes);
+    let mut completed = vec![];
+    while let Some(channel) = demux.next_message().unwrap() {
+        completed.push(channel);
+    }
+    assert_eq!(completed, vec![3, 1, 3, u64::MAX]);
+    assert_eq!(
+        demux.ready_channels().collect::<Vec<_>>(),
+        vec![1, 3, u64::MAX]
+    );
+    assert_eq!(demux.num_queued(3), 2);
+
+    assert_eq!(demux.recv(3), Some(b"first".to_vec()));
+    assert_eq!(demux.recv(3), Some(b"second".to_vec()));
+    assert_eq!(demux.recv(3), None);
+    assert_eq!(demux.recv(1), Some(vec![]));
+    assert_eq!(demux.recv(2), None);
+    assert_eq!(demux.ready_channels().collect::<Vec<_>>(), vec![u64::MAX]);
+}
+
+#[test]
+fn fragment_flags() {
+    let mux = Builder::new().new_multiplexer(4);
+    let flags: Vec<_> = mux.fragments(0, b"0123456789").map(|x| x.flags).collect();
+    assert_eq!(
+        flags,
+        vec![FLAG_START, FLAG_CONTINUATION, FLAG_CONTINUATION | FLAG_END]
+    );
+    let fragments: Vec<_> = mux.fragments(0, b"").collect();
+    assert_eq!(fragments.len(), 1);
+    assert_eq!(fragments[0].flags, FLAG_START | FLAG_END);
+}
+
+/// Encodes the fragment `payloads` as they are, bypassing the multiplexer.
+fn encode_raw(builder: &Builder, payloads: &[&[u8]]) -> Vec<u8> {
+    common::encode_all(&builder.new_encoder(), payloads)
+}
+
+#[test]
+fn protocol_errors_drop_the_message() {
+    let builder = Builder::new();
+    let bytes = encode_raw(
+        &builder,
+        &[
+            &[FLAG_CONTINUATION, 5, b'x'], // continuation without a start
+            &[FLAG_START, 5, b'a'],        // start
+            &[FLAG_START, 5, b'b'],        // start interrupting the message
+            &[FLAG_CONTINUATION | FLAG_END, 5, b'c'], // rest of the dropped message
+            &[FLAG_START | FLAG_END, 5, b'd'], // valid message
+            &[0, 5],                       // neither start nor continuation
+            &[FLAG_START],                 // missing channel id
+            &[FLAG_START | FLAG_END, 6, b'e'], // valid message
+        ],
+    );
+
+    let mut demux = builder.new_demultiplexer(100, 16);
+    demux.feed(&bytes);
+    assert_eq!(
+        demux.next_message(),
+        Err(Error::UnexpectedFragment { channel: 5 })
+    );
+    assert_eq!(
+        demux.next_message(),
+        Err(Error::UnexpectedFragment { channel: 5 })
+    );
+    assert_eq!(demux.next_message(), Ok(Some(5)));
+    assert_eq!(demux.recv(5), Some(b"d".to_vec()));
+    assert_eq!(demux.next_message(), Err(Error::InvalidMuxHeader));
+    assert_eq!(demux.next_message(), Err(Error::InvalidMuxHeader));
+    assert_eq!(demux.next_message(), Ok(Some(6)));
+    assert_eq!(demux.next_message(), Ok(None));
+    assert_eq!(demux.finish(), Ok(()));
+}
+
+#[test]
+fn oversized_messages_are_rejected_early() {
+    let builder = Builder::new();
+    let mut mux = builder.new_multiplexer(10);
+    let mut bytes = vec![];
+    mux.encode(1, &[7; 100], &mut bytes).unwrap();
+    mux.encode(2, &[8; 50], &mut bytes).unwrap();
+
+    let mut demux = builder.new_demultiplexer(50, 16);
+    demux.feed(&bytes);
+    assert_eq!(
+        demux.next_message(),
+        Err(Error::MessageTooLarge { len: 60, max: 50 })
+    );
+    // the rest of the oversized message is skipped
+    assert_eq!(demux.next_message(), Ok(Some(2)));
+    assert_eq!(demux.recv(2), Some(vec![8; 50]));
+    assert_eq!(demux.recv(1), None);
+}
+
+#[test]
+fn incomplete_message_at_the_end() {
+    let builder = Builder::new();
+    let mut mux = builder.new_multiplexer(4);
+    let mut bytes = vec![];
+    let fragments: Vec<_> = mux.fragments(9, b"truncated").collect();
+    mux.encode_fragment(&fragments[0], &mut bytes).unwrap();
+
+    let mut demux = builder.new_demultiplexer(100, 16);
+    demux.feed(&bytes);
+    assert_eq!(demux.next_message(), Ok(None));
+    assert_eq!(demux.finish(), Err(Error::IncompleteMessage { channel: 9 }));
+
+    demux.feed(&[0, 0]);
+    assert!(matches!(demux.finish(), Err(Error::TruncatedHeader { .. })));
+}
+
+#[test]
+fn channel_limit() {
+    let builder = Builder::new();
+    let mut mux = builder.new_multiplexer(4);
+    let first: Vec<_> = mux.fragments(1, b"first message").collect();
+    let second: Vec<_> = mux.fragments(2, b"second").collect();
+    let mut bytes = vec![];
+    mux.encode_fragment(&first[0], &mut bytes).unwrap();
+    mux.encode_fragment(&second[0], &mut bytes).unwrap();
+    mux.encode(3, b"third", &mut bytes).unwrap();
+    for fragment in &first[1..] {
+        mux.encode_fragment(fragment, &mut bytes).unwrap();
+    }
+
+    let mut demux = builder.new_demultiplexer(100, 2);
+    demux.feed(&bytes);
+    // each fragment of the new channel is rejected, without keeping state for it
+    for _ in 0..2 {
+        assert_eq!(
+            demux.next_message(),
+            Err(Error::TooManyChannels { channel: 3, max: 2 })
+        );
+        assert_eq!(demux.num_channels(), 2);
+    }
+
+    // a channel with a queued message is tracked until the message is received
+    assert_eq!(demux.next_message(), Ok(Some(1)));
+    assert_eq!(demux.num_channels(), 2);
+    assert_eq!(demux.recv(1), Some(b"first message".to_vec()));
+    assert_eq!(demux.num_channels(), 1);
+
+    let mut bytes = vec![];
+    mux.encode(3, b"third", &mut bytes).unwrap();
+    demux.feed(&bytes);
+    assert_eq!(demux.next_message(), Ok(Some(3)));
+    assert_eq!(demux.recv(3), Some(b"third".to_vec()));
+
+    // a stray last fragment does not leave its channel tracked
+    let bytes = encode_raw(&builder, &[&[FLAG_CONTINUATION | FLAG_END, 4, b'x']]);
+    demux.feed(&bytes);
+    assert_eq!(
+        demux.next_message(),
+        Err(Error::UnexpectedFragment { channel: 4 })
+    );
+    assert_eq!(demux.num_channels(), 1);
+}
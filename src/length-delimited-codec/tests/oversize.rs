@@ -0,0 +1,176 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{Builder, DEFAULT_MAX_FRAME_LENGTH, Error, OversizePolicy};
+#[cfg(feature = "std")]
+use std::io::{self, Cursor};
+
+const MAX: usize = 8;
+
+fn builder(policy: OversizePolicy) -> Builder {
+    Builder::new().max_frame_length(MAX).oversize_policy(policy)
+}
+
+/// Frames of lengths 3, 20 (oversized), 8, 9 (oversized), 0 and 5.
+fn stream() -> (Vec<u8>, Vec<Vec<u8>>) {
+    let mut bytes = vec![];
+    let mut expected = vec![];
+    for (len, value) in [(3, 1), (20, 2), (8, 3), (9, 4), (0, 5), (5, 6)] {
+        bytes.extend_from_slice(&(len as u32).to_be_bytes());
+        bytes.extend(std::iter::repeat_n(value, len));
+        if len <= MAX {
+            expected.push(vec![value; len]);
+        }
+    }
+    (bytes, expected)
+}
+
+#[test]
+fn length_is_checked_before_the_payload_arrives() {
+    // the header alone claims 4 GiB; the frame is rejected without waiting for the payload
+    let header = u32::MAX.to_be_bytes();
+
+    let decoder = Builder::new().new_decoder();
+    assert_eq!(
+        decoder.decode_frame(&header),
+        Err(Error::FrameTooLarge {
+            len: u32::MAX as usize,
+            max: DEFAULT_MAX_FRAME_LENGTH
+        })
+    );
+
+    let mut decoder = Builder::new().new_stream_decoder();
+    decoder.feed(&header);
+    assert!(matches!(
+        decoder.next_frame(),
+        Err(Error::FrameTooLarge { .. })
+    ));
+
+    #[cfg(feature = "std")]
+    {
+        let mut reader = Builder::new().new_framed_reader(&header[..]);
+        let error = reader.read_frame().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn error_policy() {
+    let (bytes, _) = stream();
+    let builder = builder(OversizePolicy::Error);
+    let too_large = Error::FrameTooLarge { len: 20, max: MAX };
+
+    let decoder = builder.new_decoder();
+    assert_eq!(decoder.decode_all(&bytes), Err(too_large.clone()));
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes);
+    assert_eq!(decoder.next_frame(), Ok(Some(vec![1; 3])));
+    assert_eq!(decoder.next_frame(), Err(too_large));
+
+    #[cfg(feature = "std")]
+    {
+        let mut reader = builder.new_framed_reader(Cursor::new(&bytes));
+        assert_eq!(reader.read_frame().unwrap(), Some(&[1u8; 3][..]));
+        assert_eq!(
+            reader.read_frame().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
+
+#[test]
+fn discard_policy_decoder() {
+    let (bytes, expected) = stream();
+    let decoder = builder(OversizePolicy::Discard).new_decoder();
+    assert_eq!(decoder.decode_all(&bytes).unwrap(), expected);
+
+    // the oversized frame is skipped and counted in the consumed bytes
+    let (frame, len) = decoder.decode_frame(&bytes[7..]).unwrap();
+    assert_eq!(frame, vec![3; 8]);
+    assert_eq!(len, 24 + 12);
+
+    // a truncated oversized frame cannot be skipped
+    assert_eq!(
+        decoder.decode_frame(&bytes[7..20]),
+        Err(Error::TruncatedPayload {
+            expected: 20,
+            available: 9
+        })
+    );
+}
+
+#[test]
+fn discard_policy_stream_decoder_every_split_point() {
+    let (bytes, expected) = stream();
+    let builder = builder(OversizePolicy::Discard);
+
+    for split in 0..=bytes.len() {
+        let mut decoder = builder.new_stream_decoder();
+        let mut frames = vec![];
+        for chunk in [&bytes[..split], &bytes[split..]] {
+            decoder.feed(chunk);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames, expected, "split = {split}");
+        assert_eq!(decoder.num_discarded(), 2);
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+}
+
+#[test]
+fn discarded_bytes_are_not_buffered() {
+    let mut decoder = builder(OversizePolicy::Discard).new_stream_decoder();
+    decoder.feed(&1_000_000u32.to_be_bytes());
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(decoder.num_discarded(), 1);
+
+    let chunk = vec![0; 10_000];
+    for _ in 0..100 {
+        decoder.feed(&chunk);
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+    assert_eq!(
+        decoder.finish(),
+        Ok(()),
+        "the oversized frame is skipped entirely"
+    );
+
+    decoder.feed(&[0, 0, 0, 2, 7, 7]);
+    assert_eq!(decoder.next_frame(), Ok(Some(vec![7, 7])));
+}
+
+#[test]
+fn discard_policy_truncated_oversized_frame() {
+    let mut decoder = builder(OversizePolicy::Discard).new_stream_decoder();
+    decoder.feed(&[0, 0, 0, 20, 1, 2, 3]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(
+        decoder.finish(),
+        Err(Error::TruncatedPayload {
+            expected: 20,
+            available: 3
+        })
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn discard_policy_framed_reader() {
+    let (bytes, expected) = stream();
+    let mut reader = builder(OversizePolicy::Discard).new_framed_reader(Cursor::new(&bytes));
+
+    let mut frames = vec![];
+    while let Some(frame) = reader.read_frame().unwrap() {
+        frames.push(frame.to_vec());
+    }
+    assert_eq!(frames, expected);
+    assert_eq!(reader.num_discarded(), 2);
+
+    let mut reader = builder(OversizePolicy::Discard).new_framed_reader(&bytes[7..20]);
+    assert_eq!(
+        reader.read_frame().unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+}
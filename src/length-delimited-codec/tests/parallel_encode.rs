@@ -0,0 +1,86 @@
+#![cfg(feature = "std")]
+
+use length_delimited_codec::{Builder, Encoder, Error};
+
+mod common;
+
+use common::{encode_all, layouts};
+
+#[test]
+fn parallel_matches_sequential() {
+    let payloads = common::payloads(2000, 0..700);
+    // two header bytes precede the length field of a layout
+    let payloads: Vec<_> = payloads.into_iter().filter(|x| x.len() >= 2).collect();
+    for builder in layouts() {
+        let encoder = builder.new_encoder();
+        let expected = encode_all(&encoder, &payloads);
+        assert_eq!(encoder.encode_all_par(&payloads).unwrap(), expected);
+
+        let frames = builder.new_decoder().decode_all(&expected).unwrap();
+        assert_eq!(frames.len(), payloads.len());
+    }
+}
+
+#[test]
+fn parallel_empty_input() {
+    let payloads: Vec<&[u8]> = vec![];
+    assert_eq!(Encoder::new().encode_all_par(&payloads), Ok(vec![]));
+}
+
+#[test]
+fn parallel_reports_invalid_payload() {
+    let payloads = common::payloads(2000, 0..700);
+    let first_invalid = payloads.iter().find(|x| x.len() > 500).unwrap();
+    let encoder = Builder::new().max_frame_length(500).new_encoder();
+    assert_eq!(
+        encoder.encode_all_par(&payloads),
+        Err(Error::FrameTooLarge {
+            len: first_invalid.len(),
+            max: 500
+        })
+    );
+}
+
+#[test]
+fn frame_len_matches_encode() {
+    for builder in layouts() {
+        let encoder = builder.new_encoder();
+        for payload in common::payloads(100, 2..700) {
+            let mut bytes = vec![];
+            encoder.encode(&payload, &mut bytes).unwrap();
+            assert_eq!(encoder.frame_len(&payload), Ok(bytes.len()));
+            assert_eq!(encoder.encoded_len(payload.len()), bytes.len());
+        }
+    }
+}
+
+#[test]
+fn encode_to_slice_matches_encode() {
+    for builder in layouts() {
+        let encoder = builder.new_encoder();
+        for payload in common::payloads(100, 2..700) {
+            let mut expected = vec![];
+            encoder.encode(&payload, &mut expected).unwrap();
+
+            let mut dst = vec![0xAA; expected.len() + 3];
+            assert_eq!(
+                encoder.encode_to_slice(&payload, &mut dst),
+                Ok(expected.len())
+            );
+            assert_eq!(&dst[..expected.len()], expected);
+            assert_eq!(&dst[expected.len()..], &[0xAA; 3]);
+        }
+    }
+}
+
+#[test]
+fn encode_to_slice_too_small() {
+    let mut dst = [0; 5];
+    assert_eq!(
+        Encoder::new().encode_to_slice(b"hi", &mut dst),
+        Err(Error::BufferTooSmall {
+            needed: 6,
+            available: 5
+        })
+    );
+}
@@ -0,0 +1,159 @@
+use length_delimited_codec::{DEFAULT_MAX_FRAME_LENGTH, Decoder, Encoder, Error};
+
+fn payloads() -> Vec<Vec<u8>> {
+    vec![
+        vec![],
+        vec![42],
+        b"hello".to_vec(),
+        (0..=255).collect(),
+        (0..10_000).map(|i| (i % 251) as u8).collect(),
+    ]
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn encode_single() {
+    let mut bytes = vec![];
+    Encoder::new().encode(b"abc", &mut bytes).unwrap();
+    assert_eq!(bytes, vec![0, 0, 0, 3, b'a', b'b', b'c']);
+
+    let mut bytes = vec![];
+    Encoder::new().encode(&[], &mut bytes).unwrap();
+    assert_eq!(bytes, vec![0, 0, 0, 0]);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn round_trip_each() {
+    let encoder = Encoder::new();
+    let decoder = Decoder::new();
+    for payload in payloads() {
+        let mut bytes = vec![];
+        encoder.encode(&payload, &mut bytes).unwrap();
+        assert_eq!(bytes.len(), encoder.encoded_len(payload.len()));
+
+        let (decoded, len) = decoder.decode_frame(&bytes).unwrap();
+        assert_eq!(decoded, payload);
+        assert_eq!(len, bytes.len());
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn round_trip_all() {
+    let encoder = Encoder::new();
+    let mut bytes = vec![];
+    for payload in payloads() {
+        encoder.encode(&payload, &mut bytes).unwrap();
+    }
+
+    let frames = Decoder::new().decode_all(&bytes).unwrap();
+    assert_eq!(frames, payloads());
+}
+
+#[test]
+fn round_trip_in_slices() {
+    let encoder = Encoder::new();
+    let decoder = Decoder::new();
+    let mut bytes = [0; 11_000];
+    let mut len = 0;
+    for payload in payloads() {
+        let frame_len = encoder
+            .encode_to_slice(&payload, &mut bytes[len..])
+            .unwrap();
+        assert_eq!(frame_len, encoder.encoded_len(payload.len()));
+
+        let (decoded, decoded_len) = decoder.decode_frame_ref(&bytes[len..]).unwrap();
+        assert_eq!(decoded, payload);
+        assert_eq!(decoded_len, frame_len);
+        len += frame_len;
+    }
+
+    let frames: Vec<_> = decoder.frames(&bytes[..len]).map(|x| x.unwrap()).collect();
+    assert_eq!(frames, payloads());
+    assert_eq!(decoder.frames(&[]).count(), 0);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn decode_empty_buffer() {
+    assert_eq!(Decoder::new().decode_all(&[]), Ok(vec![]));
+}
+
+#[test]
+fn truncated_header() {
+    let decoder = Decoder::new();
+    for available in 0..4 {
+        let bytes = vec![0; available];
+        assert_eq!(
+            decoder.decode_frame_ref(&bytes),
+            Err(Error::TruncatedHeader {
+                needed: 4,
+                available
+            })
+        );
+    }
+
+    let bytes = [0, 0, 0, 1, 7, 0, 0];
+    let mut frames = decoder.frames(&bytes);
+    assert_eq!(frames.next(), Some(Ok(&[7][..])));
+    assert_eq!(
+        frames.next(),
+        Some(Err(Error::TruncatedHeader {
+            needed: 4,
+            available: 2
+        }))
+    );
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        decoder.decode_all(&bytes),
+        Err(Error::TruncatedHeader {
+            needed: 4,
+            available: 2
+        })
+    );
+}
+
+#[test]
+fn truncated_payload() {
+    let bytes = [0, 0, 0, 5, 1, 2, 3];
+    assert_eq!(
+        Decoder::new().decode_frame_ref(&bytes),
+        Err(Error::TruncatedPayload {
+            expected: 5,
+            available: 3
+        })
+    );
+}
+
+#[test]
+fn frame_too_large() {
+    let payload = vec![0; DEFAULT_MAX_FRAME_LENGTH + 1];
+    let error = Error::FrameTooLarge {
+        len: DEFAULT_MAX_FRAME_LENGTH + 1,
+        max: DEFAULT_MAX_FRAME_LENGTH,
+    };
+    #[cfg(feature = "alloc")]
+    {
+        let mut bytes = vec![1, 2];
+        assert_eq!(
+            Encoder::new().encode(&payload, &mut bytes),
+            Err(error.clone())
+        );
+        assert_eq!(bytes, vec![1, 2]);
+    }
+    let mut bytes = [0; 16];
+    assert_eq!(
+        Encoder::new().encode_to_slice(&payload, &mut bytes),
+        Err(error)
+    );
+
+    let bytes = [0xFF, 0xFF, 0xFF, 0xFF];
+    assert_eq!(
+        Decoder::new().decode_frame_ref(&bytes),
+        Err(Error::FrameTooLarge {
+            len: u32::MAX as usize,
+            max: DEFAULT_MAX_FRAME_LENGTH
+        })
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{Builder, Encoder, Error, StreamDecoder};
+
+mod common;
+
+use common::{encode_all, layouts, short_payloads as payloads};
+
+fn drain(decoder: &mut StreamDecoder, frames: &mut Vec<Vec<u8>>) {
+    while let Some(frame) = decoder.next_frame().unwrap() {
+        frames.push(frame);
+    }
+}
+
+#[test]
+fn every_split_point() {
+    for builder in layouts() {
+        let bytes = encode_all(&builder.new_encoder(), &payloads());
+        let expected = builder.new_decoder().decode_all(&bytes).unwrap();
+
+        for split in 0..=bytes.len() {
+            let mut decoder = builder.new_stream_decoder();
+            let mut frames = vec![];
+
+            decoder.feed(&bytes[..split]);
+            drain(&mut decoder, &mut frames);
+            decoder.feed(&bytes[split..]);
+            drain(&mut decoder, &mut frames);
+
+            assert_eq!(frames, expected, "split = {split}");
+            assert_eq!(decoder.buffered_len(), 0);
+            assert_eq!(decoder.finish(), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn every_pair_of_split_points() {
+    let builder = Builder::new().varint();
+    let payloads = payloads();
+    let bytes = encode_all(&builder.new_encoder(), &payloads);
+
+    for first in 0..=bytes.len() {
+        for second in first..=bytes.len() {
+            let mut decoder = builder.new_stream_decoder();
+            let mut frames = vec![];
+            for chunk in [&bytes[..first], &bytes[first..second], &bytes[second..]] {
+                decoder.feed(chunk);
+                drain(&mut decoder, &mut frames);
+            }
+            assert_eq!(frames, payloads, "splits = {first}, {second}");
+        }
+    }
+}
+
+#[test]
+fn one_byte_at_a_time() {
+    for builder in layouts() {
+        let payloads = payloads();
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        let expected = builder.new_decoder().decode_all(&bytes).unwrap();
+
+        let mut decoder = builder.new_stream_decoder();
+        let mut frames = vec![];
+        for byte in &bytes {
+            decoder.feed(std::slice::from_ref(byte));
+            drain(&mut decoder, &mut frames);
+        }
+        assert_eq!(frames, expected);
+    }
+}
+
+#[test]
+fn frame_yielded_as_soon_as_complete() {
+    let bytes = encode_all(&Encoder::new(), &[b"abc".to_vec()]);
+    let mut decoder = StreamDecoder::new();
+
+    decoder.feed(&bytes[..bytes.len() - 1]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+
+    decoder.feed(&bytes[bytes.len() - 1..]);
+    assert_eq!(decoder.next_frame(), Ok(Some(b"abc".to_vec())));
+    assert_eq!(decoder.next_frame(), Ok(None));
+}
+
+#[test]
+fn finish_reports_incomplete_frame() {
+    let bytes = encode_all(&Encoder::new(), &[b"abcdef".to_vec()]);
+
+    let mut decoder = StreamDecoder::new();
+    decoder.feed(&bytes[..2]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(
+        decoder.finish(),
+        Err(Error::TruncatedHeader {
+            needed: 4,
+            available: 2
+        })
+    );
+
+    decoder.feed(&bytes[2..7]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    assert_eq!(
+        decoder.finish(),
+        Err(Error::TruncatedPayload {
+            expected: 6,
+            available: 3
+        })
+    );
+}
+
+#[test]
+fn invalid_header_is_an_error() {
+    let mut decoder = Builder::new().max_frame_length(4).new_stream_decoder();
+    decoder.feed(&[0, 0, 0, 5]);
+    assert_eq!(
+        decoder.next_frame(),
+        Err(Error::FrameTooLarge { len: 5, max: 4 })
+    );
+}
@@ -0,0 +1,428 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Error, MAX_SYNC_MARKER_LEN, Resync, StreamDecoder,
+};
+#[cfg(feature = "std")]
+use std::io::{self, Cursor};
+use std::ops::Range;
+
+const MARKER: &[u8] = &[0xA5, 0x5A, 0xC3, 0x3C];
+
+mod common;
+
+use common::{XorShift, encode_with_ranges};
+
+fn payloads(rng: &mut XorShift, n: usize) -> Vec<Vec<u8>> {
+    (0..n)
+        .map(|_| {
+            let len = rng.below(200);
+            rng.bytes(len)
+        })
+        .collect()
+}
+
+/// Encodes the payloads, returning the bytes and the range of each frame.
+fn encode_all(builder: &Builder, payloads: &[Vec<u8>]) -> (Vec<u8>, Vec<Range<usize>>) {
+    encode_with_ranges(&builder.new_encoder(), payloads)
+}
+
+fn builder() -> Builder {
+    Builder::new()
+        .sync_marker(MARKER)
+        .checksum(Checksum::Crc32c)
+}
+
+#[test]
+fn marked_frames_round_trip() {
+    let payloads = payloads(&mut XorShift(7), 50);
+    let payloads: Vec<_> = payloads.into_iter().filter(|x| x.len() >= 2).collect();
+    let builders = [
+        (Builder::new().sync_marker(b"#"), 0),
+        (builder().varint(), 0),
+        (builder().length_field_offset(2).little_endian(), 2),
+    ];
+    for (builder, offset) in builders {
+        let (bytes, _) = encode_all(&builder, &payloads);
+        let expected: Vec<_> = payloads.iter().map(|x| x[offset..].to_vec()).collect();
+
+        assert_eq!(builder.new_decoder().decode_all(&bytes).unwrap(), expected);
+
+        let mut decoder = builder.new_stream_decoder();
+        decoder.feed(&bytes);
+        let mut frames = vec![];
+        while let Some(frame) = decoder.next_frame().unwrap() {
+            frames.push(frame);
+        }
+        assert_eq!(frames, expected);
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = builder.new_framed_reader(bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+
+            let mut reader = builder.new_framed_buf_reader_with_capacity(16, bytes.as_slice());
+            let mut frames = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                frames.push(frame.to_vec());
+            }
+            assert_eq!(frames, expected);
+        }
+    }
+}
+
+#[test]
+fn marker_is_not_yielded() {
+    let builder = Builder::new()
+        .sync_marker(b"SYNC")
+        .length_field_offset(1)
+        .length_field_length(2)
+        .num_skip(0);
+    let encoder = builder.new_encoder();
+    assert_eq!(encoder.encoded_len(3), 4 + 2 + 3);
+
+    let mut bytes = vec![];
+    encoder.encode(&[7, b'h', b'i'], &mut bytes).unwrap();
+    assert_eq!(bytes, b"SYNC\x07\x00\x02hi");
+
+    let mut slice = [0; 9];
+    assert_eq!(encoder.encode_to_slice(&[7, b'h', b'i'], &mut slice), Ok(9));
+    assert_eq!(slice.as_slice(), bytes.as_slice());
+
+    let (frame, len) = builder.new_decoder().decode_frame(&bytes).unwrap();
+    assert_eq!(frame, vec![7, 0, 2, b'h', b'i']);
+    assert_eq!(len, 9);
+}
+
+#[test]
+fn missing_marker_is_an_error() {
+    let builder = Builder::new().sync_marker(b"SYNC");
+    let mut bytes = vec![];
+    builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    bytes[2] = b'X';
+
+    assert_eq!(
+        builder.new_decoder().decode_all(&bytes),
+        Err(Error::MissingSyncMarker)
+    );
+    assert_eq!(
+        builder.new_decoder().decode_frame(&bytes[..2]),
+        Err(Error::TruncatedHeader {
+            needed: 8,
+            available: 2
+        })
+    );
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes[..3]);
+    assert_eq!(decoder.next_frame(), Err(Error::MissingSyncMarker));
+
+    #[cfg(feature = "std")]
+    {
+        let mut reader = builder.new_framed_reader(Cursor::new(&bytes));
+        let error = reader.read_frame().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn resync_without_corruption_yields_all_frames() {
+    let payloads = payloads(&mut XorShift(11), 100);
+    let builder = builder();
+    let (bytes, _) = encode_all(&builder, &payloads);
+
+    let decoder = builder.new_decoder();
+    let frames: Vec<_> = decoder
+        .resync_frames(&bytes)
+        .map(|x| match x {
+            Resync::Frame(frame) => frame.to_vec(),
+            Resync::Skipped { range, .. } => panic!("unexpected skip of {range:?}"),
+        })
+        .collect();
+    assert_eq!(frames, payloads);
+}
+
+#[test]
+fn garbage_between_frames_is_skipped() {
+    let builder = builder();
+    let encoder = builder.new_encoder();
+    let mut bytes = b"leading".to_vec();
+    encoder.encode(b"first", &mut bytes).unwrap();
+    let garbage_start = bytes.len();
+    bytes.extend_from_slice(&MARKER[..3]);
+    bytes.extend_from_slice(b"garbage");
+    let garbage_end = bytes.len();
+    encoder.encode(b"second", &mut bytes).unwrap();
+    let second_end = bytes.len();
+    bytes.extend_from_slice(&MARKER[..2]);
+
+    let decoder = builder.new_decoder();
+    let items: Vec<_> = decoder.resync_frames(&bytes).collect();
+    assert_eq!(items.len(), 5);
+    assert!(matches!(
+        &items[0],
+        Resync::Skipped { range, error: Error::MissingSyncMarker } if *range == (0..7)
+    ));
+    assert_eq!(items[1], Resync::Frame(&b"first"[..]));
+    assert!(matches!(
+        &items[2],
+        Resync::Skipped { range, .. } if *range == (garbage_start..garbage_end)
+    ));
+    assert_eq!(items[3], Resync::Frame(&b"second"[..]));
+    assert!(matches!(
+        &items[4],
+        Resync::Skipped { range, error: Error::TruncatedHeader { .. } } if *range == (second_end..bytes.len())
+    ));
+}
+
+/// Flips `num_flips` random bytes and checks that exactly the frames without a flipped byte
+/// survive, and that the skipped ranges cover the corrupted ones.
+fn flip_and_resync(rng: &mut XorShift, num_flips: usize) -> (usize, usize) {
+    let payloads = payloads(rng, 200);
+    let builder = builder();
+    let (mut bytes, ranges) = encode_all(&builder, &payloads);
+
+    let mut corrupted = vec![false; payloads.len()];
+    for _ in 0..num_flips {
+        let i = rng.below(bytes.len());
+        bytes[i] ^= 1 << rng.below(8);
+        corrupted[ranges.partition_point(|x| x.end <= i)] = true;
+    }
+
+    let decoder = builder.new_decoder();
+    let mut frames = vec![];
+    let mut skipped = vec![];
+    for item in decoder.resync_frames(&bytes) {
+        match item {
+            Resync::Frame(frame) => frames.push(frame.to_vec()),
+            Resync::Skipped { range, .. } => skipped.push(range),
+        }
+    }
+
+    let expected: Vec<_> = payloads
+        .iter()
+        .zip(&corrupted)
+        .filter(|(_, corrupted)| !**corrupted)
+        .map(|(x, _)| x.clone())
+        .collect();
+    assert_eq!(frames, expected);
+
+    let corrupted_ranges: Vec<_> = ranges
+        .iter()
+        .zip(&corrupted)
+        .filter(|(_, corrupted)| **corrupted)
+        .map(|(x, _)| x.clone())
+        .collect();
+    let covered = |i: usize| skipped.iter().any(|x| x.contains(&i));
+    for range in &corrupted_ranges {
+        assert!(range.clone().all(covered));
+    }
+    let num_skipped: usize = skipped.iter().map(|x| x.len()).sum();
+    let num_corrupted: usize = corrupted_ranges.iter().map(|x| x.len()).sum();
+    assert_eq!(num_skipped, num_corrupted);
+
+    // the stream decoder fed in random chunks resynchronizes identically
+    let mut decoder = builder.new_stream_decoder();
+    let mut stream_frames = vec![];
+    let mut stream_skipped = vec![];
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(rest.len().min(1 + rng.below(300)));
+        rest = tail;
+        decoder.feed(chunk);
+        while let Some(item) = decoder.next_resync() {
+            match item {
+                Resync::Frame(frame) => stream_frames.push(frame),
+                Resync::Skipped { range, .. } => stream_skipped.push(range),
+            }
+        }
+    }
+    // a corrupted length pointing beyond the end of the buffer is reported by the slice
+    // decoder, whereas the stream decoder awaits the rest of the frame
+    assert_eq!(stream_frames, frames[..stream_frames.len()]);
+    assert_eq!(stream_skipped, skipped[..stream_skipped.len()]);
+    if stream_frames.len() < frames.len() {
+        assert!(decoder.finish().is_err());
+        assert!(decoder.buffered_len() > 0);
+    }
+
+    (frames.len(), payloads.len())
+}
+
+#[test]
+fn random_flips_lose_only_the_corrupted_frames() {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+    for num_flips in [1, 2, 5, 10, 20] {
+        for _ in 0..20 {
+            let (survived, total) = flip_and_resync(&mut rng, num_flips);
+            assert!(survived + num_flips >= total);
+        }
+    }
+}
+
+#[test]
+fn without_markers_a_corrupted_length_loses_the_rest() {
+    let payloads = payloads(&mut XorShift(3), 200);
+    let (unmarked, unmarked_ranges) = encode_all(&Builder::new(), &payloads);
+    let (marked, marked_ranges) = encode_all(&builder(), &payloads);
+
+    let mut unmarked = unmarked.clone();
+    unmarked[unmarked_ranges[10].start] ^= 0x40;
+    let survived = Builder::new()
+        .new_decoder()
+        .frames(&unmarked)
+        .take_while(|x| x.is_ok())
+        .count();
+    assert_eq!(survived, 10);
+
+    let mut marked = marked.clone();
+    marked[marked_ranges[10].start + MARKER.len()] ^= 0x40;
+    let decoder = builder().new_decoder();
+    let survived = decoder
+        .resync_frames(&marked)
+        .filter(|x| matches!(x, Resync::Frame(_)))
+        .count();
+    assert_eq!(survived, 199);
+}
+
+/// Returns the skipped ranges and the frames yielded by the stream decoder while
+/// `next_resync` returns items, and then by `finish_resync` if the stream ends.
+fn stream_resync(decoder: &mut StreamDecoder, at_end: bool) -> (Vec<Range<usize>>, Vec<Vec<u8>>) {
+    let mut skipped = vec![];
+    let mut frames = vec![];
+    let mut next = || match at_end {
+        true => decoder.next_resync().or_else(|| decoder.finish_resync()),
+        false => decoder.next_resync(),
+    };
+    while let Some(item) = next() {
+        match item {
+            Resync::Frame(frame) => frames.push(frame),
+            Resync::Skipped { range, .. } => skipped.push(range),
+        }
+    }
+    (skipped, frames)
+}
+
+#[test]
+fn corrupted_length_is_skipped_by_the_stream_decoder() {
+    let payloads: Vec<_> = (0..10).map(|i| vec![i; 50]).collect();
+    let expected_frames = [&payloads[..1], &payloads[2..6]].concat();
+    for (builder, checked) in [
+        (builder(), true),
+        (Builder::new().sync_marker(MARKER), false),
+    ] {
+        let (mut bytes, ranges) = encode_all(&builder, &payloads);
+        // the second frame claims 256 more bytes, which is within the maximum frame length
+        bytes[ranges[1].start + MARKER.len() + 2] ^= 1;
+        assert!(ranges[1].end + 256 > ranges[5].end);
+        let bytes = &bytes[..ranges[5].end];
+
+        let mut skipped = vec![];
+        let mut frames = vec![];
+        for item in builder.new_decoder().resync_frames(bytes) {
+            match item {
+                Resync::Frame(frame) => frames.push(frame.to_vec()),
+                Resync::Skipped { range, .. } => skipped.push(range),
+            }
+        }
+        assert_eq!(
+            (&skipped, &frames),
+            (&vec![ranges[1].clone()], &expected_frames)
+        );
+
+        let mut decoder = builder.new_stream_decoder();
+        decoder.feed(bytes);
+        match checked {
+            // the frames following the corrupted one are found valid by their checksums
+            true => {
+                let (skipped, frames) = stream_resync(&mut decoder, false);
+                assert_eq!(
+                    (&skipped, &frames),
+                    (&vec![ranges[1].clone()], &expected_frames)
+                );
+                assert_eq!(decoder.finish(), Ok(()));
+
+                // also as the bytes arrive in small chunks
+                let mut decoder = builder.new_stream_decoder();
+                let (mut skipped, mut frames) = (vec![], vec![]);
+                for chunk in bytes.chunks(7) {
+                    decoder.feed(chunk);
+                    let (s, f) = stream_resync(&mut decoder, false);
+                    skipped.extend(s);
+                    frames.extend(f);
+                }
+                assert_eq!(
+                    (&skipped, &frames),
+                    (&vec![ranges[1].clone()], &expected_frames)
+                );
+            }
+            // they are withheld until the claimed bytes arrive or the stream ends
+            false => {
+                let (skipped, frames) = stream_resync(&mut decoder, false);
+                assert_eq!((skipped, frames), (vec![], payloads[..1].to_vec()));
+                assert!(decoder.finish().is_err());
+
+                let (skipped, frames) = stream_resync(&mut decoder, true);
+                assert_eq!(
+                    (skipped, frames),
+                    (vec![ranges[1].clone()], payloads[2..6].to_vec())
+                );
+                assert_eq!(decoder.finish(), Ok(()));
+            }
+        }
+    }
+}
+
+#[test]
+fn garbage_at_the_end_of_the_stream_is_skipped() {
+    let builder = Builder::new().sync_marker(b"SYNC");
+    let mut bytes = vec![];
+    builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    let end = bytes.len();
+    bytes.extend_from_slice(b"SYN garbage ending");
+    bytes.truncate(end + 17);
+
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes);
+    assert_eq!(
+        decoder.next_resync(),
+        Some(Resync::Frame(b"hello".to_vec()))
+    );
+    assert_eq!(decoder.next_resync(), None);
+    assert!(matches!(decoder.finish(), Err(Error::MissingSyncMarker)));
+
+    let item = decoder.finish_resync();
+    assert!(matches!(item, Some(Resync::Skipped { range, .. }) if range == (end..end + 17)));
+    assert_eq!(decoder.finish_resync(), None);
+    assert_eq!(decoder.buffered_len(), 0);
+    assert_eq!(decoder.finish(), Ok(()));
+
+    // the bytes which might begin a marker are skipped at the end as well
+    let mut decoder = builder.new_stream_decoder();
+    decoder.feed(&bytes[..end + 3]);
+    let (skipped, frames) = stream_resync(&mut decoder, true);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0], end..end + 3);
+    assert_eq!(frames, [b"hello"]);
+}
+
+#[test]
+fn longest_sync_marker() {
+    let marker = [0xA5; MAX_SYNC_MARKER_LEN];
+    let builder = Builder::new().sync_marker(&marker);
+    let mut bytes = vec![];
+    builder.new_encoder().encode(b"hello", &mut bytes).unwrap();
+    assert_eq!(&bytes[..MAX_SYNC_MARKER_LEN], marker);
+    let frames = builder.new_decoder().decode_all(&bytes).unwrap();
+    assert_eq!(frames, [b"hello"]);
+}
+
+#[test]
+#[should_panic]
+fn sync_marker_longer_than_the_maximum() {
+    let _ = Builder::new().sync_marker(&[0xA5; MAX_SYNC_MARKER_LEN + 1]);
+}
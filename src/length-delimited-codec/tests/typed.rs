@@ -0,0 +1,256 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, Checksum, Compression, Error, Serializer,
+    serializer::{Binary, FixedLayout},
+};
+#[cfg(feature = "std")]
+use std::io;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    any(feature = "json", feature = "bincode"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+struct Order {
+    id: u64,
+    price: f64,
+    quantity: i32,
+    symbol: String,
+    tags: Vec<String>,
+    limit: Option<u16>,
+    side: char,
+    active: bool,
+}
+
+impl FixedLayout for Order {
+    fn write(&self, dst: &mut Vec<u8>) {
+        self.id.write(dst);
+        self.price.write(dst);
+        self.quantity.write(dst);
+        self.symbol.write(dst);
+        self.tags.write(dst);
+        self.limit.write(dst);
+        self.side.write(dst);
+        self.active.write(dst);
+    }
+
+    fn read(src: &mut &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            id: u64::read(src)?,
+            price: f64::read(src)?,
+            quantity: i32::read(src)?,
+            symbol: String::read(src)?,
+            tags: Vec::read(src)?,
+            limit: Option::read(src)?,
+            side: char::read(src)?,
+            active: bool::read(src)?,
+        })
+    }
+}
+
+fn orders() -> Vec<Order> {
+    (0..50)
+        .map(|i| Order {
+            id: i * 1_000_003,
+            price: i as f64 * 0.25,
+            quantity: 100 - i as i32 * 3,
+            symbol: ["AAPL", "MSFT", "ÄÖÜ", ""][i as usize % 4].to_string(),
+            tags: (0..i % 3).map(|x| format!("tag{x}")).collect(),
+            limit: (i % 2 == 0).then_some(i as u16),
+            side: ['b', 's', '€'][i as usize % 3],
+            active: i % 5 != 0,
+        })
+        .collect()
+}
+
+fn builders() -> Vec<Builder> {
+    vec![
+        Builder::new(),
+        Builder::new().varint().checksum(Checksum::Crc32c),
+        Builder::new().compression(Compression::Lz4),
+    ]
+}
+
+fn round_trip<S: Serializer<Order> + Clone>(serializer: S) {
+    let orders = orders();
+    for builder in builders() {
+        let mut encoder = builder.new_typed_encoder(serializer.clone());
+        let mut bytes = vec![];
+        for order in &orders {
+            encoder.encode(order, &mut bytes).unwrap();
+        }
+
+        let decoder = builder.new_typed_decoder(serializer.clone());
+        assert_eq!(decoder.decode_all(&bytes).unwrap(), orders);
+
+        let (first, len) = decoder.decode_frame(&bytes).unwrap();
+        assert_eq!(first, orders[0]);
+        assert_eq!(decoder.decode_frame(&bytes[len..]).unwrap().0, orders[1]);
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = builder.new_framed_buf_reader(bytes.as_slice());
+            let mut decoded = vec![];
+            while let Some(frame) = reader.read_frame().unwrap() {
+                decoded.push(decoder.deserialize(frame).unwrap());
+            }
+            assert_eq!(decoded, orders);
+
+            // payloads serialized separately are framed by a writer
+            let mut writer = builder.new_framed_writer(vec![]);
+            for order in &orders {
+                writer
+                    .write_frame(&encoder.serialize(order).unwrap())
+                    .unwrap();
+            }
+            assert_eq!(writer.into_inner(), bytes);
+        }
+    }
+}
+
+#[test]
+fn binary_round_trip() {
+    round_trip(Binary);
+}
+
+#[test]
+fn binary_layout() {
+    let mut bytes = vec![];
+    Binary
+        .serialize(
+            &(1u16, -2i8, true, Some('A'), [3u8; 2], "hi".to_string()),
+            &mut bytes,
+        )
+        .unwrap();
+    assert_eq!(
+        bytes,
+        [
+            [1, 0].as_slice(),
+            &[0xFE],
+            &[1],
+            &[1, 65, 0, 0, 0],
+            &[3, 3],
+            &[2, 0, 0, 0, 0, 0, 0, 0, b'h', b'i'],
+        ]
+        .concat()
+    );
+
+    let mut bytes = vec![];
+    Binary
+        .serialize(&(usize::MAX, None::<u8>, ()), &mut bytes)
+        .unwrap();
+    assert_eq!(bytes, [[0xFF; 8].as_slice(), &[0]].concat());
+}
+
+fn invalid<T>(result: Result<T, Error>) -> bool {
+    matches!(result, Err(Error::InvalidMessage { .. }))
+}
+
+#[test]
+fn invalid_binary_messages() {
+    assert!(invalid(Serializer::<u32>::deserialize(&Binary, &[1, 2, 3])));
+    assert!(invalid(Serializer::<u16>::deserialize(&Binary, &[1, 2, 3])));
+    assert!(invalid(Serializer::<bool>::deserialize(&Binary, &[2])));
+    assert!(invalid(Serializer::<Option<u8>>::deserialize(
+        &Binary,
+        &[2, 0]
+    )));
+    assert!(invalid(Serializer::<char>::deserialize(
+        &Binary,
+        &0xD800u32.to_le_bytes()
+    )));
+
+    let mut bytes = vec![];
+    Binary.serialize(&vec![0xFFu8, 0xFE], &mut bytes).unwrap();
+    assert!(invalid(Serializer::<String>::deserialize(&Binary, &bytes)));
+
+    // a huge length fails on the missing elements without allocating them
+    let bytes = u64::MAX.to_le_bytes();
+    assert!(invalid(Serializer::<Vec<u64>>::deserialize(
+        &Binary, &bytes
+    )));
+
+    let order = &orders()[7];
+    let mut bytes = vec![];
+    Binary.serialize(order, &mut bytes).unwrap();
+    for len in 0..bytes.len() {
+        assert!(invalid(Serializer::<Order>::deserialize(
+            &Binary,
+            &bytes[..len]
+        )));
+    }
+}
+
+#[test]
+fn invalid_message_in_a_valid_frame() {
+    let builder = Builder::new();
+    let mut bytes = vec![];
+    builder
+        .new_encoder()
+        .encode(&[1, 2, 3], &mut bytes)
+        .unwrap();
+
+    let decoder = builder.new_typed_decoder::<u32, _>(Binary);
+    let error = decoder.decode_frame(&bytes).unwrap_err();
+    assert!(matches!(error, Error::InvalidMessage { .. }));
+    #[cfg(feature = "std")]
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::InvalidData);
+
+    let decoder = builder.new_typed_decoder::<u16, _>(Binary);
+    let error = decoder.decode_all(&bytes).unwrap_err();
+    assert_eq!(error.to_string(), "invalid message: 1 trailing bytes");
+}
+
+#[test]
+fn encoding_errors_are_reported() {
+    let builder = Builder::new().max_frame_length(16);
+    let mut encoder = builder.new_typed_encoder(Binary);
+    let mut bytes = vec![];
+    encoder.encode(&"short".to_string(), &mut bytes).unwrap();
+
+    let error = encoder
+        .encode(&"too long".repeat(4), &mut bytes)
+        .unwrap_err();
+    assert!(matches!(error, Error::FrameTooLarge { .. }));
+    assert_eq!(bytes.len(), 4 + 8 + 5);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_round_trip() {
+    use length_delimited_codec::serializer::Json;
+
+    round_trip(Json);
+
+    let mut bytes = vec![];
+    Json.serialize(&orders()[1], &mut bytes).unwrap();
+    assert!(bytes.starts_with(br#"{"id":1000003,"price":0.25"#));
+
+    let decoder = Builder::new().new_typed_decoder::<Order, _>(Json);
+    assert!(matches!(
+        decoder.deserialize(b"{\"id\":1}"),
+        Err(Error::InvalidMessage { .. })
+    ));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_round_trip() {
+    use length_delimited_codec::serializer::Bincode;
+
+    round_trip(Bincode);
+
+    let mut bytes = vec![];
+    Bincode.serialize(&orders()[1], &mut bytes).unwrap();
+    let decoder = Builder::new().new_typed_decoder::<Order, _>(Bincode);
+    assert!(matches!(
+        decoder.deserialize(&bytes[..bytes.len() - 1]),
+        Err(Error::InvalidMessage { .. })
+    ));
+    bytes.push(0);
+    assert!(matches!(
+        decoder.deserialize(&bytes),
+        Err(Error::InvalidMessage { .. })
+    ));
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{
+    Builder, DEFAULT_MAX_FRAME_LENGTH, Error,
+    varint::{self, MAX_VARINT_LEN},
+};
+
+fn boundaries() -> Vec<(u64, Vec<u8>)> {
+    vec![
+        (0, vec![0x00]),
+        (1, vec![0x01]),
+        (127, vec![0x7F]),
+        (128, vec![0x80, 0x01]),
+        (300, vec![0xAC, 0x02]),
+        (16383, vec![0xFF, 0x7F]),
+        (16384, vec![0x80, 0x80, 0x01]),
+        (u32::MAX as u64, vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+        (
+            u64::MAX,
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+        ),
+    ]
+}
+
+#[test]
+fn encode_boundaries() {
+    for (value, expected) in boundaries() {
+        let mut bytes = vec![];
+        varint::encode(value, &mut bytes);
+        assert_eq!(bytes, expected, "value = {value}");
+        assert_eq!(varint::encoded_len(value), expected.len());
+    }
+}
+
+#[test]
+fn decode_boundaries() {
+    for (value, bytes) in boundaries() {
+        assert_eq!(varint::decode(&bytes), Ok((value, bytes.len())));
+
+        let mut with_next = bytes.clone();
+        with_next.extend_from_slice(&[0x05, 0xFF]);
+        assert_eq!(varint::decode(&with_next), Ok((value, bytes.len())));
+    }
+}
+
+#[test]
+fn encoded_len_at_every_group_boundary() {
+    for bits in 0..64 {
+        for value in [(1u64 << bits) - 1, 1u64 << bits] {
+            let mut bytes = vec![];
+            varint::encode(value, &mut bytes);
+            assert_eq!(varint::encoded_len(value), bytes.len());
+            assert_eq!(varint::decode(&bytes), Ok((value, bytes.len())));
+        }
+    }
+}
+
+#[test]
+fn partially_received() {
+    for (_, bytes) in boundaries() {
+        for available in 0..bytes.len() {
+            assert_eq!(
+                varint::decode(&bytes[..available]),
+                Err(Error::TruncatedHeader {
+                    needed: available + 1,
+                    available
+                })
+            );
+        }
+    }
+}
+
+#[test]
+fn overlong() {
+    assert_eq!(
+        varint::decode(&[0x80, 0x00]),
+        Err(Error::VarintOverlong { len: 2 })
+    );
+    assert_eq!(
+        varint::decode(&[0xFF, 0x80, 0x00]),
+        Err(Error::VarintOverlong { len: 3 })
+    );
+    let mut zero_in_ten_bytes = vec![0x80; MAX_VARINT_LEN - 1];
+    zero_in_ten_bytes.push(0x00);
+    assert_eq!(
+        varint::decode(&zero_in_ten_bytes),
+        Err(Error::VarintOverlong { len: 10 })
+    );
+}
+
+#[test]
+fn overflow() {
+    // 10th byte carries more than the single remaining bit
+    let mut bytes = vec![0xFF; MAX_VARINT_LEN - 1];
+    bytes.push(0x02);
+    assert_eq!(varint::decode(&bytes), Err(Error::VarintOverflow));
+
+    // 10th byte has its continuation bit set
+    let mut bytes = vec![0xFF; MAX_VARINT_LEN - 1];
+    bytes.extend_from_slice(&[0x81, 0x00]);
+    assert_eq!(varint::decode(&bytes), Err(Error::VarintOverflow));
+
+    // 10 continuation bytes are never valid, even if the rest is not received yet
+    assert_eq!(
+        varint::decode(&[0x80; MAX_VARINT_LEN]),
+        Err(Error::VarintOverflow)
+    );
+}
+
+#[test]
+fn frames_round_trip() {
+    let builder = Builder::new().varint();
+    let encoder = builder.new_encoder();
+
+    let lengths = [0, 1, 127, 128, 16383, 16384, 100_000];
+    let mut bytes = vec![];
+    for len in lengths {
+        let before = bytes.len();
+        encoder.encode(&vec![7; len], &mut bytes).unwrap();
+        assert_eq!(bytes.len() - before, encoder.encoded_len(len));
+        assert_eq!(bytes.len() - before, varint::encoded_len(len as u64) + len);
+    }
+
+    let frames = builder.new_decoder().decode_all(&bytes).unwrap();
+    let expected: Vec<_> = lengths.iter().map(|len| vec![7; *len]).collect();
+    assert_eq!(frames, expected);
+}
+
+#[test]
+fn frames_with_offset_and_adjustment() {
+    // 1-byte message type followed by a varint counting itself and the body
+    let builder = Builder::new()
+        .length_field_offset(1)
+        .varint()
+        .length_adjustment(-1)
+        .num_skip(0);
+
+    let mut bytes = vec![];
+    builder
+        .new_encoder()
+        .encode(&[9, b'a', b'b'], &mut bytes)
+        .unwrap();
+    assert_eq!(bytes, vec![9, 3, b'a', b'b']);
+    assert_eq!(
+        builder.new_decoder().decode_frame(&bytes),
+        Ok((bytes.clone(), 4))
+    );
+}
+
+#[test]
+fn frame_errors() {
+    let decoder = Builder::new().length_field_offset(2).varint().new_decoder();
+
+    // varint split after its first byte
+    assert_eq!(
+        decoder.decode_frame(&[0xAA, 0xBB, 0x80]),
+        Err(Error::TruncatedHeader {
+            needed: 4,
+            available: 3
+        })
+    );
+    assert_eq!(
+        decoder.decode_frame(&[0xAA, 0xBB, 0x80, 0x00]),
+        Err(Error::VarintOverlong { len: 2 })
+    );
+
+    let mut bytes = vec![0xAA, 0xBB];
+    varint::encode(u64::MAX, &mut bytes);
+    assert!(matches!(
+        decoder.decode_frame(&bytes),
+        Err(Error::FrameTooLarge {
+            max: DEFAULT_MAX_FRAME_LENGTH,
+            ..
+        })
+    ));
+
+    let mut bytes = vec![0xAA, 0xBB];
+    varint::encode(u32::MAX as u64, &mut bytes);
+    assert_eq!(
+        decoder.decode_frame(&bytes),
+        Err(Error::FrameTooLarge {
+            len: u32::MAX as usize,
+            max: DEFAULT_MAX_FRAME_LENGTH
+        })
+    );
+}
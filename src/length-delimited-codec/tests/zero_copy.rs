@@ -0,0 +1,183 @@
+#![cfg(feature = "alloc")]
+
+use length_delimited_codec::{Builder, Decoder, Encoder, Error, OversizePolicy};
+#[cfg(feature = "std")]
+use std::io::{self, Cursor, Read};
+
+mod common;
+
+use common::encode_all;
+
+/// Reader returning at most `chunk_len` bytes per read.
+#[cfg(feature = "std")]
+struct ChunkedReader<R> {
+    inner: R,
+    chunk_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.chunk_len);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn frames_borrow_from_the_buffer() {
+    let bytes = encode_all(&Encoder::new(), &common::payloads(50, 0..300));
+    let decoder = Decoder::new();
+
+    let frames: Vec<&[u8]> = decoder.frames(&bytes).map(|x| x.unwrap()).collect();
+    assert_eq!(frames, common::payloads(50, 0..300));
+
+    let range = bytes.as_ptr_range();
+    for frame in frames.iter().filter(|x| !x.is_empty()) {
+        assert!(range.contains(&frame.as_ptr()));
+    }
+}
+
+#[test]
+fn frames_match_decode_all() {
+    let builders = [
+        Builder::new(),
+        Builder::new().varint(),
+        Builder::new()
+            .length_field_offset(2)
+            .length_field_length(2)
+            .num_skip(0),
+    ];
+    let payloads: Vec<_> = common::payloads(50, 0..300)
+        .into_iter()
+        .filter(|x| x.len() >= 2)
+        .collect();
+    for builder in builders {
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        let decoder = builder.new_decoder();
+        let frames: Vec<_> = decoder
+            .frames(&bytes)
+            .map(|x| x.unwrap().to_vec())
+            .collect();
+        assert_eq!(frames, decoder.decode_all(&bytes).unwrap());
+    }
+}
+
+#[test]
+fn index_matches_frames() {
+    let builder = Builder::new()
+        .varint()
+        .max_frame_length(200)
+        .oversize_policy(OversizePolicy::Discard);
+    let bytes = encode_all(
+        &Builder::new().varint().new_encoder(),
+        &common::payloads(50, 0..300),
+    );
+    let decoder = builder.new_decoder();
+
+    let index = decoder.index(&bytes).unwrap();
+    let frames: Vec<_> = decoder.frames(&bytes).map(|x| x.unwrap()).collect();
+    assert_eq!(index.len(), frames.len());
+    for (range, frame) in index.iter().zip(&frames) {
+        assert_eq!(&bytes[range.clone()], *frame);
+    }
+
+    assert!(decoder.index(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn frames_stop_at_invalid_frame() {
+    let mut bytes = encode_all(&Encoder::new(), &[b"ab".to_vec(), b"cd".to_vec()]);
+    bytes.extend_from_slice(&[0, 0, 0, 9, 1]);
+
+    let decoder = Decoder::new();
+    let mut frames = decoder.frames(&bytes);
+    assert_eq!(frames.next(), Some(Ok(&b"ab"[..])));
+    assert_eq!(frames.next(), Some(Ok(&b"cd"[..])));
+    assert_eq!(frames.position(), 12);
+    assert_eq!(
+        frames.next(),
+        Some(Err(Error::TruncatedPayload {
+            expected: 9,
+            available: 1
+        }))
+    );
+    assert_eq!(frames.next(), None);
+    assert_eq!(frames.position(), 12);
+}
+
+#[test]
+fn frames_discard_oversized() {
+    let payloads = common::payloads(50, 0..300);
+    let bytes = encode_all(&Encoder::new(), &payloads);
+    let decoder = Builder::new()
+        .max_frame_length(100)
+        .oversize_policy(OversizePolicy::Discard)
+        .new_decoder();
+
+    let frames: Vec<_> = decoder.frames(&bytes).map(|x| x.unwrap()).collect();
+    let expected: Vec<_> = payloads.iter().filter(|x| x.len() <= 100).collect();
+    assert_eq!(frames, expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn buf_reader_with_various_capacities() {
+    let payloads = common::payloads(50, 0..300);
+    for builder in [Builder::new(), Builder::new().varint()] {
+        let bytes = encode_all(&builder.new_encoder(), &payloads);
+        for capacity in [1, 3, 64, 301, 4096, 1 << 20] {
+            for chunk_len in [1, 7, 1000, usize::MAX] {
+                let chunked = ChunkedReader {
+                    inner: Cursor::new(&bytes),
+                    chunk_len,
+                };
+                let mut reader = builder.new_framed_buf_reader_with_capacity(capacity, chunked);
+                let mut frames = vec![];
+                while let Some(frame) = reader.read_frame().unwrap() {
+                    frames.push(frame.to_vec());
+                }
+                assert_eq!(frames, payloads, "capacity = {capacity}");
+                assert!(reader.capacity() >= capacity);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn buf_reader_eof_in_the_middle_of_a_frame() {
+    let bytes = encode_all(&Encoder::new(), &common::payloads(50, 0..300));
+    for len in [1, 3, 5, bytes.len() - 1] {
+        let mut reader = Builder::new().new_framed_buf_reader_with_capacity(16, &bytes[..len]);
+        let error = loop {
+            match reader.read_frame() {
+                Ok(Some(_)) => continue,
+                Ok(None) => panic!("truncated stream must fail"),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof, "len = {len}");
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn buf_reader_discard_oversized() {
+    let payloads = common::payloads(50, 0..300);
+    let bytes = encode_all(&Encoder::new(), &payloads);
+    let builder = Builder::new()
+        .max_frame_length(100)
+        .oversize_policy(OversizePolicy::Discard);
+    let expected: Vec<_> = payloads.iter().filter(|x| x.len() <= 100).collect();
+
+    for capacity in [8, 64, 4096] {
+        let mut reader = builder.new_framed_buf_reader_with_capacity(capacity, Cursor::new(&bytes));
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_frame().unwrap() {
+            frames.push(frame.to_vec());
+        }
+        assert_eq!(frames.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(reader.num_discarded(), payloads.len() - expected.len());
+        assert!(reader.capacity() <= 104.max(capacity));
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt::Display;
+
+/// Shape of the input of an experiment, to interpret the benchmark numbers against.
+#[derive(Debug, Default)]
+pub struct GraphStats {
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    /// Number of distinct roots.
+    pub num_roots: usize,
+    /// Number of distinct nodes reachable from the roots, including the roots.
+    pub num_reachable: usize,
+    /// Number of reachable nodes at each depth, where roots are at depth 0.
+    pub depth_histogram: Vec<usize>,
+    /// Number of nodes with each out-degree.
+    pub fan_out_histogram: Vec<usize>,
+    pub num_sccs: usize,
+    /// Number of strongly connected components containing at least one cycle.
+    pub num_cyclic_sccs: usize,
+    pub largest_scc: usize,
+    /// Total number of Fibonacci iterations required to process the reachable nodes.
+    pub work_units: u64,
+}
+
+impl GraphStats {
+    /// Creates the stats of the graph given by its `adjacency` lists, where `work_units`
+    /// of a node is the amount of work required to process it.
+    pub fn new<W>(adjacency: &[Vec<usize>], roots: &[usize], work_units: W) -> Self
+    where
+        W: Fn(usize) -> u64,
+    {
+        let mut fan_out_histogram = vec![];
+        for children in adjacency {
+            add_to_histogram(&mut fan_out_histogram, children.len());
+        }
+
+        let depths = bfs_depths(adjacency, roots);
+        let mut depth_histogram = vec![];
+        let mut work = 0;
+        for (idx, depth) in depths.iter().enumerate() {
+            if let Some(depth) = depth {
+                add_to_histogram(&mut depth_histogram, *depth);
+                work += work_units(idx);
+            }
+        }
+
+        let sccs = strongly_connected_components(adjacency);
+        let num_cyclic_sccs = sccs.iter().filter(|x| is_cyclic(adjacency, x)).count();
+
+        Self {
+            num_nodes: adjacency.len(),
+            num_edges: adjacency.iter().map(|x| x.len()).sum(),
+            num_roots: depth_histogram.first().copied().unwrap_or(0),
+            num_reachable: depths.iter().filter(|x| x.is_some()).count(),
+            depth_histogram,
+            fan_out_histogram,
+            num_sccs: sccs.len(),
+            num_cyclic_sccs,
+            largest_scc: sccs.iter().map(|x| x.len()).max().unwrap_or(0),
+            work_units: work,
+        }
+    }
+}
+
+impl Display for GraphStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "  nodes = {}", self.num_nodes)?;
+        writeln!(f, "  edges = {}", self.num_edges)?;
+        writeln!(f, "  roots = {}", self.num_roots)?;
+        writeln!(f, "  reachable = {}", self.num_reachable)?;
+        writeln!(f, "  work units = {}", self.work_units)?;
+        writeln!(
+            f,
+            "  sccs = {} (cyclic = {}, largest = {})",
+            self.num_sccs, self.num_cyclic_sccs, self.largest_scc
+        )?;
+
+        writeln!(f, "  depth distribution:")?;
+        for (depth, count) in self.depth_histogram.iter().enumerate() {
+            writeln!(f, "    {depth:>8} : {count}")?;
+        }
+
+        // fan-outs are bucketed by powers of two
+        writeln!(f, "  fan-out histogram:")?;
+        let mut begin = 0;
+        while begin < self.fan_out_histogram.len() {
+            let end = (begin * 2).max(begin + 1).min(self.fan_out_histogram.len());
+            let count: usize = self.fan_out_histogram[begin..end].iter().sum();
+            let range = match end - begin {
+                1 => format!("{begin}"),
+                _ => format!("{begin}-{}", end - 1),
+            };
+            writeln!(f, "    {range:>8} : {count}")?;
+            begin = end;
+        }
+
+        Ok(())
+    }
+}
+
+fn add_to_histogram(histogram: &mut Vec<usize>, value: usize) {
+    if histogram.len() <= value {
+        histogram.resize(value + 1, 0);
+    }
+    histogram[value] += 1;
+}
+
+/// Shortest depth of each node from any of the roots; None if not reachable.
+fn bfs_depths(adjacency: &[Vec<usize>], roots: &[usize]) -> Vec<Option<usize>> {
+    let mut depths = vec![None; adjacency.len()];
+    let mut current = vec![];
+    for root in roots {
+        if depths[*root].is_none() {
+            depths[*root] = Some(0);
+            current.push(*root);
+        }
+    }
+
+    let mut depth = 0;
+    while !current.is_empty() {
+        depth += 1;
+        let mut next = vec![];
+        for node in current {
+            for child in &adjacency[node] {
+                if depths[*child].is_none() {
+                    depths[*child] = Some(depth);
+                    next.push(*child);
+                }
+            }
+        }
+        current = next;
+    }
+
+    depths
+}
+
+/// Returns whether or not the strongly connected component with the given `members` contains a cycle.
+pub fn is_cyclic(adjacency: &[Vec<usize>], members: &[usize]) -> bool {
+    members.len() > 1 || adjacency[members[0]].contains(&members[0])
+}
+
+/// Iterative Tarjan's algorithm returning the members of each strongly connected component.
+///
+/// Components are returned in reverse topological order; i.e., every component comes after
+/// all components reachable from it.
+pub fn strongly_connected_components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = adjacency.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low_link = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut next_index = 0;
+    let mut sccs = vec![];
+
+    for start in 0..n {
+        if index[start] != usize::MAX {
+            continue;
+        }
+
+        // (node, position of the next child to visit)
+        let mut call_stack = vec![(start, 0)];
+        while let Some((node, child_pos)) = call_stack.pop() {
+            if child_pos == 0 {
+                index[node] = next_index;
+                low_link[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            match adjacency[node].get(child_pos) {
+                Some(&child) => {
+                    call_stack.push((node, child_pos + 1));
+                    if index[child] == usize::MAX {
+                        call_stack.push((child, 0));
+                    } else if on_stack[child] {
+                        low_link[node] = low_link[node].min(index[child]);
+                    }
+                }
+                None => {
+                    if low_link[node] == index[node] {
+                        let mut members = vec![];
+                        loop {
+                            let member = stack.pop().expect("node must be on the stack");
+                            on_stack[member] = false;
+                            members.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        sccs.push(members);
+                    }
+                    if let Some((parent, _)) = call_stack.last() {
+                        low_link[*parent] = low_link[*parent].min(low_link[node]);
+                    }
+                }
+            }
+        }
+    }
+
+    sccs
+}
@@ -1,6 +1,11 @@
+use crate::tree_with_on_the_fly_children::{
+    dataset::DatasetFormat,
+    loader::{LoaderConfig, WaitMode},
+};
 use clap::Parser;
-use std::sync::OnceLock;
+use std::{io, path::PathBuf, sync::OnceLock, time::Duration};
 
+mod graph_stats;
 mod run_utils;
 mod tree_with_allocated_children;
 mod tree_with_on_the_fly_children;
@@ -10,16 +15,111 @@ struct Args {
     /// Amount of work (num times Fibonacci will be repeated).
     #[arg(long, default_value_t = 1)]
     amount_of_work: usize,
+
+    /// Simulated latency of loading a child on the fly, in microseconds; the simulated load
+    /// latency experiment runs only when this is given.
+    #[arg(long)]
+    load_latency_micros: Option<u64>,
+
+    /// Maximum jitter added to or subtracted from the load latency, in microseconds.
+    #[arg(long, default_value_t = 0)]
+    load_jitter_micros: u64,
+
+    /// How the simulated loader waits for the latency to pass.
+    #[arg(long, value_enum, default_value_t = WaitMode::Sleep)]
+    load_wait_mode: WaitMode,
+
+    /// Probability in [0, 1] that loading a child fails.
+    #[arg(long, default_value_t = 0.0)]
+    load_failure_rate: f64,
+
+    /// Path of a graph to load instead of generating a random one.
+    #[arg(long)]
+    dataset: Option<PathBuf>,
+
+    /// Path to save the graph of the experiment to.
+    #[arg(long)]
+    save_dataset: Option<PathBuf>,
+
+    /// Format of the dataset to load; inferred from its file extension when omitted.
+    #[arg(long, value_enum)]
+    dataset_format: Option<DatasetFormat>,
+
+    /// Format of the dataset to save; inferred from its file extension when omitted.
+    #[arg(long, value_enum)]
+    save_dataset_format: Option<DatasetFormat>,
+
+    /// Prints the statistics of the input graphs before running the experiments.
+    #[arg(long)]
+    stats: bool,
+}
+
+fn args() -> &'static Args {
+    static ARGS: OnceLock<Args> = OnceLock::new();
+    ARGS.get_or_init(Args::parse)
 }
 
 pub fn amount_of_work() -> &'static usize {
-    static WORK: OnceLock<usize> = OnceLock::new();
-    WORK.get_or_init(|| Args::parse().amount_of_work)
+    &args().amount_of_work
+}
+
+pub fn stats_enabled() -> bool {
+    args().stats
+}
+
+/// Configuration of the simulated loader, or None if the load latency experiment is not requested.
+pub fn loader_config() -> Option<LoaderConfig> {
+    let args = args();
+    args.load_latency_micros.map(|latency| LoaderConfig {
+        latency: Duration::from_micros(latency),
+        jitter: Duration::from_micros(args.load_jitter_micros),
+        wait_mode: args.load_wait_mode,
+        failure_rate: args.load_failure_rate,
+    })
+}
+
+pub struct DatasetArgs {
+    pub load: Option<(PathBuf, DatasetFormat)>,
+    pub save: Option<(PathBuf, DatasetFormat)>,
+}
+
+/// Dataset paths with their formats; fails if a format is neither given nor inferred from
+/// the extension.
+pub fn dataset_args() -> io::Result<DatasetArgs> {
+    let args = args();
+    let with_format = |path: &PathBuf,
+                       format: Option<DatasetFormat>,
+                       flag: &str|
+     -> io::Result<(PathBuf, DatasetFormat)> {
+        let format = format
+            .or_else(|| DatasetFormat::from_path(path))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cannot infer the format of {path:?}, use {flag}"),
+                )
+            })?;
+        Ok((path.clone(), format))
+    };
+    Ok(DatasetArgs {
+        load: (args.dataset.as_ref())
+            .map(|x| with_format(x, args.dataset_format, "--dataset-format"))
+            .transpose()?,
+        save: (args.save_dataset.as_ref())
+            .map(|x| with_format(x, args.save_dataset_format, "--save-dataset-format"))
+            .transpose()?,
+    })
 }
 
 fn main() {
     let seed = 42;
 
+    if stats_enabled() {
+        tree_with_allocated_children::print_input_stats(seed);
+    }
     // tree_with_allocated_children::run(seed);
-    tree_with_on_the_fly_children::run(seed);
+    if let Err(error) = tree_with_on_the_fly_children::run(seed) {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
 }
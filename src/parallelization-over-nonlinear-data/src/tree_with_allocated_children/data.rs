@@ -57,6 +57,11 @@ impl Node {
             .sum()
     }
 
+    /// Number of Fibonacci iterations performed by [`Node::compute`] for the `value`.
+    pub fn work_units(value: u64) -> u64 {
+        (0..*amount_of_work() as u64).map(|j| value + j).sum()
+    }
+
     pub fn compute_using_mut_var(value: u64, rng: &mut impl Rng) -> u64 {
         match rng.random_bool(0.7) {
             true => Self::compute(value),
@@ -64,6 +69,27 @@ impl Node {
         }
     }
 
+    /// Adjacency lists of the trees of the `roots` with the nodes in depth-first order,
+    /// together with the work units of each node.
+    pub fn adjacency(roots: &[Node]) -> (Vec<Vec<usize>>, Vec<u64>) {
+        fn push(node: &Node, adjacency: &mut Vec<Vec<usize>>, work_units: &mut Vec<u64>) -> usize {
+            let idx = adjacency.len();
+            adjacency.push(vec![]);
+            work_units.push(node.value.iter().map(|x| Node::work_units(*x)).sum());
+            for child in &node.children {
+                let child_idx = push(child, adjacency, work_units);
+                adjacency[idx].push(child_idx);
+            }
+            idx
+        }
+
+        let (mut adjacency, mut work_units) = (vec![], vec![]);
+        for root in roots {
+            push(root, &mut adjacency, &mut work_units);
+        }
+        (adjacency, work_units)
+    }
+
     pub fn example_roots(seed: u64) -> Vec<Node> {
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
@@ -1,4 +1,4 @@
-use crate::tree_with_allocated_children::data::Node;
+use crate::{graph_stats::GraphStats, tree_with_allocated_children::data::Node};
 
 mod data;
 mod immutable_collection;
@@ -15,3 +15,17 @@ pub fn run(seed: u64) {
 
     using_immutable_reduction::run_all(&roots);
 }
+
+/// Prints the statistics of the input trees of the experiments.
+pub fn print_input_stats(seed: u64) {
+    let roots = Node::example_roots(seed);
+    let (adjacency, work_units) = Node::adjacency(&roots);
+    let mut root_indices = vec![];
+    let mut idx = 0;
+    for root in &roots {
+        root_indices.push(idx);
+        idx += root.num_nodes();
+    }
+    let stats = GraphStats::new(&adjacency, &root_indices, |i| work_units[i]);
+    println!("\n\n# INPUT STATS - TREE WITH ALLOCATED CHILDREN\n{stats}");
+}
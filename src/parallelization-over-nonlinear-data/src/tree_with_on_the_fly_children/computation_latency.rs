@@ -0,0 +1,195 @@
+use crate::{
+    run_utils::run,
+    tree_with_on_the_fly_children::{
+        load_status::{NodeStatusPar, NodeStatusSeq, StatusPar, StatusSeq},
+        loader::{ChildLoader, LoaderConfig, SimulatedLoader},
+        node::Node,
+        node_storage::NodesStorage,
+    },
+};
+use orx_imp_vec::{ImpVec, PinnedVec};
+use orx_parallel::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// all
+
+pub fn run_all(storage: &NodesStorage, roots: &[&Node], config: LoaderConfig) {
+    println!("\n\n# IMMUTABLE REDUCTION - GROWTH ON THE FLY - SIMULATED LOAD LATENCY");
+    println!("{config:?}");
+    let log = |(sum, count, loads): (u64, usize, Loads)| {
+        println!(
+            "  count = {count}\n  sum = {sum}\n  failed loads = {}\n  dropped children = {}",
+            loads.num_failures, loads.num_dropped
+        )
+    };
+
+    let loader = || SimulatedLoader::new(config.clone());
+
+    run("sequential", || sequential(storage, roots, loader()), log);
+
+    // rayon miri fails with:
+    // Undefined Behavior: trying to retag from <84156795> for SharedReadWrite permission at alloc41643328[0x8],
+    // but that tag does not exist in the borrow stack for this location
+    #[cfg(not(miri))]
+    run("rayon", || rayon(storage, roots, loader()), log);
+
+    run("orx_rec", || orx_rec(storage, roots, loader()), log);
+    run(
+        "orx_rec_chunk",
+        || orx_rec_chunk(storage, roots, loader(), 16),
+        log,
+    );
+
+    println!();
+}
+
+/// Failures of the loads of a computation.
+pub struct Loads {
+    pub num_failures: usize,
+    /// Number of distinct symbols whose load failed.
+    pub num_dropped: usize,
+}
+
+impl Loads {
+    fn of<L: ChildLoader>(loader: &L) -> Self {
+        Self {
+            num_failures: loader.num_failures(),
+            num_dropped: loader.num_dropped(),
+        }
+    }
+}
+
+// seq
+
+pub fn sequential<L: ChildLoader>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    loader: L,
+) -> (u64, usize, Loads) {
+    let mut status = NodeStatusSeq::new(storage.all_nodes.len(), roots);
+
+    let tasks: ImpVec<_> = roots.iter().copied().collect();
+    let mut sum = 0;
+
+    for i in 0.. {
+        match tasks.get(i) {
+            None => break,
+            Some(node) => {
+                if status.start_processing(node) {
+                    // extend
+                    for s in &node.symbols_out {
+                        if let Some(child) = loader.load(storage, s)
+                            && status.load_child(child)
+                        {
+                            tasks.imp_push(child);
+                        }
+                    }
+
+                    // process
+                    let value = node.compute();
+                    sum += value;
+                }
+            }
+        }
+    }
+
+    (sum, status.num_processed(), Loads::of(&loader))
+}
+
+// rayon
+
+fn process_node<'scope, L: ChildLoader>(
+    storage: &'scope NodesStorage,
+    loader: &'scope L,
+    status: &'scope NodeStatusPar,
+    sum: &'scope AtomicU64,
+    node: &'scope Node,
+    s: &rayon::Scope<'scope>,
+) {
+    if status.start_processing(node) {
+        for symbol in &node.symbols_out {
+            if let Some(child) = loader.load(storage, symbol)
+                && status.load_child(child)
+            {
+                s.spawn(move |s| process_node(storage, loader, status, sum, child, s));
+            }
+        }
+        sum.fetch_add(node.compute(), Ordering::Relaxed);
+    }
+}
+
+pub fn rayon<L: ChildLoader>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    loader: L,
+) -> (u64, usize, Loads) {
+    let status = NodeStatusPar::new(storage.all_nodes.len(), roots);
+    let sum = AtomicU64::new(0);
+    rayon::in_place_scope(|s| {
+        for root in roots {
+            process_node(storage, &loader, &status, &sum, root, s);
+        }
+    });
+    (sum.into_inner(), status.num_processed(), Loads::of(&loader))
+}
+
+// orx
+
+fn get_extend<'x, 'b, L: ChildLoader>(
+    storage: &'x NodesStorage,
+    loader: &'x L,
+    status: &'x NodeStatusPar,
+) -> impl Fn(&&'b Node, &Queue<&'b Node>)
+where
+    'x: 'b,
+{
+    |node: &&'b Node, queue: &Queue<&'b Node>| {
+        if status.start_processing(node) {
+            for s in &node.symbols_out {
+                if let Some(child) = loader.load(storage, s)
+                    && status.load_child(child)
+                {
+                    queue.push(child);
+                }
+            }
+        }
+    }
+}
+
+pub fn orx_rec<L: ChildLoader>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    loader: L,
+) -> (u64, usize, Loads) {
+    let status = NodeStatusPar::new(storage.all_nodes.len(), roots);
+    let extend = get_extend(storage, &loader, &status);
+
+    let sum = roots
+        .iter()
+        .copied()
+        .into_par_rec(extend)
+        .map(|x| x.compute())
+        .sum();
+
+    (sum, status.num_processed(), Loads::of(&loader))
+}
+
+pub fn orx_rec_chunk<L: ChildLoader>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    loader: L,
+    chunk_size: usize,
+) -> (u64, usize, Loads) {
+    let status = NodeStatusPar::new(storage.all_nodes.len(), roots);
+    let extend = get_extend(storage, &loader, &status);
+
+    let sum = roots
+        .iter()
+        .copied()
+        .into_par_rec(extend)
+        .chunk_size(chunk_size)
+        .map(|x| x.compute())
+        .sum();
+
+    (sum, status.num_processed(), Loads::of(&loader))
+}
@@ -1,7 +1,7 @@
 use crate::{
     run_utils::run,
     tree_with_on_the_fly_children::{
-        load_status::{NodeStatusPar, NodeStatusSeq},
+        load_status::{NodeStatusPar, NodeStatusSeq, StatusPar, StatusSeq},
         node::Node,
         node_storage::NodesStorage,
     },
@@ -30,8 +30,15 @@ pub fn run_all(storage: &NodesStorage, roots: &[&Node]) {
 // seq
 
 pub fn sequential(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
-    let mut status = NodeStatusSeq::new(storage.all_nodes.len(), roots);
+    let status = NodeStatusSeq::new(storage.all_nodes.len(), roots);
+    sequential_with(storage, roots, status)
+}
 
+pub fn sequential_with<S: StatusSeq>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    mut status: S,
+) -> (u64, usize) {
     let tasks: ImpVec<_> = roots.iter().copied().collect();
     let mut sum = 0;
 
@@ -62,9 +69,9 @@ pub fn sequential(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
 
 // orx
 
-fn get_extend<'x, 'b>(
+fn get_extend<'x, 'b, S: StatusPar>(
     storage: &'x NodesStorage,
-    status: &'x NodeStatusPar,
+    status: &'x S,
 ) -> impl Fn(&&'b Node, &Queue<&'b Node>)
 where
     'x: 'b,
@@ -83,6 +90,14 @@ where
 
 pub fn orx_rec_exact(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
     let status = NodeStatusPar::new(storage.all_nodes.len(), roots);
+    orx_rec_exact_with(storage, roots, status)
+}
+
+pub fn orx_rec_exact_with<S: StatusPar>(
+    storage: &NodesStorage,
+    roots: &[&Node],
+    status: S,
+) -> (u64, usize) {
     let extend = get_extend(storage, &status);
 
     let sum = roots
@@ -0,0 +1,77 @@
+use crate::{
+    run_utils::run,
+    tree_with_on_the_fly_children::{
+        computation_reduce::{orx_rec_exact, orx_rec_exact_with, sequential, sequential_with},
+        load_status::{NodeStatusSparsePar, NodeStatusSparseSeq},
+        node::Node,
+        node_storage::NodesStorage,
+    },
+};
+
+// keys
+
+/// Key given by the dataset, or the hash of the name of the node.
+fn key_u64(node: &Node) -> &u64 {
+    &node.key
+}
+
+/// Name of the node, as in the dataset.
+fn key_string(node: &Node) -> &String {
+    &node.name
+}
+
+// all
+
+pub fn run_all(storage: &NodesStorage, roots: &[&Node]) {
+    println!("\n\n# IMMUTABLE REDUCTION - GROWTH ON THE FLY - SPARSE KEYS");
+    let log = |(sum, count): (u64, usize)| println!("  count = {count}\n  sum = {sum}");
+
+    run("sequential_dense", || sequential(storage, roots), log);
+    run(
+        "sequential_sparse_u64",
+        || sequential_sparse_u64(storage, roots),
+        log,
+    );
+    run(
+        "sequential_sparse_string",
+        || sequential_sparse_string(storage, roots),
+        log,
+    );
+    run("orx_rec_exact_dense", || orx_rec_exact(storage, roots), log);
+    run(
+        "orx_rec_exact_sparse_u64",
+        || orx_rec_exact_sparse_u64(storage, roots),
+        log,
+    );
+    run(
+        "orx_rec_exact_sparse_string",
+        || orx_rec_exact_sparse_string(storage, roots),
+        log,
+    );
+
+    println!();
+}
+
+// seq
+
+pub fn sequential_sparse_u64(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
+    let status = NodeStatusSparseSeq::new(roots, key_u64);
+    sequential_with(storage, roots, status)
+}
+
+pub fn sequential_sparse_string(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
+    let status = NodeStatusSparseSeq::new(roots, key_string);
+    sequential_with(storage, roots, status)
+}
+
+// orx
+
+pub fn orx_rec_exact_sparse_u64(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
+    let status = NodeStatusSparsePar::new(roots, key_u64);
+    orx_rec_exact_with(storage, roots, status)
+}
+
+pub fn orx_rec_exact_sparse_string(storage: &NodesStorage, roots: &[&Node]) -> (u64, usize) {
+    let status = NodeStatusSparsePar::new(roots, key_string);
+    orx_rec_exact_with(storage, roots, status)
+}
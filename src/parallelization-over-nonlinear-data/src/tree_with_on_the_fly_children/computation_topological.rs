@@ -0,0 +1,318 @@
+use crate::{
+    graph_stats::{is_cyclic, strongly_connected_components},
+    run_utils::run,
+    tree_with_on_the_fly_children::{node::Node, node_storage::NodesStorage},
+};
+use orx_parallel::*;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+// setup
+
+/// Dependency graph of the nodes reachable from the roots, where a node depends on the
+/// nodes defining its `symbols_out`, and each node is executed as a task of its own.
+///
+/// Nodes on a cycle cannot be ordered; hence, the cycles are reported and broken by dropping
+/// the back edges of a depth first search from the roots, which leaves a DAG.
+pub struct TaskGraph<'a> {
+    /// Dependencies of the reachable nodes including the dropped back edges.
+    adjacency: Vec<Vec<usize>>,
+    nodes: Vec<&'a Node>,
+    num_back_edges: usize,
+    dependencies: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+}
+
+impl<'a> TaskGraph<'a> {
+    pub fn new(storage: &'a NodesStorage, roots: &[&Node]) -> Self {
+        let full_adjacency = (storage.adjacency())
+            .expect("the symbols of a generated or validated storage are defined");
+
+        // restrict to the nodes reachable from the roots
+        let mut local_idx = vec![usize::MAX; storage.all_nodes.len()];
+        let mut nodes = vec![];
+        for root in roots {
+            if local_idx[root.id] == usize::MAX {
+                local_idx[root.id] = nodes.len();
+                nodes.push(&storage.all_nodes[root.id]);
+            }
+        }
+        let mut i = 0;
+        while i < nodes.len() {
+            for child in &full_adjacency[nodes[i].id] {
+                if local_idx[*child] == usize::MAX {
+                    local_idx[*child] = nodes.len();
+                    nodes.push(&storage.all_nodes[*child]);
+                }
+            }
+            i += 1;
+        }
+        let adjacency: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|x| full_adjacency[x.id].iter().map(|c| local_idx[*c]).collect())
+            .collect();
+
+        let (dependencies, num_back_edges) = break_back_edges(&adjacency);
+        let mut dependents = vec![vec![]; nodes.len()];
+        for (t, deps) in dependencies.iter().enumerate() {
+            for d in deps {
+                dependents[*d].push(t);
+            }
+        }
+
+        Self {
+            adjacency,
+            nodes,
+            num_back_edges,
+            dependencies,
+            dependents,
+        }
+    }
+
+    pub fn num_tasks(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Tasks without any dependencies which are ready to be executed at the start.
+    pub fn initial_tasks(&self) -> Vec<usize> {
+        (0..self.num_tasks())
+            .filter(|t| self.dependencies[*t].is_empty())
+            .collect()
+    }
+
+    /// Prints the strongly connected components and cycles of the graph, and the number of
+    /// back edges dropped to break them.
+    pub fn report_cycles(&self) {
+        let sccs = strongly_connected_components(&self.adjacency);
+        let largest_scc = sccs.iter().map(|x| x.len()).max().unwrap_or(0);
+        let cyclic: Vec<_> = sccs
+            .iter()
+            .filter(|x| is_cyclic(&self.adjacency, x))
+            .collect();
+        let num_nodes: usize = cyclic.iter().map(|x| x.len()).sum();
+        println!(
+            "  strongly connected components = {} for {} nodes, the largest with {largest_scc} nodes",
+            sccs.len(),
+            self.nodes.len()
+        );
+        println!(
+            "  cyclic components = {} containing {num_nodes} nodes",
+            cyclic.len()
+        );
+
+        if let Some(largest) = cyclic.iter().max_by_key(|x| x.len()) {
+            let cycle: Vec<_> = self
+                .find_cycle(largest)
+                .iter()
+                .map(|x| self.nodes[*x].name.as_str())
+                .collect();
+            println!(
+                "  example cycle of length {} in the largest component of {} nodes:\n    {} -> {}",
+                cycle.len(),
+                largest.len(),
+                cycle.join(" -> "),
+                cycle[0]
+            );
+        }
+        println!(
+            "  dropped back edges = {} of {} dependencies",
+            self.num_back_edges,
+            self.adjacency.iter().map(|x| x.len()).sum::<usize>()
+        );
+    }
+
+    /// Walks the edges within the cyclic component until a node is repeated.
+    fn find_cycle(&self, members: &[usize]) -> Vec<usize> {
+        let mut position = vec![usize::MAX; self.nodes.len()];
+        let in_component = |x: &usize| members.contains(x);
+        let mut path = vec![];
+        let mut node = members[0];
+        while position[node] == usize::MAX {
+            position[node] = path.len();
+            path.push(node);
+            node = *self.adjacency[node]
+                .iter()
+                .find(|x| in_component(x))
+                .expect("every node of a cyclic component has an edge within the component");
+        }
+        path.split_off(position[node])
+    }
+}
+
+/// Returns the `adjacency` without the back edges of a depth first search visiting the
+/// nodes in index order, which is acyclic, together with the number of dropped edges.
+fn break_back_edges(adjacency: &[Vec<usize>]) -> (Vec<Vec<usize>>, usize) {
+    let mut visited = vec![false; adjacency.len()];
+    let mut on_stack = vec![false; adjacency.len()];
+    let mut dag = vec![vec![]; adjacency.len()];
+    let mut num_back_edges = 0;
+
+    for start in 0..adjacency.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        on_stack[start] = true;
+        // nodes on the current path with the position of their next edge to visit
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            let Some(&child) = adjacency[node].get(*next) else {
+                on_stack[node] = false;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            match on_stack[child] {
+                true => num_back_edges += 1,
+                false => {
+                    dag[node].push(child);
+                    if !visited[child] {
+                        visited[child] = true;
+                        on_stack[child] = true;
+                        stack.push((child, 0));
+                    }
+                }
+            }
+        }
+    }
+
+    (dag, num_back_edges)
+}
+
+/// Shared state of an execution of the task graph.
+struct Execution {
+    remaining_dependencies: Vec<AtomicUsize>,
+    sequence: AtomicUsize,
+    started: Vec<AtomicUsize>,
+    finished: Vec<AtomicUsize>,
+    sum: AtomicU64,
+}
+
+impl Execution {
+    fn new(graph: &TaskGraph) -> Self {
+        let stamps = || (0..graph.num_tasks()).map(|_| usize::MAX.into()).collect();
+        Self {
+            remaining_dependencies: graph.dependencies.iter().map(|x| x.len().into()).collect(),
+            sequence: 0.into(),
+            started: stamps(),
+            finished: stamps(),
+            sum: 0.into(),
+        }
+    }
+
+    /// Executes the `task` and calls `ready` for each dependent task whose dependencies are now all completed.
+    fn execute(&self, graph: &TaskGraph, task: usize, mut ready: impl FnMut(usize)) {
+        let stamp = || self.sequence.fetch_add(1, Ordering::AcqRel);
+
+        self.started[task].store(stamp(), Ordering::Release);
+        let value = graph.nodes[task].compute();
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.finished[task].store(stamp(), Ordering::Release);
+
+        for dependent in &graph.dependents[task] {
+            if self.remaining_dependencies[*dependent].fetch_sub(1, Ordering::AcqRel) == 1 {
+                ready(*dependent);
+            }
+        }
+    }
+
+    /// Returns the sum, number of executed tasks and number of violated dependencies.
+    fn result(self, graph: &TaskGraph) -> (u64, usize, usize) {
+        let started: Vec<_> = self.started.into_iter().map(|x| x.into_inner()).collect();
+        let finished: Vec<_> = self.finished.into_iter().map(|x| x.into_inner()).collect();
+
+        let num_executed = finished.iter().filter(|x| **x != usize::MAX).count();
+        let num_violations = (0..graph.num_tasks())
+            .flat_map(|t| graph.dependencies[t].iter().map(move |d| (t, *d)))
+            .filter(|(t, d)| started[*t] == usize::MAX || finished[*d] > started[*t])
+            .count();
+
+        (self.sum.into_inner(), num_executed, num_violations)
+    }
+}
+
+// all
+
+pub fn run_all(storage: &NodesStorage, roots: &[&Node]) {
+    println!("\n\n# TOPOLOGICAL EXECUTION - DEPENDENCIES FIRST");
+    let graph = TaskGraph::new(storage, roots);
+    graph.report_cycles();
+    println!();
+
+    let log = |(sum, executed, violations): (u64, usize, usize)| {
+        println!(
+            "  executed tasks = {executed}\n  sum = {sum}\n  violated dependencies = {violations}"
+        )
+    };
+
+    run("sequential", || sequential(&graph), log);
+
+    // rayon miri fails with:
+    // Undefined Behavior: trying to retag from <84156795> for SharedReadWrite permission at alloc41643328[0x8],
+    // but that tag does not exist in the borrow stack for this location
+    #[cfg(not(miri))]
+    run("rayon", || rayon(&graph), log);
+
+    run("orx_rec", || orx_rec(&graph), log);
+
+    println!();
+}
+
+// seq
+
+pub fn sequential(graph: &TaskGraph) -> (u64, usize, usize) {
+    let execution = Execution::new(graph);
+    let mut ready: VecDeque<_> = graph.initial_tasks().into();
+    while let Some(task) = ready.pop_front() {
+        execution.execute(graph, task, |t| ready.push_back(t));
+    }
+    execution.result(graph)
+}
+
+// rayon
+
+fn process_task<'scope>(
+    graph: &'scope TaskGraph,
+    execution: &'scope Execution,
+    task: usize,
+    s: &rayon::Scope<'scope>,
+) {
+    execution.execute(graph, task, |t| {
+        s.spawn(move |s| process_task(graph, execution, t, s))
+    });
+}
+
+pub fn rayon(graph: &TaskGraph) -> (u64, usize, usize) {
+    let execution = Execution::new(graph);
+    rayon::in_place_scope(|s| {
+        for task in graph.initial_tasks() {
+            let execution = &execution;
+            s.spawn(move |s| process_task(graph, execution, task, s));
+        }
+    });
+    execution.result(graph)
+}
+
+// orx
+
+pub fn orx_rec(graph: &TaskGraph) -> (u64, usize, usize) {
+    let execution = Execution::new(graph);
+
+    // tasks are executed within extend, so that their dependents are
+    // pushed to the queue only after they are completed
+    let extend = |task: &usize, queue: &Queue<usize>| {
+        execution.execute(graph, *task, |t| queue.push(t));
+    };
+
+    graph
+        .initial_tasks()
+        .into_iter()
+        .into_par_rec(extend)
+        .count();
+
+    execution.result(graph)
+}
@@ -0,0 +1,802 @@
+//! Persisting and importing [`NodesStorage`] graphs.
+//!
+//! In all formats, the `id` of a node is its position in the file, which keeps the
+//! ids dense as required by the index-based visit status.
+//!
+//! # JSON lines (`.jsonl`)
+//!
+//! One node per line:
+//!
+//! ```text
+//! {"key":17578501715939098556,"name":"node-0","symbols":["0_0","0_1"],"symbols_out":["7_3"]}
+//! ```
+//!
+//! # Binary (`.bin`)
+//!
+//! All integers are little endian and every string is a `u32` byte length followed by utf-8 bytes.
+//!
+//! ```text
+//! magic   : b"NSTG"
+//! version : u8 (= 1)
+//! len     : u64
+//! nodes   : len x { key: u64, name: str, symbols: u32 x str, symbols_out: u32 x str }
+//! ```
+//!
+//! # Edge list (`.edges`, `.txt`)
+//!
+//! One `source target` pair of whitespace separated names per line, meaning that `source`
+//! depends on `target`. Empty lines and lines starting with `#` are ignored; a line with a
+//! single name declares an isolated node.
+//!
+//! # Graphviz DOT (`.dot`, `.gv`)
+//!
+//! A subset of the DOT language: node statements, edge chains such as `a -> b -> c`, edge
+//! groups such as `a -> {b c}`, quoted ids and comments. Attribute lists and graph level
+//! attributes are ignored. Undirected edges (`--`) are imported in both directions. Edge
+//! groups may only contain node ids; groups with edges or nested groups are rejected.
+//!
+//! Imported nodes define a single symbol, their name, and each edge `source -> target`
+//! adds the name of the target to the `symbols_out` of the source.
+//!
+//! The edge list and DOT writers only keep the dependency graph: the symbols of a node are
+//! replaced by its name, and its key by the hash of its name.
+
+use crate::tree_with_on_the_fly_children::{node::Node, node_storage::NodesStorage};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8; 4] = b"NSTG";
+const VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DatasetFormat {
+    Jsonl,
+    Binary,
+    EdgeList,
+    Dot,
+}
+
+impl DatasetFormat {
+    /// Infers the format from the extension of the `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "jsonl" => Some(Self::Jsonl),
+            "bin" => Some(Self::Binary),
+            "edges" | "txt" => Some(Self::EdgeList),
+            "dot" | "gv" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeRecord {
+    key: u64,
+    name: String,
+    symbols: Vec<String>,
+    symbols_out: Vec<String>,
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+impl NodesStorage {
+    pub fn load(path: &Path, format: DatasetFormat) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let storage = match format {
+            DatasetFormat::Jsonl => Self::read_jsonl(reader),
+            DatasetFormat::Binary => Self::read_binary(reader),
+            DatasetFormat::EdgeList => Self::read_edge_list(reader),
+            DatasetFormat::Dot => Self::read_dot(reader),
+        }?;
+        storage.validate()?;
+        Ok(storage)
+    }
+
+    pub fn save(&self, path: &Path, format: DatasetFormat) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            DatasetFormat::Jsonl => self.write_jsonl(&mut writer),
+            DatasetFormat::Binary => self.write_binary(&mut writer),
+            DatasetFormat::EdgeList => self.write_edge_list(&mut writer),
+            DatasetFormat::Dot => self.write_dot(&mut writer),
+        }?;
+        writer.flush()
+    }
+
+    /// Makes sure that every symbol a node depends on is defined by some node.
+    fn validate(&self) -> io::Result<()> {
+        self.adjacency().map(|_| ())
+    }
+
+    // jsonl
+
+    pub fn write_jsonl(&self, writer: &mut impl Write) -> io::Result<()> {
+        for node in &self.all_nodes {
+            let record = NodeRecord {
+                key: node.key,
+                name: node.name.clone(),
+                symbols: node.symbols.clone(),
+                symbols_out: node.symbols_out.clone(),
+            };
+            serde_json::to_writer(&mut *writer, &record)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    pub fn read_jsonl(reader: impl BufRead) -> io::Result<Self> {
+        let mut all_nodes = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: NodeRecord = serde_json::from_str(&line)?;
+            all_nodes.push(Node {
+                id: all_nodes.len(),
+                key: record.key,
+                name: record.name,
+                symbols: record.symbols,
+                symbols_out: record.symbols_out,
+            });
+        }
+        Ok(Self { all_nodes })
+    }
+
+    // binary
+
+    pub fn write_binary(&self, writer: &mut impl Write) -> io::Result<()> {
+        fn write_str(writer: &mut impl Write, s: &str) -> io::Result<()> {
+            writer.write_all(&(s.len() as u32).to_le_bytes())?;
+            writer.write_all(s.as_bytes())
+        }
+
+        fn write_strs(writer: &mut impl Write, strs: &[String]) -> io::Result<()> {
+            writer.write_all(&(strs.len() as u32).to_le_bytes())?;
+            strs.iter().try_for_each(|s| write_str(writer, s))
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(self.all_nodes.len() as u64).to_le_bytes())?;
+        for node in &self.all_nodes {
+            writer.write_all(&node.key.to_le_bytes())?;
+            write_str(writer, &node.name)?;
+            write_strs(writer, &node.symbols)?;
+            write_strs(writer, &node.symbols_out)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_binary(mut reader: impl Read) -> io::Result<Self> {
+        fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+            let mut bytes = [0u8; N];
+            reader.read_exact(&mut bytes)?;
+            Ok(bytes)
+        }
+
+        fn read_u32(reader: &mut impl Read) -> io::Result<usize> {
+            Ok(u32::from_le_bytes(read_array(reader)?) as usize)
+        }
+
+        fn read_str(reader: &mut impl Read) -> io::Result<String> {
+            let len = read_u32(reader)?;
+            let mut bytes = vec![];
+            reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+            match bytes.len() == len {
+                true => String::from_utf8(bytes).map_err(|e| invalid_data(e.to_string())),
+                false => Err(io::ErrorKind::UnexpectedEof.into()),
+            }
+        }
+
+        fn read_strs(reader: &mut impl Read) -> io::Result<Vec<String>> {
+            let len = read_u32(reader)?;
+            (0..len).map(|_| read_str(reader)).collect()
+        }
+
+        if &read_array::<4>(&mut reader)? != MAGIC {
+            return Err(invalid_data("not a nodes storage binary file"));
+        }
+        let [version] = read_array(&mut reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported version {version}")));
+        }
+
+        let len = u64::from_le_bytes(read_array(&mut reader)?) as usize;
+        let mut all_nodes = Vec::with_capacity(len.min(1 << 20));
+        for id in 0..len {
+            all_nodes.push(Node {
+                id,
+                key: u64::from_le_bytes(read_array(&mut reader)?),
+                name: read_str(&mut reader)?,
+                symbols: read_strs(&mut reader)?,
+                symbols_out: read_strs(&mut reader)?,
+            });
+        }
+        Ok(Self { all_nodes })
+    }
+
+    // edge list
+
+    pub fn read_edge_list(reader: impl BufRead) -> io::Result<Self> {
+        let mut builder = GraphBuilder::default();
+        for (line_idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut names = line.split_whitespace();
+            match (names.next(), names.next(), names.next()) {
+                (None, ..) => continue,
+                (Some(x), ..) if x.starts_with('#') => continue,
+                (Some(name), None, _) => _ = builder.node(name),
+                (Some(source), Some(target), None) => builder.edge(source, target),
+                _ => {
+                    return Err(invalid_data(format!(
+                        "line {}: expected `source target`",
+                        line_idx + 1
+                    )));
+                }
+            }
+        }
+        Ok(builder.build())
+    }
+
+    /// Writes the dependency graph as an edge list; lossy since the symbols of the nodes
+    /// are collapsed to edges between their names.
+    pub fn write_edge_list(&self, writer: &mut impl Write) -> io::Result<()> {
+        if let Some(node) = self.all_nodes.iter().find(|x| {
+            x.name.is_empty() || x.name.starts_with('#') || x.name.contains(char::is_whitespace)
+        }) {
+            return Err(invalid_data(format!(
+                "node name {:?} cannot be written to an edge list",
+                node.name
+            )));
+        }
+
+        for (node, children) in self.all_nodes.iter().zip(self.adjacency()?) {
+            match children.is_empty() {
+                true => writeln!(writer, "{}", node.name)?,
+                false => {
+                    for child in children {
+                        writeln!(writer, "{} {}", node.name, self.all_nodes[child].name)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // dot
+
+    pub fn read_dot(mut reader: impl Read) -> io::Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let tokens = dot_tokens(&text)?;
+
+        let body = match tokens.iter().position(|x| *x == DotToken::Punct('{')) {
+            Some(open) => &tokens[open + 1..],
+            None => return Err(invalid_data("missing `{` of the graph body")),
+        };
+
+        let mut builder = GraphBuilder::default();
+        // nodes of the last operand of the current statement and the edge operator following it
+        let mut prev: Option<Vec<&str>> = None;
+        let mut edge: Option<bool> = None;
+        let mut depth = 0;
+
+        let mut i = 0;
+        while i < body.len() {
+            match &body[i] {
+                DotToken::Punct('[') => depth += 1,
+                DotToken::Punct(']') => depth -= 1,
+                _ if depth > 0 => {}
+                DotToken::Id {
+                    name,
+                    quoted: false,
+                } if name.eq_ignore_ascii_case("subgraph") => {
+                    // skip the name of a subgraph, its body is handled by the following `{`
+                    if matches!(body.get(i + 1), Some(DotToken::Id { .. })) {
+                        i += 1;
+                    }
+                }
+                DotToken::Id {
+                    name,
+                    quoted: false,
+                } if prev.is_none() && is_dot_keyword(name) => {}
+                DotToken::Id { .. } if body.get(i + 1) == Some(&DotToken::Punct('=')) => {
+                    // graph level attribute such as `rankdir = LR`
+                    i += 2;
+                }
+                DotToken::Id { name, .. } => {
+                    let operand = vec![name.as_str()];
+                    builder.operand(prev.as_deref(), edge.take(), &operand);
+                    prev = Some(operand);
+                }
+                DotToken::Punct('{') => {
+                    let close = dot_matching_brace(body, i)?;
+                    let is_operand = edge.is_some()
+                        || matches!(body.get(close + 1), Some(DotToken::Edge { .. }));
+                    match is_operand {
+                        // edge group such as `a -> {b c}`
+                        true => {
+                            let operand = dot_edge_group(&body[i + 1..close])?;
+                            builder.operand(prev.as_deref(), edge.take(), &operand);
+                            prev = Some(operand);
+                            i = close;
+                        }
+                        // body of a subgraph whose statements are parsed as they are reached
+                        false => (prev, edge) = (None, None),
+                    }
+                }
+                DotToken::Edge { undirected } => match prev {
+                    Some(_) => edge = Some(*undirected),
+                    None => return Err(invalid_data("edge without a source node")),
+                },
+                DotToken::Punct(_) if edge.is_some() => {
+                    return Err(invalid_data("edge without a target node"));
+                }
+                DotToken::Punct(_) => (prev, edge) = (None, None),
+            }
+            i += 1;
+        }
+
+        match edge {
+            Some(_) => Err(invalid_data("edge without a target node")),
+            None => Ok(builder.build()),
+        }
+    }
+
+    /// Writes the dependency graph in DOT; lossy since each node is written as a single
+    /// quoted id, its name, and its symbols are collapsed to edges.
+    pub fn write_dot(&self, writer: &mut impl Write) -> io::Result<()> {
+        let quoted = |name: &str| format!("\"{}\"", name.replace('"', "\\\""));
+        writeln!(writer, "digraph {{")?;
+        for (node, children) in self.all_nodes.iter().zip(self.adjacency()?) {
+            write!(writer, "    {}", quoted(&node.name))?;
+            if !children.is_empty() {
+                let children: Vec<_> = children
+                    .iter()
+                    .map(|i| quoted(&self.all_nodes[*i].name))
+                    .collect();
+                write!(writer, " -> {{{}}}", children.join(" "))?;
+            }
+            writeln!(writer, ";")?;
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+// dot tokenizer
+
+#[derive(Debug, PartialEq, Eq)]
+enum DotToken {
+    /// Quoted ids are never keywords.
+    Id {
+        name: String,
+        quoted: bool,
+    },
+    Edge {
+        undirected: bool,
+    },
+    Punct(char),
+}
+
+fn is_dot_keyword(id: &str) -> bool {
+    ["graph", "digraph", "subgraph", "node", "edge", "strict"]
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(id))
+}
+
+/// Returns the position of the `}` closing the `{` at position `open`.
+fn dot_matching_brace(tokens: &[DotToken], open: usize) -> io::Result<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            DotToken::Punct('{') => depth += 1,
+            DotToken::Punct('}') if depth == 1 => return Ok(i),
+            DotToken::Punct('}') => depth -= 1,
+            _ => {}
+        }
+    }
+    Err(invalid_data("missing `}`"))
+}
+
+/// Names of the nodes of an edge group such as `{b c}`, given the tokens within the braces.
+fn dot_edge_group(tokens: &[DotToken]) -> io::Result<Vec<&str>> {
+    tokens
+        .iter()
+        .filter(|x| !matches!(x, DotToken::Punct(';' | ',')))
+        .map(|x| match x {
+            DotToken::Id { name, .. } => Ok(name.as_str()),
+            _ => Err(invalid_data(
+                "edge groups may only contain node ids, such as `a -> {b c}`",
+            )),
+        })
+        .collect()
+}
+
+fn dot_tokens(text: &str) -> io::Result<Vec<DotToken>> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|x| *x != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|x| *x != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '-' if matches!(chars.peek(), Some('>' | '-')) => {
+                let undirected = chars.next() == Some('-');
+                tokens.push(DotToken::Edge { undirected });
+            }
+            '"' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err(invalid_data("unterminated quoted id")),
+                        Some('"') => break,
+                        Some('\\') if chars.peek() == Some(&'"') => {
+                            name.push(chars.next().unwrap())
+                        }
+                        Some(c) => name.push(c),
+                    }
+                }
+                tokens.push(DotToken::Id { name, quoted: true });
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut name = String::from(c);
+                while let Some(c) = chars.next_if(|x| x.is_alphanumeric() || *x == '_' || *x == '.')
+                {
+                    name.push(c);
+                }
+                tokens.push(DotToken::Id {
+                    name,
+                    quoted: false,
+                });
+            }
+            c => tokens.push(DotToken::Punct(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// graph builder shared by the importers
+
+#[derive(Default)]
+struct GraphBuilder {
+    indices: HashMap<String, usize>,
+    all_nodes: Vec<Node>,
+}
+
+impl GraphBuilder {
+    fn node(&mut self, name: &str) -> usize {
+        if let Some(idx) = self.indices.get(name) {
+            return *idx;
+        }
+
+        let id = self.all_nodes.len();
+        self.all_nodes.push(Node {
+            id,
+            key: Node::key_of(name),
+            name: name.to_string(),
+            symbols: vec![name.to_string()],
+            symbols_out: vec![],
+        });
+        self.indices.insert(name.to_string(), id);
+        id
+    }
+
+    fn edge(&mut self, source: &str, target: &str) {
+        let source = self.node(source);
+        self.node(target);
+        let symbols_out = &mut self.all_nodes[source].symbols_out;
+        if !symbols_out.iter().any(|s| s == target) {
+            symbols_out.push(target.to_string());
+        }
+    }
+
+    /// Adds the edges from each of the `sources` to each of the `targets` if the edge
+    /// operator between them is given, or the `targets` as nodes otherwise.
+    fn operand(&mut self, sources: Option<&[&str]>, edge: Option<bool>, targets: &[&str]) {
+        match (sources, edge) {
+            (Some(sources), Some(undirected)) => {
+                for source in sources {
+                    for target in targets {
+                        self.edge(source, target);
+                        if undirected {
+                            self.edge(target, source);
+                        }
+                    }
+                }
+            }
+            _ => targets.iter().for_each(|x| _ = self.node(x)),
+        }
+    }
+
+    fn build(self) -> NodesStorage {
+        NodesStorage {
+            all_nodes: self.all_nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    type Fields = (usize, u64, String, Vec<String>, Vec<String>);
+
+    fn fields(storage: &NodesStorage) -> Vec<Fields> {
+        let fields = |x: &Node| {
+            let (symbols, symbols_out) = (x.symbols.clone(), x.symbols_out.clone());
+            (x.id, x.key, x.name.clone(), symbols, symbols_out)
+        };
+        storage.all_nodes.iter().map(fields).collect()
+    }
+
+    /// Names of the nodes each node depends on, by name.
+    fn edges(storage: &NodesStorage) -> BTreeMap<String, Vec<String>> {
+        let name = |i: usize| storage.all_nodes[i].name.clone();
+        let adjacency = storage.adjacency().unwrap();
+        (0..storage.all_nodes.len())
+            .map(|i| {
+                let mut children: Vec<_> = adjacency[i].iter().map(|j| name(*j)).collect();
+                children.sort();
+                (name(i), children)
+            })
+            .collect()
+    }
+
+    fn storage() -> NodesStorage {
+        NodesStorage::new(100, &mut ChaCha8Rng::seed_from_u64(42))
+    }
+
+    fn dot(text: &str) -> io::Result<BTreeMap<String, Vec<String>>> {
+        NodesStorage::read_dot(text.as_bytes()).map(|x| edges(&x))
+    }
+
+    fn expected(edges: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        let names = |x: &[&str]| x.iter().map(|x| x.to_string()).collect();
+        edges
+            .iter()
+            .map(|(x, y)| (x.to_string(), names(y)))
+            .collect()
+    }
+
+    #[test]
+    fn jsonl_round_trip() {
+        let storage = storage();
+        let mut bytes = vec![];
+        storage.write_jsonl(&mut bytes).unwrap();
+        let read = NodesStorage::read_jsonl(&bytes[..]).unwrap();
+        assert_eq!(fields(&read), fields(&storage));
+        read.validate().unwrap();
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let storage = storage();
+        let mut bytes = vec![];
+        storage.write_binary(&mut bytes).unwrap();
+        let read = NodesStorage::read_binary(&bytes[..]).unwrap();
+        assert_eq!(fields(&read), fields(&storage));
+    }
+
+    #[test]
+    fn edge_list_round_trip() {
+        let storage = storage();
+        let mut bytes = vec![];
+        storage.write_edge_list(&mut bytes).unwrap();
+        let read = NodesStorage::read_edge_list(&bytes[..]).unwrap();
+        assert_eq!(edges(&read), edges(&storage));
+        read.validate().unwrap();
+    }
+
+    #[test]
+    fn dot_round_trip() {
+        let mut storage = storage();
+        // names which are keywords or need escaping
+        storage.all_nodes[0].name = "node".to_string();
+        storage.all_nodes[1].name = "graph".to_string();
+        storage.all_nodes[2].name = "with \"quotes\" and spaces".to_string();
+        let mut bytes = vec![];
+        storage.write_dot(&mut bytes).unwrap();
+        let read = NodesStorage::read_dot(&bytes[..]).unwrap();
+        assert_eq!(edges(&read), edges(&storage));
+        read.validate().unwrap();
+    }
+
+    #[test]
+    fn empty_datasets() {
+        let empty = NodesStorage { all_nodes: vec![] };
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        assert!(empty.get_roots(20, &mut rng).is_empty());
+
+        let mut bytes = vec![];
+        empty.write_binary(&mut bytes).unwrap();
+        assert!(
+            NodesStorage::read_binary(&bytes[..])
+                .unwrap()
+                .all_nodes
+                .is_empty()
+        );
+        assert!(
+            NodesStorage::read_jsonl(&b"\n\n"[..])
+                .unwrap()
+                .all_nodes
+                .is_empty()
+        );
+        assert!(
+            NodesStorage::read_edge_list(&b"# comment\n"[..])
+                .unwrap()
+                .all_nodes
+                .is_empty()
+        );
+        assert_eq!(dot("digraph {}").unwrap(), expected(&[]));
+    }
+
+    #[test]
+    fn invalid_binary() {
+        let mut bytes = vec![];
+        storage().write_binary(&mut bytes).unwrap();
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        let mut version = bytes.clone();
+        version[4] = VERSION + 1;
+        for (bytes, kind) in [
+            (magic, io::ErrorKind::InvalidData),
+            (version, io::ErrorKind::InvalidData),
+            (
+                bytes[..bytes.len() - 1].to_vec(),
+                io::ErrorKind::UnexpectedEof,
+            ),
+        ] {
+            let error = NodesStorage::read_binary(&bytes[..]).err().unwrap();
+            assert_eq!(error.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn invalid_jsonl_and_edge_list() {
+        assert!(NodesStorage::read_jsonl(&b"{\"key\":1}\n"[..]).is_err());
+        let error = NodesStorage::read_edge_list(&b"a b\na b c\n"[..])
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "line 2: expected `source target`");
+
+        let mut storage = storage();
+        storage.all_nodes[3].name = "two words".to_string();
+        assert!(storage.write_edge_list(&mut vec![]).is_err());
+    }
+
+    #[test]
+    fn undefined_symbol() {
+        let mut storage = storage();
+        storage.all_nodes[0]
+            .symbols_out
+            .push("undefined".to_string());
+        assert!(storage.validate().is_err());
+        assert!(storage.adjacency().is_err());
+    }
+
+    #[test]
+    fn symbol_defined_twice_resolves_to_the_first_node() {
+        let strings = |x: &[&str]| x.iter().map(|x| x.to_string()).collect();
+        let node = |id: usize, symbols: &[&str], symbols_out: &[&str]| Node {
+            id,
+            key: id as u64,
+            name: format!("n{id}"),
+            symbols: strings(symbols),
+            symbols_out: strings(symbols_out),
+        };
+        let storage = NodesStorage {
+            all_nodes: vec![
+                node(0, &[], &["s"]),
+                node(1, &["s"], &[]),
+                node(2, &["s"], &[]),
+            ],
+        };
+        assert_eq!(storage.get_relevant_node("s").id, 1);
+        assert_eq!(storage.adjacency().unwrap(), [vec![1], vec![], vec![]]);
+    }
+
+    #[test]
+    fn dot_edge_groups() {
+        let text = "digraph { a -> {b c}; {d; e} -> f -> {g, h} }";
+        assert_eq!(
+            dot(text).unwrap(),
+            expected(&[
+                ("a", &["b", "c"]),
+                ("b", &[]),
+                ("c", &[]),
+                ("d", &["f"]),
+                ("e", &["f"]),
+                ("f", &["g", "h"]),
+                ("g", &[]),
+                ("h", &[]),
+            ])
+        );
+
+        let text = "digraph { a -> subgraph s { b c } }";
+        assert_eq!(
+            dot(text).unwrap(),
+            expected(&[("a", &["b", "c"]), ("b", &[]), ("c", &[])])
+        );
+
+        // a subgraph which is not an edge operand is parsed statement by statement
+        let text = "digraph { subgraph s { a -> b } c }";
+        assert_eq!(
+            dot(text).unwrap(),
+            expected(&[("a", &["b"]), ("b", &[]), ("c", &[])])
+        );
+
+        for text in [
+            "digraph { a -> {b -> c} }",
+            "digraph { a -> {b {c}} }",
+            "digraph { a -> {b c",
+        ] {
+            assert!(dot(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn dot_quoted_keywords() {
+        let text = r#"digraph { "node" -> "graph"; node [shape=box]; "edge" -- x }"#;
+        assert_eq!(
+            dot(text).unwrap(),
+            expected(&[
+                ("edge", &["x"]),
+                ("graph", &[]),
+                ("node", &["graph"]),
+                ("x", &["edge"]),
+            ])
+        );
+    }
+
+    #[test]
+    fn dot_statements() {
+        let text = r#"
+            strict digraph "g" {
+                rankdir = LR; // comment
+                /* block
+                   comment */
+                a [label="A"];
+                a -> b -> c [color=red];
+                # preprocessor style comment
+                "d e" -> a
+            }"#;
+        assert_eq!(
+            dot(text).unwrap(),
+            expected(&[("a", &["b"]), ("b", &["c"]), ("c", &[]), ("d e", &["a"]),])
+        );
+
+        for text in [
+            "a -> b",
+            "digraph { -> a }",
+            "digraph { a -> }",
+            "digraph { \"a }",
+        ] {
+            assert!(dot(text).is_err(), "{text}");
+        }
+    }
+}
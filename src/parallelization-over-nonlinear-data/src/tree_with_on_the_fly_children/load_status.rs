@@ -1,5 +1,33 @@
-use crate::tree_with_on_the_fly_children::node::Node;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crate::tree_with_on_the_fly_children::{node::Node, sharded_set::ShardedSet};
+use std::{
+    collections::HashSet,
+    hash::Hash,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Visit status of nodes shared by the threads of a parallel computation.
+pub trait StatusPar: Sync {
+    /// Marks the `node` as processed; returns false if it was already processed.
+    fn start_processing(&self, node: &Node) -> bool;
+
+    /// Marks the `child` as loaded; returns false if it was already loaded or processed.
+    fn load_child(&self, child: &Node) -> bool;
+
+    fn num_processed(&self) -> usize;
+}
+
+/// Visit status of nodes used by a sequential computation.
+pub trait StatusSeq {
+    /// Marks the `node` as processed; returns false if it was already processed.
+    fn start_processing(&mut self, node: &Node) -> bool;
+
+    /// Marks the `child` as loaded; returns false if it was already loaded or processed.
+    fn load_child(&mut self, child: &Node) -> bool;
+
+    fn num_processed(&self) -> usize;
+}
+
+// dense: indexed by `Node::id`
 
 pub struct NodeStatusPar {
     loaded: Vec<AtomicBool>,
@@ -16,8 +44,10 @@ impl NodeStatusPar {
             processed: (0..len).map(|_| false.into()).collect(),
         }
     }
+}
 
-    pub fn start_processing(&self, node: &Node) -> bool {
+impl StatusPar for NodeStatusPar {
+    fn start_processing(&self, node: &Node) -> bool {
         match self.processed[node.id].compare_exchange(
             false,
             true,
@@ -29,11 +59,11 @@ impl NodeStatusPar {
         }
     }
 
-    pub fn load_child(&self, node: &Node) -> bool {
-        match self.processed[node.id].load(Ordering::Relaxed) {
+    fn load_child(&self, child: &Node) -> bool {
+        match self.processed[child.id].load(Ordering::Relaxed) {
             true => false,
             false => {
-                match self.loaded[node.id].compare_exchange(
+                match self.loaded[child.id].compare_exchange(
                     false,
                     true,
                     Ordering::AcqRel,
@@ -46,7 +76,7 @@ impl NodeStatusPar {
         }
     }
 
-    pub fn num_processed(&self) -> usize {
+    fn num_processed(&self) -> usize {
         self.processed
             .iter()
             .filter(|x| x.load(Ordering::Relaxed))
@@ -70,8 +100,10 @@ impl NodeStatusSeq {
             processed: (0..len).map(|_| false).collect(),
         }
     }
+}
 
-    pub fn start_processing(&mut self, node: &Node) -> bool {
+impl StatusSeq for NodeStatusSeq {
+    fn start_processing(&mut self, node: &Node) -> bool {
         let processed = self.processed.get_mut(node.id).unwrap();
         match *processed {
             true => false,
@@ -82,7 +114,7 @@ impl NodeStatusSeq {
         }
     }
 
-    pub fn load_child(&mut self, child: &Node) -> bool {
+    fn load_child(&mut self, child: &Node) -> bool {
         match self.processed[child.id] {
             true => false,
             false => {
@@ -98,7 +130,85 @@ impl NodeStatusSeq {
         }
     }
 
-    pub fn num_processed(&self) -> usize {
+    fn num_processed(&self) -> usize {
         self.processed.iter().filter(|x| **x).count()
     }
 }
+
+// sparse: hashed by an arbitrary key of the node
+
+pub struct NodeStatusSparsePar<K> {
+    key: fn(&Node) -> &K,
+    loaded: ShardedSet<K>,
+    processed: ShardedSet<K>,
+}
+
+impl<K: Hash + Eq + Clone> NodeStatusSparsePar<K> {
+    pub fn new(roots: &[&Node], key: fn(&Node) -> &K) -> Self {
+        let loaded = ShardedSet::default();
+        for root in roots {
+            loaded.insert(key(root));
+        }
+        Self {
+            key,
+            loaded,
+            processed: ShardedSet::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone + Send> StatusPar for NodeStatusSparsePar<K> {
+    fn start_processing(&self, node: &Node) -> bool {
+        self.processed.insert((self.key)(node))
+    }
+
+    fn load_child(&self, child: &Node) -> bool {
+        let key = (self.key)(child);
+        match self.processed.contains(key) {
+            true => false,
+            false => self.loaded.insert(key),
+        }
+    }
+
+    fn num_processed(&self) -> usize {
+        self.processed.len()
+    }
+}
+
+pub struct NodeStatusSparseSeq<K> {
+    key: fn(&Node) -> &K,
+    loaded: HashSet<K>,
+    processed: HashSet<K>,
+}
+
+impl<K: Hash + Eq + Clone> NodeStatusSparseSeq<K> {
+    pub fn new(roots: &[&Node], key: fn(&Node) -> &K) -> Self {
+        Self {
+            key,
+            loaded: roots.iter().map(|x| key(x).clone()).collect(),
+            processed: HashSet::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> StatusSeq for NodeStatusSparseSeq<K> {
+    fn start_processing(&mut self, node: &Node) -> bool {
+        let key = (self.key)(node);
+        match self.processed.contains(key) {
+            true => false,
+            false => self.processed.insert(key.clone()),
+        }
+    }
+
+    fn load_child(&mut self, child: &Node) -> bool {
+        let key = (self.key)(child);
+        match self.processed.contains(key) || self.loaded.contains(key) {
+            true => false,
+            false => self.loaded.insert(key.clone()),
+        }
+    }
+
+    fn num_processed(&self) -> usize {
+        self.processed.len()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::tree_with_on_the_fly_children::{
+    node::Node, node_storage::NodesStorage, sharded_set::ShardedSet,
+};
+use clap::ValueEnum;
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Loads the children of nodes while the computation is running.
+pub trait ChildLoader: Sync {
+    /// Loads the node defining the `symbol_out` from the `storage`; returns None if the load failed.
+    fn load<'a>(&self, storage: &'a NodesStorage, symbol_out: &str) -> Option<&'a Node>;
+
+    fn num_failures(&self) -> usize;
+
+    /// Number of distinct symbols whose load failed; the descendants reachable only through
+    /// these children are dropped from the computation as well, but are not counted.
+    fn num_dropped(&self) -> usize;
+}
+
+// simulated
+
+/// How the simulated loader waits for the latency to pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WaitMode {
+    /// Blocks the thread, as waiting for a store over the network does.
+    Sleep,
+    /// Busy-waits, keeping the thread occupied as CPU-bound loading does.
+    Spin,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoaderConfig {
+    pub latency: Duration,
+    /// Maximum deviation added to or subtracted from the `latency` of each load.
+    pub jitter: Duration,
+    pub wait_mode: WaitMode,
+    /// Probability in [0, 1] that a load fails.
+    pub failure_rate: f64,
+}
+
+/// Wraps the in-memory lookup with a configurable latency, jitter and failure rate.
+///
+/// Jitter and failures are derived from the hash of the loaded symbol rather than
+/// a shared rng; hence, every computation observes exactly the same loads regardless
+/// of the order in which threads perform them.
+pub struct SimulatedLoader {
+    config: LoaderConfig,
+    num_failures: AtomicUsize,
+    dropped: ShardedSet<String>,
+}
+
+impl SimulatedLoader {
+    pub fn new(config: LoaderConfig) -> Self {
+        Self {
+            config,
+            num_failures: 0.into(),
+            dropped: ShardedSet::default(),
+        }
+    }
+
+    fn wait(&self, symbol_out: &str) {
+        let jitter = self.config.jitter.as_nanos() as f64;
+        let deviation = (2.0 * unit_hash(symbol_out, 0) - 1.0) * jitter;
+        let nanos = (self.config.latency.as_nanos() as f64 + deviation).max(0.0);
+        let duration = Duration::from_nanos(nanos as u64);
+
+        match self.config.wait_mode {
+            WaitMode::Sleep => std::thread::sleep(duration),
+            WaitMode::Spin => {
+                let start = Instant::now();
+                while start.elapsed() < duration {
+                    core::hint::spin_loop();
+                }
+            }
+        }
+    }
+}
+
+impl ChildLoader for SimulatedLoader {
+    fn load<'a>(&self, storage: &'a NodesStorage, symbol_out: &str) -> Option<&'a Node> {
+        self.wait(symbol_out);
+        match unit_hash(symbol_out, 1) < self.config.failure_rate {
+            true => {
+                self.num_failures.fetch_add(1, Ordering::Relaxed);
+                self.dropped.insert(&symbol_out.to_string());
+                None
+            }
+            false => Some(storage.get_relevant_node(symbol_out)),
+        }
+    }
+
+    fn num_failures(&self) -> usize {
+        self.num_failures.load(Ordering::Relaxed)
+    }
+
+    fn num_dropped(&self) -> usize {
+        self.dropped.len()
+    }
+}
+
+/// Deterministic value in [0, 1) computed from the `symbol` and `salt`.
+fn unit_hash(symbol: &str, salt: u64) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    symbol.hash(&mut hasher);
+    salt.hash(&mut hasher);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -1,17 +1,56 @@
-use crate::tree_with_on_the_fly_children::node_storage::NodesStorage;
+use crate::{
+    dataset_args, graph_stats::GraphStats, loader_config, stats_enabled,
+    tree_with_on_the_fly_children::node_storage::NodesStorage,
+};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
+use std::io;
 
+mod computation_latency;
 mod computation_reduce;
+mod computation_reduce_sparse;
+mod computation_topological;
+pub mod dataset;
 mod load_status;
+pub mod loader;
 mod node;
 mod node_storage;
+mod sharded_set;
 
-pub fn run(seed: u64) {
+pub fn run(seed: u64) -> io::Result<()> {
     let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
-    let storage = NodesStorage::new(10_000, &mut rng);
+    let dataset = dataset_args()?;
+
+    let storage = match &dataset.load {
+        Some((path, format)) => NodesStorage::load(path, *format)?,
+        None => NodesStorage::new(10_000, &mut rng),
+    };
+    if let Some((path, format)) = &dataset.save {
+        storage.save(path, *format)?;
+    }
+
     let roots = storage.get_roots(20, &mut rng);
+    if roots.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the dataset has no nodes",
+        ));
+    }
+
+    if stats_enabled() {
+        let root_indices: Vec<_> = roots.iter().map(|x| x.id).collect();
+        let stats = GraphStats::new(&storage.adjacency()?, &root_indices, |i| {
+            storage.all_nodes[i].work_units()
+        });
+        println!("\n\n# INPUT STATS - TREE WITH ON THE FLY CHILDREN\n{stats}");
+    }
 
     computation_reduce::run_all(&storage, &roots);
+    computation_reduce_sparse::run_all(&storage, &roots);
+    if let Some(config) = loader_config() {
+        computation_latency::run_all(&storage, &roots, config);
+    }
+    computation_topological::run_all(&storage, &roots);
+    Ok(())
 }
@@ -1,14 +1,28 @@
 use crate::amount_of_work;
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 #[derive(Clone)]
 pub struct Node {
     pub id: usize,
+    /// Sparse 64-bit key of the node, as given by the dataset or else the hash of its name.
+    pub key: u64,
+    /// String key of the node, unique among the nodes.
+    pub name: String,
     pub symbols: Vec<String>,
     pub symbols_out: Vec<String>,
 }
 
 impl Node {
+    /// Sparse 64-bit key of the node with the given `name`.
+    pub fn key_of(name: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Fibonacci as example computation on each of the node values.
     pub fn compute(&self) -> u64 {
         (0..*amount_of_work())
@@ -25,12 +39,20 @@ impl Node {
             })
             .sum()
     }
+
+    /// Number of Fibonacci iterations performed by [`Node::compute`].
+    pub fn work_units(&self) -> u64 {
+        (0..*amount_of_work() as u64)
+            .map(|j| 40 + self.id as u64 + j)
+            .sum()
+    }
 }
 
 impl Debug for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Node")
             .field("id", &self.id)
+            .field("name", &self.name)
             .field("symbols", &format!("{:?}", &self.symbols).replace("\"", ""))
             .field(
                 "symbols_out",
@@ -1,5 +1,6 @@
 use crate::tree_with_on_the_fly_children::node::Node;
 use rand::Rng;
+use std::{collections::HashMap, io};
 
 const MAX_NUM_SYMBOLS: usize = 50;
 
@@ -11,10 +12,15 @@ pub struct NodesStorage {
 impl NodesStorage {
     pub fn new(len: usize, rng: &mut impl Rng) -> Self {
         let mut all_nodes: Vec<_> = (0..len)
-            .map(|id| Node {
-                id,
-                symbols: vec![],
-                symbols_out: vec![],
+            .map(|id| {
+                let name = format!("node-{id}");
+                Node {
+                    id,
+                    key: Node::key_of(&name),
+                    name,
+                    symbols: vec![],
+                    symbols_out: vec![],
+                }
             })
             .collect();
 
@@ -51,7 +57,48 @@ impl NodesStorage {
             .unwrap()
     }
 
+    /// Indices of the distinct nodes that each node depends on through its `symbols_out`; a
+    /// symbol defined by several nodes resolves to the first, as in `get_relevant_node`.
+    ///
+    /// Fails if a node depends on a symbol which no node defines.
+    pub fn adjacency(&self) -> io::Result<Vec<Vec<usize>>> {
+        let mut defined_by = HashMap::new();
+        for node in &self.all_nodes {
+            for s in &node.symbols {
+                defined_by.entry(s.as_str()).or_insert(node.id);
+            }
+        }
+
+        self.all_nodes
+            .iter()
+            .map(|node| {
+                let mut children = node
+                    .symbols_out
+                    .iter()
+                    .map(|s| {
+                        defined_by.get(s.as_str()).copied().ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!(
+                                    "symbol {s} used by node {} is not defined by any node",
+                                    node.name
+                                ),
+                            )
+                        })
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                children.sort();
+                children.dedup();
+                Ok(children)
+            })
+            .collect()
+    }
+
+    /// Randomly picks `number_of_roots` roots, or none if the storage is empty.
     pub fn get_roots(&self, number_of_roots: usize, rng: &mut impl Rng) -> Vec<&Node> {
+        if self.all_nodes.is_empty() {
+            return vec![];
+        }
         (0..number_of_roots)
             .map(|_| rng.random_range(0..self.all_nodes.len()))
             .map(|idx| &self.all_nodes[idx])
@@ -0,0 +1,58 @@
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash, RandomState},
+    sync::Mutex,
+};
+
+const DEFAULT_NUM_SHARDS: usize = 64;
+
+/// A concurrent hash set split into independently locked shards.
+///
+/// The shard of a key is determined by its hash; hence, threads working
+/// on different keys rarely contend for the same lock.
+pub struct ShardedSet<K> {
+    hasher: RandomState,
+    shards: Vec<Mutex<HashSet<K>>>,
+}
+
+impl<K> Default for ShardedSet<K> {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_SHARDS)
+    }
+}
+
+impl<K> ShardedSet<K> {
+    pub fn new(num_shards: usize) -> Self {
+        assert!(num_shards > 0, "there must be at least one shard");
+        Self {
+            hasher: RandomState::new(),
+            shards: (0..num_shards)
+                .map(|_| Mutex::new(HashSet::new()))
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|x| x.lock().unwrap().len()).sum()
+    }
+}
+
+impl<K: Hash + Eq + Clone> ShardedSet<K> {
+    fn shard(&self, key: &K) -> &Mutex<HashSet<K>> {
+        let idx = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[idx]
+    }
+
+    /// Inserts the `key` and returns true if it was not already present.
+    pub fn insert(&self, key: &K) -> bool {
+        let mut shard = self.shard(key).lock().unwrap();
+        match shard.contains(key) {
+            true => false,
+            false => shard.insert(key.clone()),
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.shard(key).lock().unwrap().contains(key)
+    }
+}